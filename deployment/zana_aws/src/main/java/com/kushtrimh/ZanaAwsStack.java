@@ -220,6 +220,8 @@ public class ZanaAwsStack extends Stack {
 
         var booksResource = restApi.getRoot().addResource("books");
         booksResource.addMethod("GET", booksDataLambdaIntegration);
+        var editionsResource = booksResource.addResource("editions");
+        editionsResource.addMethod("GET", booksDataLambdaIntegration);
 
         // Create account for API Gateway CloudWatch logs
         var apiGatewayCloudWatchRole = Role.Builder.create(this, "zana-api-gateway-cloudwatch-role")
@@ -249,6 +249,37 @@ public class ZanaAwsTest {
                         "Uri", Match.anyValue())));
     }
 
+    @Test
+    public void restApi_RestApiGatewayEditionsMethodIsConfiguredProperly() {
+        var restApiGatewayId = getRestApiGatewayId();
+
+        var restApiResource = template.findResources("AWS::ApiGateway::Resource", Map.of(
+                "Properties", Match.objectLike(getRestApiGatewayResourceBaseProps())));
+        var restApiResourceId = restApiResource.keySet().iterator().next();
+
+        var editionsResource = template.findResources("AWS::ApiGateway::Resource", Map.of(
+                "Properties", Map.of(
+                        "ParentId", Map.of(
+                                "Ref", restApiResourceId),
+                        "PathPart", "editions",
+                        "RestApiId", Map.of(
+                                "Ref", restApiGatewayId))));
+        var editionsResourceId = editionsResource.keySet().iterator().next();
+
+        template.hasResourceProperties("AWS::ApiGateway::Method", Map.of(
+                "HttpMethod", "GET",
+                "ResourceId", Map.of(
+                        "Ref", editionsResourceId),
+                "RestApiId", Map.of(
+                        "Ref", restApiGatewayId),
+                "AuthorizationType", "NONE",
+                "Integration", Map.of(
+                        "IntegrationHttpMethod", "POST",
+                        "TimeoutInMillis", 29000,
+                        "Type", "AWS_PROXY",
+                        "Uri", Match.anyValue())));
+    }
+
     @Test
     public void restApi_RestApiGatewayCloudWatchRoleIsConfiguredProperly() {
         template.hasResourceProperties("AWS::IAM::Role", getRestApiGatewayCloudWatchRoleBaseProps());
@@ -0,0 +1,428 @@
+/*!
+Caches books returned by any [`BookClient`](trait@BookClient) using the
+[`CachingClient`](struct@CachingClient) wrapper.
+
+By default books are kept in memory by [`MemoryStore`](struct@MemoryStore), in a cache of
+limited size that evicts the least recently used books first. Other stores can be used by
+implementing [`CacheStore`](trait@CacheStore), such as `DiskStore` which keeps books in a file
+between runs, and is available with the `disk-cache` feature.
+
+Books that are not found are cached as well, usually for a shorter time, so that
+repeated lookups of missing books do not reach the external service either.
+Other errors are never cached.
+
+The client can be shared between tasks, e.g. with an [`Arc`](std::sync::Arc), so that all of
+them use the same cache.
+
+## Example
+
+```
+use std::sync::Arc;
+use zana::cache::{CachePolicy, CachingClient};
+use zana::openlibrary::Client;
+
+# fn main() -> Result<(), zana::ClientError> {
+let client = Arc::new(CachingClient::new(
+    Client::new("https://openlibrary.org")?,
+    CachePolicy::default(),
+));
+# Ok(())
+# }
+```
+ */
+use std::collections::HashMap;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use lru::LruCache;
+
+use crate::isbn::Isbn;
+use crate::{Book, BookClient, Capabilities, ClientError, IsbnResult};
+
+#[cfg(feature = "disk-cache")]
+mod disk;
+
+#[cfg(feature = "disk-cache")]
+pub use disk::{DiskStore, DiskStoreError, DEFAULT_DISK_CAPACITY};
+
+/// Provider used in cache keys of clients created with [`CachingClient::new`](CachingClient::new).
+const DEFAULT_PROVIDER: &str = "default";
+
+/// Configuration of how books are cached by [`CachingClient`](struct@CachingClient).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// Maximum number of cached results, including books that were not found.
+    /// Only used by [`CachingClient::new`](CachingClient::new) to create its
+    /// [`MemoryStore`](struct@MemoryStore).
+    pub capacity: NonZeroUsize,
+    /// How long a returned book is kept in the cache
+    pub ttl: Duration,
+    /// How long a [`NotFound`](ClientError::NotFound) result is kept in the cache.
+    /// [`None`](None) disables caching of books that are not found.
+    pub not_found_ttl: Option<Duration>,
+}
+
+impl Default for CachePolicy {
+    /// Returns a policy that caches up to 1000 results, books for 1 hour,
+    /// and books that are not found for 5 minutes.
+    fn default() -> Self {
+        Self {
+            capacity: NonZeroUsize::new(1000).expect("capacity is not zero"),
+            ttl: Duration::from_secs(60 * 60),
+            not_found_ttl: Some(Duration::from_secs(5 * 60)),
+        }
+    }
+}
+
+/// Statistics of a [`CachingClient`](struct@CachingClient), counted since it was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of requests answered from the cache, including books that were not found
+    pub hits: u64,
+    /// Number of requests sent to the wrapped client
+    pub misses: u64,
+    /// Number of requests answered from the cache with a [`NotFound`](ClientError::NotFound) error
+    pub not_found_hits: u64,
+    /// Number of results that were removed from the cache to make room for newer ones
+    pub evictions: u64,
+    /// Number of results in the cache, including expired ones that were not removed yet
+    pub entries: usize,
+}
+
+/// Query that a result is cached for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CacheQuery {
+    Isbn(Isbn),
+    /// Author and title, in lowercase and without surrounding whitespace
+    Book {
+        author: String,
+        title: String,
+    },
+}
+
+impl CacheQuery {
+    /// Returns a query for the given author and title, ignoring case and surrounding whitespace.
+    pub fn book(author: &str, title: &str) -> Self {
+        CacheQuery::Book {
+            author: author.trim().to_lowercase(),
+            title: title.trim().to_lowercase(),
+        }
+    }
+}
+
+/// Key of a cached result, made of the provider and the query.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// Name of the provider that returned the result, e.g. `googlebooks`
+    pub provider: String,
+    pub query: CacheQuery,
+}
+
+/// A cached result, kept until it expires.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheEntry {
+    /// Returned book, or [`None`](None) if the book was not found
+    pub book: Option<Book>,
+    pub expires_at: SystemTime,
+}
+
+impl CacheEntry {
+    /// Returns `true` if the entry has expired at the given time.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// Storage of the results cached by [`CachingClient`](struct@CachingClient).
+///
+/// Stores are not expected to remove expired entries, since expiry is checked by the client.
+pub trait CacheStore: Send + Sync {
+    /// Returns the entry stored for the key.
+    fn get(&self, key: &CacheKey) -> Option<CacheEntry>;
+
+    /// Stores the entry for the key, replacing any existing entry.
+    /// Returns `true` if another entry was evicted to make room for it.
+    fn put(&self, key: CacheKey, entry: CacheEntry) -> bool;
+
+    /// Removes the entry stored for the key.
+    fn remove(&self, key: &CacheKey);
+
+    /// Returns the number of stored entries.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no stored entries.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all stored entries.
+    fn clear(&self);
+}
+
+impl<S: CacheStore + ?Sized> CacheStore for Arc<S> {
+    fn get(&self, key: &CacheKey) -> Option<CacheEntry> {
+        (**self).get(key)
+    }
+
+    fn put(&self, key: CacheKey, entry: CacheEntry) -> bool {
+        (**self).put(key, entry)
+    }
+
+    fn remove(&self, key: &CacheKey) {
+        (**self).remove(key)
+    }
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn clear(&self) {
+        (**self).clear()
+    }
+}
+
+/// A [`CacheStore`](trait@CacheStore) that keeps entries in memory, and evicts the least
+/// recently used entry when it is full.
+pub struct MemoryStore {
+    cache: Mutex<LruCache<CacheKey, CacheEntry>>,
+}
+
+impl MemoryStore {
+    /// Returns a new store that keeps up to `capacity` entries.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LruCache<CacheKey, CacheEntry>> {
+        // The cache is left consistent by every operation, so it is still usable after a panic
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get(&self, key: &CacheKey) -> Option<CacheEntry> {
+        self.lock().get(key).cloned()
+    }
+
+    fn put(&self, key: CacheKey, entry: CacheEntry) -> bool {
+        match self.lock().push(key.clone(), entry) {
+            Some((evicted_key, _)) => evicted_key != key,
+            None => false,
+        }
+    }
+
+    fn remove(&self, key: &CacheKey) {
+        self.lock().pop(key);
+    }
+
+    fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+/// A [`BookClient`](trait@BookClient) that caches books returned by the wrapped client.
+pub struct CachingClient<C, S = MemoryStore> {
+    client: C,
+    provider: String,
+    store: S,
+    policy: CachePolicy,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    not_found_hits: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl<C: BookClient> CachingClient<C> {
+    /// Returns a new client that caches books of the given client in memory,
+    /// based on the policy.
+    pub fn new(client: C, policy: CachePolicy) -> Self {
+        let store = MemoryStore::new(policy.capacity);
+        Self::with_store(client, DEFAULT_PROVIDER, store, policy)
+    }
+}
+
+impl<C: BookClient, S: CacheStore> CachingClient<C, S> {
+    /// Returns a new client that caches books of the given client in the store,
+    /// based on the policy.
+    ///
+    /// Results are stored with the provider as part of their key, so that a store can be
+    /// shared by clients of different providers.
+    pub fn with_store(client: C, provider: &str, store: S, policy: CachePolicy) -> Self {
+        Self {
+            client,
+            provider: String::from(provider),
+            store,
+            policy,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            not_found_hits: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns a reference to the wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.client
+    }
+
+    /// Returns a reference to the store of the cached results.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the statistics of the cache.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            not_found_hits: self.not_found_hits.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries: self.store.len(),
+        }
+    }
+
+    /// Removes all results from the cache.
+    pub fn clear(&self) {
+        self.store.clear();
+    }
+
+    fn key(&self, query: CacheQuery) -> CacheKey {
+        CacheKey {
+            provider: self.provider.clone(),
+            query,
+        }
+    }
+
+    /// Returns the cached result for the key, removing it if it has expired.
+    fn get(&self, key: &CacheKey) -> Option<Result<Book, ClientError>> {
+        let entry = self.store.get(key)?;
+        if entry.is_expired(SystemTime::now()) {
+            self.store.remove(key);
+            return None;
+        }
+        match entry.book {
+            Some(book) => Some(Ok(book)),
+            None => {
+                self.not_found_hits.fetch_add(1, Ordering::Relaxed);
+                Some(Err(ClientError::NotFound))
+            }
+        }
+    }
+
+    fn put(&self, key: CacheKey, result: &Result<Book, ClientError>) {
+        let (book, ttl) = match result {
+            Ok(book) => (Some(book.clone()), self.policy.ttl),
+            Err(ClientError::NotFound) => match self.policy.not_found_ttl {
+                Some(not_found_ttl) => (None, not_found_ttl),
+                None => return,
+            },
+            Err(_) => return,
+        };
+        let entry = CacheEntry {
+            book,
+            expires_at: SystemTime::now() + ttl,
+        };
+        if self.store.put(key, entry) {
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    async fn cached<F, Fut>(&self, query: CacheQuery, request: F) -> Result<Book, ClientError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Book, ClientError>>,
+    {
+        let key = self.key(query);
+        if let Some(result) = self.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return result;
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let result = request().await;
+        self.put(key, &result);
+        result
+    }
+}
+
+#[async_trait]
+impl<C: BookClient + Send + Sync, S: CacheStore> BookClient for CachingClient<C, S> {
+    /// Returns a book by ISBN from the cache, or from the wrapped client if it is not cached.
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.cached(CacheQuery::Isbn(isbn.clone()), || {
+            self.client.book_by_isbn(isbn)
+        })
+        .await
+    }
+
+    /// Returns a book by author and title from the cache, or from the wrapped client if it is
+    /// not cached. Author and title are matched ignoring case and surrounding whitespace.
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        self.cached(CacheQuery::book(author, title), || {
+            self.client.book(author, title)
+        })
+        .await
+    }
+
+    /// Returns a result for each of the ISBNs from the cache, and from the wrapped client for
+    /// the ISBNs that are not cached.
+    ///
+    /// The ISBNs that are not cached are sent to the wrapped client as a single request.
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        let mut results = Vec::with_capacity(isbns.len());
+        let mut uncached = Vec::new();
+        for isbn in isbns {
+            let cached = self.get(&self.key(CacheQuery::Isbn(isbn.clone())));
+            if cached.is_some() {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+            } else {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                uncached.push(isbn.clone());
+            }
+            results.push(cached.map(|result| IsbnResult::new(isbn, result)));
+        }
+        if uncached.is_empty() {
+            return results.into_iter().flatten().collect();
+        }
+
+        let fetched: HashMap<Isbn, Result<Book, ClientError>> = self
+            .client
+            .books_by_isbns(&uncached)
+            .await
+            .into_iter()
+            .map(|fetched| (fetched.isbn, fetched.result))
+            .collect();
+        let mut books = Vec::with_capacity(isbns.len());
+        for (cached, isbn) in results.into_iter().zip(isbns) {
+            if let Some(cached) = cached {
+                books.push(cached);
+                continue;
+            }
+            // A book missing from the results of the wrapped client was not looked up, so it is
+            // looked up on its own instead of being cached as not found
+            let result = match fetched.get(isbn) {
+                Some(result) => result.clone(),
+                None => self.client.book_by_isbn(isbn).await,
+            };
+            self.put(self.key(CacheQuery::Isbn(isbn.clone())), &result);
+            books.push(IsbnResult::new(isbn, result));
+        }
+        books
+    }
+
+    /// Returns the capabilities of the wrapped client.
+    fn capabilities(&self) -> Capabilities {
+        self.client.capabilities()
+    }
+}
@@ -0,0 +1,372 @@
+/*!
+File-backed [`CacheStore`](trait@CacheStore) that keeps cached results between runs.
+
+Entries are appended to the file as JSON lines, one entry per line, and are all loaded in memory
+when the store is opened. Every entry has a format version, and entries of versions that are not
+supported are skipped when loading, as are lines that cannot be read, such as a line that was
+partially written when a process exited.
+
+Since entries are only appended, replaced and expired entries stay in the file until it is
+compacted. The file is compacted when it is opened, and when it has grown to twice the number
+of stored entries.
+
+The store keeps a limited number of entries. When it is full, expired entries are removed to
+make room for a new one, followed by the entries that expire first.
+ */
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cache::{CacheEntry, CacheKey, CacheQuery, CacheStore};
+use crate::isbn::Isbn;
+use crate::Book;
+
+/// Version of the entry format written by the store.
+const ENTRY_VERSION: u32 = 1;
+/// Minimum number of lines in the file before it is compacted automatically.
+const MIN_COMPACTION_LINES: usize = 1000;
+/// Maximum number of entries of stores opened with [`DiskStore::open`](DiskStore::open).
+pub const DEFAULT_DISK_CAPACITY: usize = 10_000;
+
+/// An error that occurs when a [`DiskStore`](struct@DiskStore) cannot be opened or compacted.
+#[derive(Error, Debug)]
+pub enum DiskStoreError {
+    #[error("cache file error: {0}")]
+    Io(#[from] io::Error),
+    #[error("could not serialize cache entry: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// Entry as it is written in the file.
+#[derive(Serialize, Deserialize)]
+struct Record {
+    version: u32,
+    provider: String,
+    query: RecordQuery,
+    /// Milliseconds since the Unix epoch
+    expires_at: u64,
+    book: Option<Book>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordQuery {
+    Isbn(Isbn),
+    Book { author: String, title: String },
+}
+
+/// Only the version of an entry, read before the rest of the entry.
+#[derive(Deserialize)]
+struct RecordVersion {
+    version: u32,
+}
+
+impl Record {
+    fn new(key: &CacheKey, entry: &CacheEntry) -> Self {
+        let query = match &key.query {
+            CacheQuery::Isbn(isbn) => RecordQuery::Isbn(isbn.clone()),
+            CacheQuery::Book { author, title } => RecordQuery::Book {
+                author: author.clone(),
+                title: title.clone(),
+            },
+        };
+        let expires_at = entry
+            .expires_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        Self {
+            version: ENTRY_VERSION,
+            provider: key.provider.clone(),
+            query,
+            expires_at: u64::try_from(expires_at).unwrap_or(u64::MAX),
+            book: entry.book.clone(),
+        }
+    }
+
+    fn into_entry(self) -> (CacheKey, CacheEntry) {
+        let query = match self.query {
+            RecordQuery::Isbn(isbn) => CacheQuery::Isbn(isbn),
+            RecordQuery::Book { author, title } => CacheQuery::Book { author, title },
+        };
+        let key = CacheKey {
+            provider: self.provider,
+            query,
+        };
+        let entry = CacheEntry {
+            book: self.book,
+            expires_at: UNIX_EPOCH + Duration::from_millis(self.expires_at),
+        };
+        (key, entry)
+    }
+}
+
+struct DiskState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    writer: BufWriter<File>,
+    /// Number of lines in the file, including replaced and expired entries
+    lines: usize,
+}
+
+/// A [`CacheStore`](trait@CacheStore) that keeps up to a maximum number of entries in a file.
+///
+/// The store can be shared between clients of different providers, e.g. with an
+/// [`Arc`](std::sync::Arc), since entries are keyed by provider.
+/// Errors when writing to the file are logged, and the entry is kept only in memory.
+pub struct DiskStore {
+    path: PathBuf,
+    capacity: NonZeroUsize,
+    state: Mutex<DiskState>,
+}
+
+impl DiskStore {
+    /// Opens the store kept in the file at the given path, creating the file if it does not exist.
+    /// The store keeps up to [`DEFAULT_DISK_CAPACITY`](DEFAULT_DISK_CAPACITY) entries.
+    ///
+    /// Expired entries are removed from the file when it is opened.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DiskStoreError> {
+        let capacity = NonZeroUsize::new(DEFAULT_DISK_CAPACITY).expect("capacity is not zero");
+        Self::open_with_capacity(path, capacity)
+    }
+
+    /// Opens the store kept in the file at the given path, creating the file if it does not exist,
+    /// that keeps up to `capacity` entries.
+    ///
+    /// Expired entries are removed from the file when it is opened, as are the entries that
+    /// expire first if the file has more entries than the capacity.
+    pub fn open_with_capacity<P: AsRef<Path>>(
+        path: P,
+        capacity: NonZeroUsize,
+    ) -> Result<Self, DiskStoreError> {
+        let path = path.as_ref().to_path_buf();
+        let mut entries = match File::open(&path) {
+            Ok(file) => read_entries(file)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+        evict_entries(&mut entries, capacity.get());
+
+        let (writer, lines) = write_entries(&path, &entries)?;
+        Ok(Self {
+            path,
+            capacity,
+            state: Mutex::new(DiskState {
+                entries,
+                writer,
+                lines,
+            }),
+        })
+    }
+
+    /// Returns the path of the file that the store is kept in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the maximum number of entries kept by the store.
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    /// Rewrites the file with only the current entries, removing replaced and expired ones.
+    pub fn compact(&self) -> Result<(), DiskStoreError> {
+        let mut state = self.lock();
+        self.compact_state(&mut state)
+    }
+
+    fn compact_state(&self, state: &mut DiskState) -> Result<(), DiskStoreError> {
+        let now = SystemTime::now();
+        state.entries.retain(|_, entry| !entry.is_expired(now));
+        let (writer, lines) = write_entries(&self.path, &state.entries)?;
+        state.writer = writer;
+        state.lines = lines;
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, DiskState> {
+        // Entries in memory are left consistent by every operation, so they are still usable
+        // after a panic
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Removes entries until there is room for a new one, expired entries first, followed by
+    /// the entries that expire first. Returns `true` if an entry that had not expired was
+    /// evicted.
+    fn make_room(&self, state: &mut DiskState) -> bool {
+        if state.entries.len() < self.capacity.get() {
+            return false;
+        }
+        let now = SystemTime::now();
+        // Expired entries are ignored when the file is loaded, so their removal is not written
+        state.entries.retain(|_, entry| !entry.is_expired(now));
+        let evicted = evict_entries(&mut state.entries, self.capacity.get() - 1);
+        for key in &evicted {
+            self.append(state, key, &removed_entry());
+        }
+        !evicted.is_empty()
+    }
+
+    fn append(&self, state: &mut DiskState, key: &CacheKey, entry: &CacheEntry) {
+        if let Err(err) = append_record(&mut state.writer, &Record::new(key, entry)) {
+            log::warn!("could not write cache entry to {:?}: {}", self.path, err);
+            return;
+        }
+        state.lines += 1;
+        if state.lines >= MIN_COMPACTION_LINES && state.lines > state.entries.len() * 2 {
+            if let Err(err) = self.compact_state(state) {
+                log::warn!("could not compact cache file {:?}: {}", self.path, err);
+            }
+        }
+    }
+}
+
+impl CacheStore for DiskStore {
+    fn get(&self, key: &CacheKey) -> Option<CacheEntry> {
+        self.lock().entries.get(key).cloned()
+    }
+
+    fn put(&self, key: CacheKey, entry: CacheEntry) -> bool {
+        let mut state = self.lock();
+        let evicted = !state.entries.contains_key(&key) && self.make_room(&mut state);
+        // Entries are updated first, so that a compaction after the append keeps the entry
+        state.entries.insert(key.clone(), entry.clone());
+        self.append(&mut state, &key, &entry);
+        evicted
+    }
+
+    fn remove(&self, key: &CacheKey) {
+        let mut state = self.lock();
+        if state.entries.remove(key).is_some() {
+            self.append(&mut state, key, &removed_entry());
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    fn clear(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        if let Err(err) = self.compact_state(&mut state) {
+            log::warn!("could not clear cache file {:?}: {}", self.path, err);
+        }
+    }
+}
+
+/// Returns an entry that removes the entry of its key, since an expired entry is ignored when
+/// the file is loaded.
+fn removed_entry() -> CacheEntry {
+    CacheEntry {
+        book: None,
+        expires_at: UNIX_EPOCH,
+    }
+}
+
+/// Removes the entries that expire first until at most `max_entries` are left,
+/// and returns their keys.
+fn evict_entries(entries: &mut HashMap<CacheKey, CacheEntry>, max_entries: usize) -> Vec<CacheKey> {
+    if entries.len() <= max_entries {
+        return Vec::new();
+    }
+    let mut by_expiry: Vec<(&CacheKey, SystemTime)> = entries
+        .iter()
+        .map(|(key, entry)| (key, entry.expires_at))
+        .collect();
+    by_expiry.sort_unstable_by_key(|(_, expires_at)| *expires_at);
+    let evicted: Vec<CacheKey> = by_expiry[..entries.len() - max_entries]
+        .iter()
+        .map(|(key, _)| (*key).clone())
+        .collect();
+    for key in &evicted {
+        entries.remove(key);
+    }
+    evicted
+}
+
+/// Reads the entries of a file, keeping the last entry of each key if it has not expired.
+fn read_entries(file: File) -> Result<HashMap<CacheKey, CacheEntry>, DiskStoreError> {
+    let now = SystemTime::now();
+    let mut entries = HashMap::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<RecordVersion>(&line) {
+            Ok(record) if record.version == ENTRY_VERSION => {}
+            Ok(record) => {
+                log::debug!(
+                    "skipping cache entry with unsupported version {} on line {}",
+                    record.version,
+                    index + 1
+                );
+                continue;
+            }
+            Err(err) => {
+                log::warn!(
+                    "skipping invalid cache entry on line {}: {}",
+                    index + 1,
+                    err
+                );
+                continue;
+            }
+        }
+        match serde_json::from_str::<Record>(&line) {
+            Ok(record) => {
+                let (key, entry) = record.into_entry();
+                if entry.is_expired(now) {
+                    entries.remove(&key);
+                } else {
+                    entries.insert(key, entry);
+                }
+            }
+            Err(err) => log::warn!(
+                "skipping invalid cache entry on line {}: {}",
+                index + 1,
+                err
+            ),
+        }
+    }
+    Ok(entries)
+}
+
+/// Replaces the file with the given entries, and returns a writer that appends to it.
+fn write_entries(
+    path: &Path,
+    entries: &HashMap<CacheKey, CacheEntry>,
+) -> Result<(BufWriter<File>, usize), DiskStoreError> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut writer = BufWriter::new(File::create(&temp_path)?);
+    for (key, entry) in entries {
+        serde_json::to_writer(&mut writer, &Record::new(key, entry))?;
+        writer.write_all(b"\n")?;
+    }
+    writer
+        .into_inner()
+        .map_err(|err| err.into_error())?
+        .sync_all()?;
+    fs::rename(&temp_path, path)?;
+
+    let file = OpenOptions::new().append(true).open(path)?;
+    Ok((BufWriter::new(file), entries.len()))
+}
+
+fn append_record(writer: &mut BufWriter<File>, record: &Record) -> Result<(), DiskStoreError> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,274 @@
+/*!
+Stops sending requests to a failing external service using the
+[`CircuitBreaker`](struct@CircuitBreaker) wrapper of any [`BookClient`](trait@BookClient).
+
+The circuit starts closed, and requests are sent to the wrapped client. After a number of
+consecutive failures the circuit opens, and requests fail right away with
+[`CircuitOpen`](crate::ClientError::CircuitOpen) until the cool-down passes. The circuit is then
+half-open, and a single request is sent to check if the service has recovered. The circuit closes
+once enough requests succeed, or opens again if the request fails.
+
+Failures are errors that are [transient](crate::ClientError::is_transient), such as timeouts and
+exceeded rate limits, and [`Unauthorized`](crate::ClientError::Unauthorized) errors.
+Books that are not found and invalid input are not failures of the service.
+
+## Example
+
+```
+use zana::circuit::{CircuitBreaker, CircuitPolicy};
+use zana::openlibrary::Client;
+
+# fn main() -> Result<(), zana::ClientError> {
+let client = CircuitBreaker::new(Client::new("https://openlibrary.org")?, CircuitPolicy::default());
+# Ok(())
+# }
+```
+ */
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::isbn::Isbn;
+use crate::{Book, BookClient, Capabilities, ClientError, IsbnResult};
+
+/// Shortest wait returned to requests rejected while a request checks if the service has
+/// recovered, since that request may finish at any time.
+const MIN_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Configuration of when a [`CircuitBreaker`](struct@CircuitBreaker) opens and closes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitPolicy {
+    /// Number of consecutive failures that opens the circuit
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a request is sent again
+    pub cool_down: Duration,
+    /// Number of consecutive successful requests in the half-open state that closes the circuit
+    pub success_threshold: u32,
+}
+
+impl Default for CircuitPolicy {
+    /// Returns a policy that opens the circuit after 5 consecutive failures for 30s,
+    /// and closes it after 1 successful request.
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cool_down: Duration::from_secs(30),
+            success_threshold: 1,
+        }
+    }
+}
+
+/// State of a [`CircuitBreaker`](struct@CircuitBreaker).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent to the wrapped client
+    Closed,
+    /// Requests are rejected without being sent
+    Open,
+    /// Requests are sent one at a time, to check if the external service has recovered
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum State {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    HalfOpen {
+        successes: u32,
+        /// Start of the request that is in flight, if any
+        probe_started: Option<Instant>,
+    },
+}
+
+/// A [`BookClient`](trait@BookClient) that rejects requests while the wrapped client keeps failing.
+pub struct CircuitBreaker<C> {
+    client: C,
+    policy: CircuitPolicy,
+    state: Arc<Mutex<State>>,
+}
+
+impl<C: BookClient> CircuitBreaker<C> {
+    /// Returns a new client, with a closed circuit, that sends requests to the given client
+    /// based on the policy.
+    pub fn new(client: C, policy: CircuitPolicy) -> Self {
+        Self {
+            client,
+            policy,
+            state: Arc::new(Mutex::new(State::Closed { failures: 0 })),
+        }
+    }
+
+    /// Returns a circuit breaker of the given client that shares the circuit and the policy
+    /// of this one, e.g. for clients that query the same service with different options.
+    ///
+    /// Failures of any of the clients count towards opening the circuit, and once it is open,
+    /// requests of all of them are rejected.
+    pub fn shared<D: BookClient>(&self, client: D) -> CircuitBreaker<D> {
+        CircuitBreaker {
+            client,
+            policy: self.policy.clone(),
+            state: Arc::clone(&self.state),
+        }
+    }
+
+    /// Returns a reference to the wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.client
+    }
+
+    /// Returns the current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        match *self.lock() {
+            State::Closed { .. } => CircuitState::Closed,
+            State::Open { until } if until > Instant::now() => CircuitState::Open,
+            State::Open { .. } | State::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The state is replaced as a whole, so it is still usable after a panic
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns an error if the request is not allowed in the current state.
+    fn acquire(&self) -> Result<(), ClientError> {
+        let now = Instant::now();
+        let mut state = self.lock();
+        match *state {
+            State::Closed { .. } => Ok(()),
+            State::Open { until } if until > now => Err(ClientError::CircuitOpen(until - now)),
+            State::Open { .. } => {
+                log::debug!("circuit is half-open, sending a request to check the service");
+                *state = State::HalfOpen {
+                    successes: 0,
+                    probe_started: Some(now),
+                };
+                Ok(())
+            }
+            State::HalfOpen {
+                successes,
+                probe_started,
+            } => match probe_started {
+                // A request that was cancelled never reports its result, so another request
+                // is allowed once the cool-down has passed
+                Some(started) if now.duration_since(started) < self.policy.cool_down => {
+                    let remaining = self.policy.cool_down - now.duration_since(started);
+                    Err(ClientError::CircuitOpen(remaining.max(MIN_RETRY_AFTER)))
+                }
+                _ => {
+                    *state = State::HalfOpen {
+                        successes,
+                        probe_started: Some(now),
+                    };
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    /// Records the outcome of a request, which failed if the given error is a failure.
+    fn record(&self, error: Option<&ClientError>) {
+        let failed = error.is_some_and(is_failure);
+        let mut state = self.lock();
+        let next = match (&*state, failed) {
+            (State::Closed { .. }, false) => State::Closed { failures: 0 },
+            (State::Closed { failures }, true) if failures + 1 < self.policy.failure_threshold => {
+                State::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (State::HalfOpen { successes, .. }, false)
+                if successes + 1 < self.policy.success_threshold =>
+            {
+                State::HalfOpen {
+                    successes: successes + 1,
+                    probe_started: None,
+                }
+            }
+            (State::HalfOpen { .. }, false) => {
+                log::info!("circuit closed after the service recovered");
+                State::Closed { failures: 0 }
+            }
+            // The circuit was opened by another request in the meantime
+            (State::Open { until }, _) => State::Open { until: *until },
+            (State::Closed { .. } | State::HalfOpen { .. }, true) => {
+                log::warn!(
+                    "circuit opened for {:?} after request failed: {:?}",
+                    self.policy.cool_down,
+                    error
+                );
+                State::Open {
+                    until: Instant::now() + self.policy.cool_down,
+                }
+            }
+        };
+        *state = next;
+    }
+
+    async fn call<F, Fut>(&self, request: F) -> Result<Book, ClientError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Book, ClientError>>,
+    {
+        self.acquire()?;
+        let result = request().await;
+        self.record(result.as_ref().err());
+        result
+    }
+}
+
+/// Returns true if the error shows that the external service cannot be used at the moment.
+fn is_failure(err: &ClientError) -> bool {
+    err.is_transient() || matches!(err, ClientError::Unauthorized(_, _))
+}
+
+#[async_trait]
+impl<C: BookClient + Send + Sync> BookClient for CircuitBreaker<C> {
+    /// Returns a book by ISBN from the wrapped client, or an error if the circuit is open.
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.call(|| self.client.book_by_isbn(isbn)).await
+    }
+
+    /// Returns a book by author and title from the wrapped client, or an error if the circuit
+    /// is open.
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        self.call(|| self.client.book(author, title)).await
+    }
+
+    /// Returns a result for each of the ISBNs from the wrapped client, or an error for each
+    /// of them if the circuit is open.
+    ///
+    /// The ISBNs are sent to the wrapped client as a single request, which fails if the result
+    /// of any of the ISBNs is a failure.
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        if isbns.is_empty() {
+            return Vec::new();
+        }
+        if let Err(err) = self.acquire() {
+            return isbns
+                .iter()
+                .map(|isbn| IsbnResult::new(isbn, Err(err.clone())))
+                .collect();
+        }
+        let results = self.client.books_by_isbns(isbns).await;
+        let failure = results
+            .iter()
+            .filter_map(|result| result.result.as_ref().err())
+            .find(|err| is_failure(err));
+        self.record(failure);
+        results
+    }
+
+    /// Returns the capabilities of the wrapped client.
+    fn capabilities(&self) -> Capabilities {
+        self.client.capabilities()
+    }
+}
@@ -0,0 +1,127 @@
+/*!
+Shares a single request between concurrent identical lookups of any
+[`BookClient`](trait@BookClient) using the [`CoalescingClient`](struct@CoalescingClient) wrapper.
+
+When a lookup arrives while an identical lookup is in flight, it waits for the result of that
+lookup instead of making its own request, and every caller gets a clone of the result.
+Lookups are identical when they are for the same ISBN, or for the same author and title,
+ignoring case and surrounding whitespace.
+
+Results are not kept once the request completes, so the client can be combined with a
+[`CachingClient`](crate::cache::CachingClient) to also reuse results of earlier lookups.
+
+If the caller whose request is in flight is cancelled, one of the waiting callers makes the
+request instead.
+
+## Example
+
+```
+use std::sync::Arc;
+use zana::coalesce::CoalescingClient;
+use zana::openlibrary::Client;
+
+# fn main() -> Result<(), zana::ClientError> {
+let client = Arc::new(CoalescingClient::new(Client::new("https://openlibrary.org")?));
+# Ok(())
+# }
+```
+ */
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use tokio::sync::OnceCell;
+
+use crate::cache::CacheQuery;
+use crate::isbn::Isbn;
+use crate::{Book, BookClient, Capabilities, ClientError, IsbnResult};
+
+type SharedResult = Arc<OnceCell<Result<Book, ClientError>>>;
+
+/// A [`BookClient`](trait@BookClient) that shares in-flight requests of the wrapped client
+/// between identical lookups.
+pub struct CoalescingClient<C> {
+    client: C,
+    in_flight: Mutex<HashMap<CacheQuery, SharedResult>>,
+}
+
+impl<C: BookClient> CoalescingClient<C> {
+    /// Returns a new client that shares in-flight requests of the given client.
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns a reference to the wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.client
+    }
+
+    /// Returns the number of lookups with a request in flight.
+    pub fn in_flight(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<CacheQuery, SharedResult>> {
+        // The map is left consistent by every operation, so it is still usable after a panic
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    async fn coalesce<F, Fut>(&self, query: CacheQuery, request: F) -> Result<Book, ClientError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Book, ClientError>>,
+    {
+        let shared = Arc::clone(self.lock().entry(query.clone()).or_default());
+        let result = shared.get_or_init(request).await.clone();
+
+        let mut in_flight = self.lock();
+        // A later lookup may have started a new request for the same query in the meantime
+        if in_flight
+            .get(&query)
+            .is_some_and(|current| Arc::ptr_eq(current, &shared))
+        {
+            in_flight.remove(&query);
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl<C: BookClient + Send + Sync> BookClient for CoalescingClient<C> {
+    /// Returns a book by ISBN from the wrapped client, sharing the request with concurrent
+    /// lookups of the same ISBN.
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.coalesce(CacheQuery::Isbn(isbn.clone()), || {
+            self.client.book_by_isbn(isbn)
+        })
+        .await
+    }
+
+    /// Returns a book by author and title from the wrapped client, sharing the request with
+    /// concurrent lookups of the same author and title.
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        self.coalesce(CacheQuery::book(author, title), || {
+            self.client.book(author, title)
+        })
+        .await
+    }
+
+    /// Returns a result for each of the ISBNs from the wrapped client.
+    ///
+    /// The ISBNs are sent to the wrapped client as a single request, that is not shared with
+    /// other lookups, so that clients that return several books in one request can do so.
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        self.client.books_by_isbns(isbns).await
+    }
+
+    /// Returns the capabilities of the wrapped client.
+    fn capabilities(&self) -> Capabilities {
+        self.client.capabilities()
+    }
+}
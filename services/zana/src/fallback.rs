@@ -0,0 +1,310 @@
+/*!
+Queries several providers in turn using the [`FallbackClient`](struct@FallbackClient)
+implementation of [`BookClient`](trait@BookClient).
+
+Providers are queried in the order they are given, and the next provider is queried only when
+the previous one fails with an error of a class that the [`FallbackPolicy`](struct@FallbackPolicy)
+falls back on, e.g. when the book is not found or the rate limit is exceeded.
+Other errors, such as invalid input, are returned right away.
+
+Providers whose [capabilities](crate::BookClient::capabilities) do not include the kind of lookup
+are not queried, and fail with [`Unsupported`](crate::ClientError::Unsupported) instead.
+
+[`provider_book_by_isbn`](FallbackClient::provider_book_by_isbn) and
+[`provider_book`](FallbackClient::provider_book) also return the name of the provider
+that answered.
+
+## Example
+
+```no_run
+use zana::fallback::{FallbackClient, FallbackPolicy, Provider};
+use zana::isbn::Isbn;
+use zana::{googlebooks, openlibrary};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = FallbackClient::new(
+        vec![
+            Provider::new(
+                "googlebooks",
+                googlebooks::Client::new("<api_key>", "https://www.googleapis.com")?,
+            ),
+            Provider::new("openlibrary", openlibrary::Client::new("https://openlibrary.org")?),
+        ],
+        FallbackPolicy::default(),
+    );
+
+    let isbn: Isbn = "9780316387316".parse()?;
+    let answer = client.provider_book_by_isbn(&isbn).await?;
+    println!("book found by {}: {:?}", answer.provider, answer.book);
+    Ok(())
+}
+```
+ */
+use async_trait::async_trait;
+
+use crate::isbn::Isbn;
+use crate::{Book, BookClient, Capabilities, ClientError, IsbnResult, LookupKind};
+
+/// Class of a [`ClientError`](enum@ClientError), used to decide whether to fall back
+/// to the next provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorClass {
+    /// [`NotFound`](ClientError::NotFound)
+    NotFound,
+    /// [`RateLimitExceeded`](ClientError::RateLimitExceeded)
+    RateLimited,
+    /// Other [transient](ClientError::is_transient) errors, such as timeouts, connection failures,
+    /// `5xx` status codes and open circuits
+    Unavailable,
+    /// [`Unauthorized`](ClientError::Unauthorized)
+    Unauthorized,
+    /// [`InvalidInput`](ClientError::InvalidInput)
+    InvalidInput,
+    /// [`Unsupported`](ClientError::Unsupported)
+    Unsupported,
+    /// Any other error, such as invalid responses or unexpected status codes
+    Other,
+}
+
+impl ErrorClass {
+    /// Returns the class of the error.
+    pub fn of(err: &ClientError) -> Self {
+        match err {
+            ClientError::NotFound => ErrorClass::NotFound,
+            ClientError::RateLimitExceeded(_) => ErrorClass::RateLimited,
+            ClientError::Unauthorized(_, _) => ErrorClass::Unauthorized,
+            ClientError::InvalidInput(_) => ErrorClass::InvalidInput,
+            ClientError::Unsupported(_) => ErrorClass::Unsupported,
+            err if err.is_transient() => ErrorClass::Unavailable,
+            _ => ErrorClass::Other,
+        }
+    }
+}
+
+/// Configuration of when [`FallbackClient`](struct@FallbackClient) queries the next provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackPolicy {
+    /// Classes of errors that the next provider is queried for
+    pub fallback_on: Vec<ErrorClass>,
+}
+
+impl Default for FallbackPolicy {
+    /// Returns a policy that falls back when the book is not found, the rate limit is exceeded,
+    /// the provider is unavailable, rejects the credentials or does not support the lookup.
+    fn default() -> Self {
+        Self {
+            fallback_on: vec![
+                ErrorClass::NotFound,
+                ErrorClass::RateLimited,
+                ErrorClass::Unavailable,
+                ErrorClass::Unauthorized,
+                ErrorClass::Unsupported,
+            ],
+        }
+    }
+}
+
+impl FallbackPolicy {
+    /// Returns true if the next provider should be queried after the error.
+    pub fn falls_back_on(&self, err: &ClientError) -> bool {
+        self.fallback_on.contains(&ErrorClass::of(err))
+    }
+}
+
+/// A named client queried by [`FallbackClient`](struct@FallbackClient).
+pub struct Provider {
+    name: String,
+    client: Box<dyn BookClient + Send + Sync>,
+}
+
+impl Provider {
+    /// Returns a new provider with the given name, e.g. `googlebooks`, and client.
+    pub fn new<C: BookClient + Send + Sync + 'static>(name: &str, client: C) -> Self {
+        Self {
+            name: String::from(name),
+            client: Box::new(client),
+        }
+    }
+
+    /// Returns the name of the provider.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the capabilities of the client of the provider.
+    pub fn capabilities(&self) -> Capabilities {
+        self.client.capabilities()
+    }
+
+    /// Returns the client of the provider, or an error if it does not support the lookup.
+    pub(crate) fn client_for(
+        &self,
+        lookup: LookupKind,
+    ) -> Result<&(dyn BookClient + Send + Sync), ClientError> {
+        if self.capabilities().supports(lookup) {
+            Ok(self.client.as_ref())
+        } else {
+            Err(ClientError::Unsupported(lookup))
+        }
+    }
+}
+
+/// A book together with the name of the provider that returned it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderBook {
+    pub provider: String,
+    pub book: Book,
+}
+
+/// A [`BookClient`](trait@BookClient) that queries providers in turn until one of them
+/// returns the book.
+pub struct FallbackClient {
+    providers: Vec<Provider>,
+    policy: FallbackPolicy,
+}
+
+impl FallbackClient {
+    /// Returns a new client that queries the providers in the given order, based on the policy.
+    pub fn new(providers: Vec<Provider>, policy: FallbackPolicy) -> Self {
+        Self { providers, policy }
+    }
+
+    /// Returns the providers, in the order they are queried.
+    pub fn providers(&self) -> &[Provider] {
+        &self.providers
+    }
+
+    /// Returns a book by ISBN and the provider that returned it.
+    ///
+    /// If no provider returns the book, the error of the last queried provider is returned.
+    pub async fn provider_book_by_isbn(&self, isbn: &Isbn) -> Result<ProviderBook, ClientError> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            let book = match provider.client_for(LookupKind::Isbn) {
+                Ok(client) => client.book_by_isbn(isbn).await,
+                Err(err) => Err(err),
+            };
+            match book {
+                Ok(book) => return Ok(self.answer(provider, book)),
+                Err(err) => last_error = Some(self.check(provider, err)?),
+            }
+        }
+        Err(last_error.unwrap_or(ClientError::NotFound))
+    }
+
+    /// Returns a book by author and title, and the provider that returned it.
+    ///
+    /// If no provider returns the book, the error of the last queried provider is returned.
+    pub async fn provider_book(
+        &self,
+        author: &str,
+        title: &str,
+    ) -> Result<ProviderBook, ClientError> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            let book = match provider.client_for(LookupKind::AuthorAndTitle) {
+                Ok(client) => client.book(author, title).await,
+                Err(err) => Err(err),
+            };
+            match book {
+                Ok(book) => return Ok(self.answer(provider, book)),
+                Err(err) => last_error = Some(self.check(provider, err)?),
+            }
+        }
+        Err(last_error.unwrap_or(ClientError::NotFound))
+    }
+
+    fn answer(&self, provider: &Provider, book: Book) -> ProviderBook {
+        ProviderBook {
+            provider: provider.name.clone(),
+            book,
+        }
+    }
+
+    /// Returns the error back if the next provider should be queried, or as an error otherwise.
+    fn check(&self, provider: &Provider, err: ClientError) -> Result<ClientError, ClientError> {
+        if self.policy.falls_back_on(&err) {
+            log::debug!("provider {} failed, falling back: {:?}", provider.name, err);
+            Ok(err)
+        } else {
+            Err(err)
+        }
+    }
+}
+
+#[async_trait]
+impl BookClient for FallbackClient {
+    /// Returns a book by ISBN from the first provider that returns it.
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.provider_book_by_isbn(isbn)
+            .await
+            .map(|answer| answer.book)
+    }
+
+    /// Returns a book by author and title from the first provider that returns it.
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        self.provider_book(author, title)
+            .await
+            .map(|answer| answer.book)
+    }
+
+    /// Returns a result for each of the ISBNs from the first provider that returns the book.
+    ///
+    /// The ISBNs are sent to each provider as a single request, and only the ISBNs that the
+    /// provider did not return are sent to the next one.
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        let mut results: Vec<IsbnResult> = isbns
+            .iter()
+            .map(|isbn| IsbnResult::new(isbn, Err(ClientError::NotFound)))
+            .collect();
+        let mut pending: Vec<usize> = (0..isbns.len()).collect();
+        for provider in &self.providers {
+            if pending.is_empty() {
+                break;
+            }
+            let pending_isbns: Vec<Isbn> =
+                pending.iter().map(|index| isbns[*index].clone()).collect();
+            let provider_results = match provider.client_for(LookupKind::Isbn) {
+                Ok(client) => client.books_by_isbns(&pending_isbns).await,
+                Err(err) => pending_isbns
+                    .iter()
+                    .map(|isbn| IsbnResult::new(isbn, Err(err.clone())))
+                    .collect(),
+            };
+
+            let mut next = Vec::new();
+            for (index, result) in pending.into_iter().zip(provider_results) {
+                if let Err(err) = &result.result {
+                    if self.policy.falls_back_on(err) {
+                        next.push(index);
+                    }
+                }
+                results[index] = result;
+            }
+            if !next.is_empty() {
+                log::debug!(
+                    "provider {} did not return {} isbns, falling back",
+                    provider.name,
+                    next.len()
+                );
+            }
+            pending = next;
+        }
+        results
+    }
+
+    /// Returns the lookups supported by any of the providers.
+    fn capabilities(&self) -> Capabilities {
+        capabilities_of(&self.providers)
+    }
+}
+
+/// Returns the lookups supported by any of the providers.
+pub(crate) fn capabilities_of(providers: &[Provider]) -> Capabilities {
+    providers
+        .iter()
+        .fold(Capabilities::default(), |capabilities, provider| {
+            capabilities.union(&provider.capabilities())
+        })
+}
@@ -4,14 +4,82 @@ implementation of [`BookClient`](trait@BookClient).
 
 It queries the `volumes` endpoints to retrieve data about a book, its author and ratings.
 
+Author and title are [normalized](crate::normalize) before they are queried, e.g. to remove
+diacritics and reorder names written as `Surname, Name`.
+Queries by author and title often return study guides, summaries or translations of the book
+before the book itself, so several volumes are fetched and each is scored against the requested
+author and title. The volume with the best score is returned, if its score reaches the
+[match threshold](ClientBuilder::match_threshold). The score is returned by
+[`best_match`](Client::best_match).
+
+Queries by ISBN may also return volumes that are only loosely related to the ISBN, so only
+a volume with the requested ISBN, as ISBN-10 or ISBN-13, among its identifiers is returned.
+
+Volumes returned by queries lack some of their details, such as the full description or the main
+category, which are returned when a volume is fetched by its ID with
+[`book_by_volume_id`](Client::book_by_volume_id). Volumes found by ISBN can be fetched by their ID
+as well, as set by the [detail policy](ClientBuilder::detail_policy).
+
+Results and their availability vary by language and country, which are set with
+[`QueryOptions`](struct@QueryOptions), either for all requests of a client with
+[`query_options`](ClientBuilder::query_options), or for some requests only with
+[`with_options`](Client::with_options).
+
+## Example
+
+```
+use zana::googlebooks::{Client, PrintType, QueryOptions};
+
+# fn main() -> Result<(), zana::ClientError> {
+let client = Client::builder("YOUR-API-KEY", "https://www.googleapis.com")
+    .query_options(QueryOptions {
+        print_type: Some(PrintType::Books),
+        ..QueryOptions::default()
+    })
+    .build()?;
+
+// Albanian volumes available in Albania, which are still limited to books
+let albanian_client = client.with_options(&QueryOptions {
+    lang_restrict: Some(String::from("sq")),
+    country: Some(String::from("AL")),
+    ..QueryOptions::default()
+});
+# Ok(())
+# }
+```
+
 See example [here](../index.html#example).
  */
 use async_trait::async_trait;
 use serde::Deserialize;
 
-use crate::{create_http_client, Book, BookClient, ClientError, Rating};
+use crate::http::{HttpClient, HttpConfig};
+use crate::isbn::Isbn;
+use crate::normalize;
+use crate::{
+    Book, BookClient, Capabilities, ClientError, Identifier, IdentifierType, LookupKind, RateLimit,
+    Rating,
+};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub use crate::normalize::DEFAULT_MATCH_THRESHOLD;
 
 const VOLUMES_PATH: &str = "/books/v1/volumes";
+/// Number of volumes fetched for queries by ISBN, to find the volume with the ISBN among them
+const ISBN_CANDIDATES: &str = "5";
+/// Number of volumes fetched for queries by author and title, to choose the best match from
+const MATCH_CANDIDATES: &str = "10";
+/// Error reasons returned by Google APIs when the API key is invalid, or cannot be used for Google Books API.
+const UNAUTHORIZED_REASONS: [&str; 6] = [
+    "keyInvalid",
+    "keyExpired",
+    "accessNotConfigured",
+    "API_KEY_INVALID",
+    "API_KEY_SERVICE_BLOCKED",
+    "SERVICE_DISABLED",
+];
 
 #[derive(Deserialize, Debug)]
 struct Volume {
@@ -27,10 +95,21 @@ struct VolumeItem {
 
 #[derive(Deserialize, Debug)]
 struct VolumeInfo {
-    #[serde(rename(deserialize = "title"))]
-    _title: String,
-    #[serde(rename(deserialize = "authors"))]
-    _authors: Vec<String>,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    authors: Vec<String>,
+    publisher: Option<String>,
+    #[serde(rename(deserialize = "publishedDate"))]
+    published_date: Option<String>,
+    language: Option<String>,
+    /// Only returned when the volume is fetched by its ID
+    #[serde(rename(deserialize = "mainCategory"))]
+    main_category: Option<String>,
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(rename(deserialize = "industryIdentifiers"), default)]
+    industry_identifiers: Vec<IndustryIdentifier>,
     description: Option<String>,
     #[serde(rename(deserialize = "pageCount"))]
     page_count: Option<u32>,
@@ -42,31 +121,466 @@ struct VolumeInfo {
     info_link: String,
 }
 
+#[derive(Deserialize, Debug)]
+struct IndustryIdentifier {
+    #[serde(rename(deserialize = "type"))]
+    identifier_type: String,
+    identifier: String,
+}
+
+impl From<&IndustryIdentifier> for Identifier {
+    fn from(industry_identifier: &IndustryIdentifier) -> Self {
+        let identifier_type = match industry_identifier.identifier_type.as_str() {
+            "ISBN_10" => IdentifierType::Isbn10,
+            "ISBN_13" => IdentifierType::Isbn13,
+            "ISSN" => IdentifierType::Issn,
+            other => IdentifierType::Other(String::from(other)),
+        };
+        Identifier::new(identifier_type, &industry_identifier.identifier)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct ErrorResponse {
+    error: ErrorDetails,
+}
+
+#[derive(Deserialize, Debug)]
+struct ErrorDetails {
+    #[serde(default)]
+    errors: Vec<ErrorDetail>,
+    #[serde(default)]
+    details: Vec<ErrorDetail>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ErrorDetail {
+    reason: Option<String>,
+    /// Set for `google.rpc.RetryInfo` details, in the `<seconds>s` format (e.g. `30s`)
+    #[serde(rename(deserialize = "retryDelay"))]
+    retry_delay: Option<String>,
+}
+
+impl ErrorResponse {
+    fn error_details(&self) -> impl Iterator<Item = &ErrorDetail> {
+        self.error.errors.iter().chain(self.error.details.iter())
+    }
+
+    fn reasons(&self) -> impl Iterator<Item = &str> {
+        self.error_details()
+            .filter_map(|error_detail| error_detail.reason.as_deref())
+    }
+
+    fn has_reason(&self, reasons: &[&str]) -> bool {
+        self.reasons().any(|reason| reasons.contains(&reason))
+    }
+
+    fn retry_delay(&self) -> Option<Duration> {
+        self.error_details()
+            .filter_map(|error_detail| error_detail.retry_delay.as_deref())
+            .find_map(|retry_delay| retry_delay.strip_suffix('s')?.parse::<f64>().ok())
+            .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+            .map(Duration::from_secs_f64)
+    }
+}
+
+/// When a volume found by ISBN is fetched again by its ID, for the details that are missing
+/// from query results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DetailPolicy {
+    /// The volume returned by the query is used as it is
+    #[default]
+    Never,
+    /// The volume is fetched by its ID when it has no description or page count
+    WhenIncomplete,
+    /// The volume is always fetched by its ID
+    Always,
+}
+
+/// Type of volumes returned, set as the `printType` parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrintType {
+    All,
+    Books,
+    Magazines,
+}
+
+impl PrintType {
+    /// Returns the value of the `printType` parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrintType::All => "all",
+            PrintType::Books => "books",
+            PrintType::Magazines => "magazines",
+        }
+    }
+}
+
+impl FromStr for PrintType {
+    type Err = ClientError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "all" => Ok(PrintType::All),
+            "books" => Ok(PrintType::Books),
+            "magazines" => Ok(PrintType::Magazines),
+            _ => Err(ClientError::InvalidInput(format!(
+                "invalid print type: {}",
+                input
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for PrintType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Set of volume fields returned, set as the `projection` parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Projection {
+    Full,
+    Lite,
+}
+
+impl Projection {
+    /// Returns the value of the `projection` parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Projection::Full => "full",
+            Projection::Lite => "lite",
+        }
+    }
+}
+
+impl FromStr for Projection {
+    type Err = ClientError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "full" => Ok(Projection::Full),
+            "lite" => Ok(Projection::Lite),
+            _ => Err(ClientError::InvalidInput(format!(
+                "invalid projection: {}",
+                input
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Projection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Options sent with each query to the `volumes` endpoint. Options that are not set
+/// are not sent, so the defaults of Google Books API apply.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct QueryOptions {
+    /// Two-letter ISO 639-1 code of the language of returned volumes, e.g. `sq`,
+    /// set as the `langRestrict` parameter
+    pub lang_restrict: Option<String>,
+    /// Two-letter ISO 3166-1 code of the country that returned volumes are available in,
+    /// e.g. `AL`, set as the `country` parameter
+    pub country: Option<String>,
+    pub print_type: Option<PrintType>,
+    pub projection: Option<Projection>,
+}
+
+impl QueryOptions {
+    /// Returns true if no option is set.
+    pub fn is_empty(&self) -> bool {
+        self == &QueryOptions::default()
+    }
+
+    /// Returns these options, with the options that are not set taken from `defaults`.
+    pub fn or(&self, defaults: &QueryOptions) -> QueryOptions {
+        QueryOptions {
+            lang_restrict: self
+                .lang_restrict
+                .clone()
+                .or_else(|| defaults.lang_restrict.clone()),
+            country: self.country.clone().or_else(|| defaults.country.clone()),
+            print_type: self.print_type.or(defaults.print_type),
+            projection: self.projection.or(defaults.projection),
+        }
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, &str)> {
+        let mut parameters = Vec::new();
+        if let Some(lang_restrict) = &self.lang_restrict {
+            parameters.push(("langRestrict", lang_restrict.as_str()));
+        }
+        if let Some(country) = &self.country {
+            parameters.push(("country", country.as_str()));
+        }
+        if let Some(print_type) = &self.print_type {
+            parameters.push(("printType", print_type.as_str()));
+        }
+        if let Some(projection) = &self.projection {
+            parameters.push(("projection", projection.as_str()));
+        }
+        parameters
+    }
+}
+
+impl fmt::Display for QueryOptions {
+    /// Formats the options as the query parameters they are sent as,
+    /// e.g. `langRestrict=sq&country=AL`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parameters: Vec<String> = self
+            .parameters()
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        write!(f, "{}", parameters.join("&"))
+    }
+}
+
+/// Builder used to configure a [`Client`](struct@Client), created with
+/// [`Client::builder`](Client::builder).
+#[derive(Debug)]
+pub struct ClientBuilder {
+    api_key: String,
+    api_url: String,
+    http_config: HttpConfig,
+    match_threshold: f32,
+    query_options: QueryOptions,
+    detail_policy: DetailPolicy,
+}
+
+impl ClientBuilder {
+    /// Sets the timeout of each request, from when it is sent until the response body is read.
+    /// Defaults to 30 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.http_config.timeout = timeout;
+        self
+    }
+
+    /// Sets the timeout for connecting to the API. Defaults to 30 seconds.
+    ///
+    /// Not applied when a shared [`http_client`](ClientBuilder::http_client) is used.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.http_config.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Sets a suffix that is appended to the `zana/{version} (gzip)` user agent.
+    ///
+    /// Not applied when a shared [`http_client`](ClientBuilder::http_client) is used.
+    pub fn user_agent_suffix(mut self, suffix: &str) -> Self {
+        self.http_config.user_agent_suffix = Some(String::from(suffix));
+        self
+    }
+
+    /// Sets a proxy that all requests are sent through.
+    ///
+    /// Not applied when a shared [`http_client`](ClientBuilder::http_client) is used.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.http_config.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets headers that are sent with each request.
+    pub fn default_headers(mut self, headers: reqwest::header::HeaderMap) -> Self {
+        self.http_config.default_headers = headers;
+        self
+    }
+
+    /// Sets an existing HTTP client to send requests with, e.g. to share its connection pool
+    /// between clients. Its own settings are kept, except for the timeout and default headers.
+    pub fn http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_config.http_client = Some(http_client);
+        self
+    }
+
+    /// Sets the minimum score, between 0 and 1, of a volume returned for a query by author
+    /// and title. Defaults to [`DEFAULT_MATCH_THRESHOLD`](DEFAULT_MATCH_THRESHOLD).
+    pub fn match_threshold(mut self, match_threshold: f32) -> Self {
+        self.match_threshold = match_threshold;
+        self
+    }
+
+    /// Sets the options sent with each query, such as the language of returned volumes.
+    /// Defaults to no options.
+    pub fn query_options(mut self, query_options: QueryOptions) -> Self {
+        self.query_options = query_options;
+        self
+    }
+
+    /// Sets when a volume found by ISBN is fetched again by its ID, which takes one more
+    /// request. Defaults to [`Never`](DetailPolicy::Never).
+    ///
+    /// If fetching the volume by its ID fails, the volume found by ISBN is returned.
+    pub fn detail_policy(mut self, detail_policy: DetailPolicy) -> Self {
+        self.detail_policy = detail_policy;
+        self
+    }
+
+    /// Returns a new client with the configured settings.
+    pub fn build(self) -> Result<Client, ClientError> {
+        Ok(Client {
+            api_key: self.api_key,
+            api_url: self.api_url,
+            http_client: self.http_config.build()?,
+            match_threshold: self.match_threshold,
+            query_options: self.query_options,
+            detail_policy: self.detail_policy,
+        })
+    }
+}
+
+/// A book returned for a query by author and title, and how well it matches them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookMatch {
+    pub book: Book,
+    /// Similarity of the book to the requested author and title, between 0 and 1
+    pub score: f32,
+}
+
 /// Client used to retrieve data from Google Books API.
 pub struct Client {
     api_key: String,
     api_url: String,
-    http_client: reqwest::Client,
+    http_client: HttpClient,
+    match_threshold: f32,
+    query_options: QueryOptions,
+    detail_policy: DetailPolicy,
 }
 
 impl Client {
     /// Returns a new client that will make requests using the given API key to
     /// the given API URL.
+    ///
+    /// Use [`builder`](Client::builder) to change the default settings of the client.
     pub fn new(api_key: &str, api_url: &str) -> Result<Self, ClientError> {
-        let http_client = create_http_client()?;
-        Ok(Client {
+        Self::builder(api_key, api_url).build()
+    }
+
+    /// Returns a builder for a client that will make requests using the given API key to
+    /// the given API URL.
+    pub fn builder(api_key: &str, api_url: &str) -> ClientBuilder {
+        ClientBuilder {
             api_key: String::from(api_key),
             api_url: String::from(api_url),
-            http_client,
-        })
+            http_config: HttpConfig::default(),
+            match_threshold: DEFAULT_MATCH_THRESHOLD,
+            query_options: QueryOptions::default(),
+            detail_policy: DetailPolicy::default(),
+        }
     }
 
-    fn create_book(&self, items: Vec<VolumeItem>) -> Result<Book, ClientError> {
-        if items.is_empty() {
-            return Err(ClientError::NotFound);
+    /// Returns a client with the same settings, that sends the given options with each query.
+    ///
+    /// Options that are not set are taken from the options of this client. The returned client
+    /// shares the HTTP client, and so the connection pool, of this client.
+    pub fn with_options(&self, query_options: &QueryOptions) -> Client {
+        Client {
+            api_key: self.api_key.clone(),
+            api_url: self.api_url.clone(),
+            http_client: self.http_client.clone(),
+            match_threshold: self.match_threshold,
+            query_options: query_options.or(&self.query_options),
+            detail_policy: self.detail_policy,
+        }
+    }
+
+    /// Returns the options sent with each query.
+    pub fn query_options(&self) -> &QueryOptions {
+        &self.query_options
+    }
+
+    /// Returns the book that best matches the author and title, and its score.
+    ///
+    /// Volumes are queried with the [normalized](crate::normalize) author and title, each quoted
+    /// so that all of its words are matched, and omitted from the query when it is empty.
+    /// If both author and title are empty once normalized, an error is returned without making
+    /// any request.
+    /// If no volume has a score of at least the match threshold, the book is not found.
+    pub async fn best_match(&self, author: &str, title: &str) -> Result<BookMatch, ClientError> {
+        let normalized_author = normalize::normalize_author(author);
+        let normalized_title = normalize::normalize(title);
+        let mut qualifiers = Vec::with_capacity(2);
+        if !normalized_author.is_empty() {
+            qualifiers.push(format!("inauthor:\"{}\"", normalized_author));
+        }
+        if !normalized_title.is_empty() {
+            qualifiers.push(format!("intitle:\"{}\"", normalized_title));
+        }
+        if qualifiers.is_empty() {
+            return Err(ClientError::InvalidInput(String::from(
+                "author or title must be provided",
+            )));
         }
+        let query = qualifiers.join(" ");
+        let items = self.fetch_volumes(&query, MATCH_CANDIDATES).await?;
+        let best_match = items
+            .iter()
+            .map(|item| (item, match_score(&item.info, author, title)))
+            .reduce(|best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
 
-        let volume_item = &items[0];
+        match best_match {
+            Some((item, score)) if score >= self.match_threshold => {
+                log::debug!(
+                    "volume {} matches author {} and title {} with score {}",
+                    &item.id,
+                    author,
+                    title,
+                    score
+                );
+                Ok(BookMatch {
+                    book: self.create_book(item),
+                    score,
+                })
+            }
+            Some((item, score)) => {
+                log::debug!(
+                    "best volume {} for author {} and title {} has score {}, below threshold {}",
+                    &item.id,
+                    author,
+                    title,
+                    score,
+                    self.match_threshold
+                );
+                Err(ClientError::NotFound)
+            }
+            None => Err(ClientError::NotFound),
+        }
+    }
+
+    /// Returns a book by the ID of its volume at Google Books, e.g. `wwspvAEACAAJ`.
+    ///
+    /// The volume is fetched with all of its details, such as the full description and the main
+    /// category, which queries by ISBN or by author and title do not return.
+    /// If the ID is empty or not valid, an error is returned without making any request.
+    /// If an error occurs with the communication, an HTTP status code that is not 200 is returned,
+    /// the volume is not found, or the rate limit is exceeded then an error is returned.
+    pub async fn book_by_volume_id(&self, volume_id: &str) -> Result<Book, ClientError> {
+        let volume_id = volume_id.trim();
+        if volume_id.is_empty()
+            || !volume_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(ClientError::InvalidInput(format!(
+                "invalid volume ID: {}",
+                volume_id
+            )));
+        }
+        let volume_item = self.fetch_volume(volume_id).await?;
+        Ok(self.create_book(&volume_item))
+    }
+
+    fn create_book(&self, volume_item: &VolumeItem) -> Book {
         let volume_info = &volume_item.info;
 
         let average_rating = volume_info.average_rating.unwrap_or(0_f32);
@@ -75,6 +589,24 @@ impl Client {
         let description = volume_info.description.as_deref().unwrap_or("");
         let page_count = volume_info.page_count.unwrap_or(0);
 
+        let mut book = Book::new(page_count, description, &volume_info.info_link);
+        book.title = volume_info.title.clone();
+        book.authors = volume_info.authors.clone();
+        book.publisher = volume_info.publisher.clone().unwrap_or_default();
+        book.published_date = volume_info.published_date.clone().unwrap_or_default();
+        book.language = volume_info.language.clone().unwrap_or_default();
+        book.categories = volume_info.categories.clone();
+        if let Some(main_category) = &volume_info.main_category {
+            if !book.categories.contains(main_category) {
+                book.categories.insert(0, main_category.clone());
+            }
+        }
+        book.identifiers = volume_info
+            .industry_identifiers
+            .iter()
+            .map(Identifier::from)
+            .collect();
+
         if average_rating == 0_f32 || ratings_count == 0 {
             log::debug!(
                 "ratings not added for book with volume id {}, average_rating {}, ratings_count {}",
@@ -82,25 +614,112 @@ impl Client {
                 average_rating,
                 ratings_count
             );
-            Ok(Book::new(page_count, &description, &volume_info.info_link))
         } else {
-            let rating = Rating::new(average_rating, ratings_count);
-            Ok(Book::new_with_rating(
-                page_count,
-                &description,
-                &volume_info.info_link,
-                rating,
-            ))
+            book.rating = Some(Rating::new(average_rating, ratings_count));
+        }
+        book
+    }
+
+    /// Returns the first volume that has the ISBN among its identifiers.
+    async fn fetch_book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        let items = self
+            .fetch_volumes(&format!("isbn:{}", isbn), ISBN_CANDIDATES)
+            .await?;
+        match items.iter().find(|item| has_isbn(&item.info, isbn)) {
+            Some(volume_item) if self.needs_details(&volume_item.info) => {
+                match self.fetch_volume(&volume_item.id).await {
+                    Ok(detailed_item) => Ok(self.create_book(&detailed_item)),
+                    Err(err) => {
+                        log::debug!(
+                            "could not fetch details of volume {} for ISBN({}), {:?}",
+                            &volume_item.id,
+                            isbn,
+                            err
+                        );
+                        Ok(self.create_book(volume_item))
+                    }
+                }
+            }
+            Some(volume_item) => Ok(self.create_book(volume_item)),
+            None => {
+                log::debug!(
+                    "none of the {} volumes returned for ISBN({}) has the ISBN",
+                    items.len(),
+                    isbn
+                );
+                Err(ClientError::NotFound)
+            }
+        }
+    }
+
+    fn needs_details(&self, volume_info: &VolumeInfo) -> bool {
+        match self.detail_policy {
+            DetailPolicy::Never => false,
+            DetailPolicy::WhenIncomplete => {
+                volume_info
+                    .description
+                    .as_deref()
+                    .filter(|description| !description.trim().is_empty())
+                    .is_none()
+                    || volume_info
+                        .page_count
+                        .filter(|page_count| *page_count > 0)
+                        .is_none()
+            }
+            DetailPolicy::Always => true,
+        }
+    }
+
+    /// Returns the volume with the given ID, with only the `country` query option sent, since
+    /// the others do not apply to a single volume.
+    ///
+    /// The full projection is always requested, since the volume is fetched for the fields
+    /// that the lite projection leaves out, such as the description.
+    async fn fetch_volume(&self, volume_id: &str) -> Result<VolumeItem, ClientError> {
+        let mut query_list: Vec<(&str, &str)> = vec![
+            ("key", &self.api_key),
+            ("projection", Projection::Full.as_str()),
+        ];
+        query_list.extend(
+            self.query_options
+                .parameters()
+                .into_iter()
+                .filter(|(name, _)| *name == "country"),
+        );
+
+        let response = self
+            .http_client
+            .get(format!("{}{}/{}", self.api_url, VOLUMES_PATH, volume_id))
+            .header("Accept-Encoding", "gzip")
+            .query(&query_list)
+            .send()
+            .await?;
+
+        let status_code = response.status().as_u16();
+        if status_code == 404 {
+            return Err(ClientError::NotFound);
         }
+        if !(200..300).contains(&status_code) {
+            let rate_limit = RateLimit::from_headers(response.headers());
+            let response_body = response.text().await?;
+            return Err(create_error(status_code, rate_limit, response_body));
+        }
+
+        Ok(response.json().await?)
     }
 
-    async fn fetch_book(&self, query: &str) -> Result<Book, ClientError> {
-        let query_list: Vec<(&str, &str)> = vec![
+    async fn fetch_volumes(
+        &self,
+        query: &str,
+        max_results: &str,
+    ) -> Result<Vec<VolumeItem>, ClientError> {
+        let mut query_list: Vec<(&str, &str)> = vec![
             ("key", &self.api_key),
-            ("maxResults", "1"),
+            ("maxResults", max_results),
             ("fields", "items"),
             ("q", query),
         ];
+        query_list.extend(self.query_options.parameters());
 
         let response = self
             .http_client
@@ -111,19 +730,14 @@ impl Client {
             .await?;
 
         let status_code = response.status().as_u16();
-        if status_code == 429 || status_code == 403 {
-            return Err(ClientError::RateLimitExceeded);
-        } else if status_code < 200 || status_code >= 300 {
+        if !(200..300).contains(&status_code) {
+            let rate_limit = RateLimit::from_headers(response.headers());
             let response_body = response.text().await?;
-            return Err(ClientError::Http(status_code, response_body));
+            return Err(create_error(status_code, rate_limit, response_body));
         }
 
         let volume: Volume = response.json().await?;
-
-        if let Some(items) = volume.items {
-            return self.create_book(items);
-        }
-        Err(ClientError::NotFound)
+        Ok(volume.items.unwrap_or_default())
     }
 }
 
@@ -131,20 +745,76 @@ impl Client {
 impl BookClient for Client {
     /// Returns a book by ISBN.
     ///
-    /// Volumes endpoint of Google Books API is queried.
+    /// Volumes endpoint of Google Books API is queried, and the first volume that has the ISBN
+    /// among its identifiers is returned.
     /// If an error occurs with the communication, an HTTP status code that is not 200 is returned,
     /// the book is not found, or the rate limit is exceeded then an error is returned.
-    async fn book_by_isbn(&self, isbn: &str) -> Result<Book, ClientError> {
-        self.fetch_book(&format!("isbn:{}", isbn)).await
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.fetch_book_by_isbn(isbn).await
     }
 
     /// Returns a book by author and title.
     ///
-    /// Volumes endpoint of Google Books API is queried.
+    /// Volumes endpoint of Google Books API is queried, and the volume that best matches
+    /// the author and title is returned, as with [`best_match`](Client::best_match).
+    /// If both author and title are empty, an error is returned without making any request.
     /// If an error occurs with the communication, an HTTP status code that is not 200 is returned,
     /// the book is not found, or the rate limit is exceeded then an error is returned.
     async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
-        self.fetch_book(&format!("inauthor:{} intitle:{}", author, title))
+        self.best_match(author, title)
             .await
+            .map(|book_match| book_match.book)
+    }
+
+    /// Returns the capabilities of the client, which supports lookups by ISBN, by author
+    /// and title, and by volume ID, with [`book_by_volume_id`](Client::book_by_volume_id).
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(&[
+            LookupKind::Isbn,
+            LookupKind::AuthorAndTitle,
+            LookupKind::Identifiers,
+        ])
+    }
+}
+
+/// Returns true if the volume has an ISBN-10 or ISBN-13 identifier equal to the ISBN.
+fn has_isbn(volume_info: &VolumeInfo, isbn: &Isbn) -> bool {
+    volume_info
+        .industry_identifiers
+        .iter()
+        .filter(|identifier| matches!(identifier.identifier_type.as_str(), "ISBN_10" | "ISBN_13"))
+        .filter_map(|identifier| Isbn::parse(&identifier.identifier).ok())
+        .any(|identifier| &identifier == isbn)
+}
+
+/// Returns how well the volume matches the requested author and title, between 0 and 1,
+/// as scored by [`match_score`](normalize::match_score).
+fn match_score(volume_info: &VolumeInfo, author: &str, title: &str) -> f32 {
+    normalize::match_score(author, title, &volume_info.title, &volume_info.authors)
+}
+
+/// Returns the error for a response with a status code that is not 2xx.
+///
+/// Google Books API returns `403` both when the quota is exceeded and when the API key cannot be
+/// used, so the error reasons in the response body are used to tell them apart.
+/// For exceeded rate limits, the `Retry-After` header is preferred over the retry delay
+/// and reason provided in the response body.
+fn create_error(status_code: u16, mut rate_limit: RateLimit, response_body: String) -> ClientError {
+    let error_response = serde_json::from_str::<ErrorResponse>(&response_body).ok();
+    let unauthorized = error_response
+        .as_ref()
+        .is_some_and(|error_response| error_response.has_reason(&UNAUTHORIZED_REASONS));
+
+    match status_code {
+        401 => ClientError::Unauthorized(status_code, response_body),
+        400 | 403 if unauthorized => ClientError::Unauthorized(status_code, response_body),
+        403 | 429 => {
+            if let Some(error_response) = &error_response {
+                rate_limit.retry_after = rate_limit.retry_after.or(error_response.retry_delay());
+                rate_limit.reason = error_response.reasons().next().map(String::from);
+            }
+            ClientError::RateLimitExceeded(rate_limit)
+        }
+        _ => ClientError::Http(status_code, response_body),
     }
 }
@@ -0,0 +1,88 @@
+/*!
+HTTP client shared by the implementations of [`BookClient`](trait@crate::BookClient),
+configured through their builders.
+ */
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::{IntoUrl, Proxy, RequestBuilder};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Settings used to create an [`HttpClient`](struct@HttpClient).
+#[derive(Debug, Clone)]
+pub(crate) struct HttpConfig {
+    pub(crate) timeout: Duration,
+    pub(crate) connect_timeout: Duration,
+    pub(crate) user_agent_suffix: Option<String>,
+    pub(crate) proxy: Option<Proxy>,
+    pub(crate) default_headers: HeaderMap,
+    pub(crate) http_client: Option<reqwest::Client>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            user_agent_suffix: None,
+            proxy: None,
+            default_headers: HeaderMap::new(),
+            http_client: None,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Returns a new HTTP client based on the settings.
+    ///
+    /// If a shared [`reqwest::Client`](reqwest::Client) is set, it is used as it is, and only the
+    /// timeout and the default headers are applied to its requests.
+    pub(crate) fn build(self) -> Result<HttpClient, reqwest::Error> {
+        let client = match self.http_client {
+            Some(http_client) => http_client,
+            None => {
+                let mut builder = reqwest::Client::builder()
+                    .gzip(true)
+                    .user_agent(user_agent(self.user_agent_suffix.as_deref()))
+                    .connect_timeout(self.connect_timeout);
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+        Ok(HttpClient {
+            client,
+            timeout: self.timeout,
+            default_headers: self.default_headers,
+        })
+    }
+}
+
+/// HTTP client with the settings that are applied to each request.
+#[derive(Debug, Clone)]
+pub(crate) struct HttpClient {
+    client: reqwest::Client,
+    timeout: Duration,
+    default_headers: HeaderMap,
+}
+
+impl HttpClient {
+    /// Returns a `GET` request for the given URL, with the timeout and default headers set.
+    pub(crate) fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client
+            .get(url)
+            .timeout(self.timeout)
+            .headers(self.default_headers.clone())
+    }
+}
+
+fn user_agent(suffix: Option<&str>) -> String {
+    let version: &str = option_env!("CARGO_PKG_VERSION").unwrap_or("1.0.0");
+    match suffix {
+        Some(suffix) => format!("zana/{} (gzip) {}", version, suffix),
+        None => format!("zana/{} (gzip)", version),
+    }
+}
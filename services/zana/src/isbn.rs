@@ -0,0 +1,297 @@
+/*!
+Provides [`Isbn`](struct@Isbn), a validated International Standard Book Number.
+
+ISBNs can be parsed from user input that is not strictly formatted, such as ISBNs that
+contain hyphens, spaces or an `ISBN` prefix (e.g. `ISBN-10: 0-316-38731-2`).
+Both ISBN-10 and ISBN-13 are supported, and their checksums are validated during parsing.
+
+Every ISBN is kept in its ISBN-13 form, which is also its canonical form, so an ISBN-10
+and an ISBN-13 of the same book are equal once parsed.
+
+ISBNs can be hyphenated into their prefix, registration group, registrant, publication and
+check digit elements. Hyphenation uses an embedded copy of the range data published by the
+[International ISBN Agency](https://www.isbn-international.org/range_file_generation),
+which can be regenerated with the `tools/isbn_ranges` tool.
+
+## Example
+
+```
+use zana::isbn::Isbn;
+
+let isbn: Isbn = "ISBN 0-316-38731-2".parse().expect("valid ISBN");
+
+assert_eq!("9780316387316", isbn.as_str());
+assert_eq!(Some(String::from("0316387312")), isbn.to_isbn10());
+assert_eq!(Some(String::from("978-0-316-38731-6")), isbn.hyphenated());
+```
+ */
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+mod ranges;
+
+const ISBN_PREFIX: &str = "ISBN";
+const BOOKLAND_PREFIX: &str = "978";
+const BOOKLAND_PREFIXES: [&str; 2] = [BOOKLAND_PREFIX, "979"];
+
+/// An error that occurs when an [`Isbn`](struct@Isbn) cannot be parsed.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IsbnError {
+    /// Occurs when the ISBN does not have 10 or 13 digits, after separators are removed.
+    #[error("ISBN must have 10 or 13 digits, found {0}")]
+    InvalidLength(usize),
+    /// Occurs when the ISBN contains a character that is neither a digit nor a separator.
+    #[error("invalid character '{0}' in ISBN")]
+    InvalidCharacter(char),
+    /// Occurs when the check digit of the ISBN does not match the rest of its digits.
+    #[error("invalid ISBN checksum")]
+    InvalidChecksum,
+    /// Occurs when an ISBN-13 does not start with one of the `978` or `979` prefixes.
+    #[error("ISBN-13 must start with 978 or 979")]
+    InvalidPrefix,
+}
+
+/// A validated ISBN, kept in its canonical ISBN-13 form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct Isbn {
+    isbn13: String,
+}
+
+/// Elements of an ISBN-13, as separated by hyphens.
+///
+/// For `978-0-316-38731-6`, the prefix is `978`, the registration group is `0`,
+/// the registrant is `316`, the publication is `38731` and the check digit is `6`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IsbnParts {
+    /// EAN.UCC prefix, either `978` or `979`
+    pub prefix: String,
+    /// Registration group, identifying a language area or country
+    pub registration_group: String,
+    /// Name of the language area or country of the registration group, e.g. `Albania`
+    pub registration_group_agency: String,
+    /// Registrant, identifying the publisher within the registration group
+    pub registrant: String,
+    /// Publication, identifying the edition within the registrant
+    pub publication: String,
+    pub check_digit: char,
+}
+
+impl Isbn {
+    /// Returns a new ISBN parsed from the given input.
+    ///
+    /// Hyphens, spaces and an `ISBN`, `ISBN-10` or `ISBN-13` prefix are ignored.
+    /// An error is returned if the input is not a valid ISBN-10 or ISBN-13.
+    pub fn parse(input: &str) -> Result<Self, IsbnError> {
+        let digits = normalize(strip_prefix(input))?;
+        match digits.len() {
+            10 => {
+                if isbn10_check_digit(&digits[..9]) != digits.as_bytes()[9] {
+                    return Err(IsbnError::InvalidChecksum);
+                }
+                let isbn13 = format!("{}{}", BOOKLAND_PREFIX, &digits[..9]);
+                let check_digit = isbn13_check_digit(&isbn13) as char;
+                Ok(Self {
+                    isbn13: format!("{}{}", isbn13, check_digit),
+                })
+            }
+            13 => {
+                if !BOOKLAND_PREFIXES.contains(&&digits[..3]) {
+                    return Err(IsbnError::InvalidPrefix);
+                }
+                if isbn13_check_digit(&digits[..12]) != digits.as_bytes()[12] {
+                    return Err(IsbnError::InvalidChecksum);
+                }
+                Ok(Self { isbn13: digits })
+            }
+            length => Err(IsbnError::InvalidLength(length)),
+        }
+    }
+
+    /// Returns the canonical form of the ISBN, which is the ISBN-13 without separators.
+    pub fn as_str(&self) -> &str {
+        &self.isbn13
+    }
+
+    /// Returns the ISBN-13 form of the ISBN without separators.
+    pub fn to_isbn13(&self) -> String {
+        self.isbn13.clone()
+    }
+
+    /// Returns the ISBN-10 form of the ISBN without separators.
+    ///
+    /// Only ISBNs with the `978` prefix have an ISBN-10 form, [`None`](None) is returned otherwise.
+    pub fn to_isbn10(&self) -> Option<String> {
+        if !self.isbn13.starts_with(BOOKLAND_PREFIX) {
+            return None;
+        }
+        let digits = &self.isbn13[3..12];
+        let check_digit = isbn10_check_digit(digits) as char;
+        Some(format!("{}{}", digits, check_digit))
+    }
+
+    /// Returns the elements of the ISBN as defined by the ISBN ranges.
+    ///
+    /// [`None`](None) is returned when the registration group or registrant of the ISBN
+    /// is in a range that is not in use, or not part of the embedded range data.
+    pub fn parts(&self) -> Option<IsbnParts> {
+        let ranges = ranges::ranges();
+        let (prefix, rest) = self.isbn13.split_at(3);
+        let rest = &rest[..rest.len() - 1];
+
+        let group_length = ranges.prefix(prefix)?.element_length(rest)?;
+        let registration_group = &rest[..group_length];
+        let rest = &rest[group_length..];
+
+        let group = ranges.group(&format!("{}-{}", prefix, registration_group))?;
+        let registrant_length = group.element_length(rest)?;
+        if registrant_length >= rest.len() {
+            return None;
+        }
+        let (registrant, publication) = rest.split_at(registrant_length);
+
+        Some(IsbnParts {
+            prefix: String::from(prefix),
+            registration_group: String::from(registration_group),
+            registration_group_agency: group.agency.clone(),
+            registrant: String::from(registrant),
+            publication: String::from(publication),
+            check_digit: self.isbn13.as_bytes()[12] as char,
+        })
+    }
+
+    /// Returns the ISBN-13 with hyphens between its elements, e.g. `978-0-316-38731-6`.
+    ///
+    /// [`None`](None) is returned if the ISBN cannot be split into its elements,
+    /// see [`parts`](Isbn::parts).
+    pub fn hyphenated(&self) -> Option<String> {
+        self.parts().map(|parts| {
+            format!(
+                "{}-{}-{}-{}-{}",
+                parts.prefix,
+                parts.registration_group,
+                parts.registrant,
+                parts.publication,
+                parts.check_digit
+            )
+        })
+    }
+
+    /// Returns the ISBN-10 with hyphens between its elements, e.g. `0-316-38731-2`.
+    ///
+    /// [`None`](None) is returned if the ISBN has no ISBN-10 form, or if it cannot be split
+    /// into its elements, see [`parts`](Isbn::parts).
+    pub fn hyphenated_isbn10(&self) -> Option<String> {
+        let isbn10 = self.to_isbn10()?;
+        self.parts().map(|parts| {
+            format!(
+                "{}-{}-{}-{}",
+                parts.registration_group,
+                parts.registrant,
+                parts.publication,
+                &isbn10[9..]
+            )
+        })
+    }
+}
+
+impl FromStr for Isbn {
+    type Err = IsbnError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+    }
+}
+
+impl TryFrom<String> for Isbn {
+    type Error = IsbnError;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        Self::parse(&input)
+    }
+}
+
+impl From<Isbn> for String {
+    fn from(isbn: Isbn) -> Self {
+        isbn.isbn13
+    }
+}
+
+impl fmt::Display for Isbn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.isbn13)
+    }
+}
+
+/// Removes `ISBN`, `ISBN-10` and `ISBN-13` prefixes, followed by optional `:` and whitespace.
+fn strip_prefix(input: &str) -> &str {
+    let input = input.trim();
+    let has_prefix = input
+        .get(..ISBN_PREFIX.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(ISBN_PREFIX));
+    if !has_prefix {
+        return input;
+    }
+
+    let mut rest = &input[ISBN_PREFIX.len()..];
+    for label in ["-13", "-10", "13", "10"] {
+        if let Some(after_label) = rest.strip_prefix(label) {
+            if after_label.starts_with(|c: char| c == ':' || c.is_whitespace()) {
+                rest = after_label;
+                break;
+            }
+        }
+    }
+    rest.trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+}
+
+/// Removes separators and returns only digits and the `X` check digit, in uppercase.
+fn normalize(input: &str) -> Result<String, IsbnError> {
+    let mut digits = String::with_capacity(13);
+    for c in input.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            'x' | 'X' => digits.push('X'),
+            '-' | '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' => {}
+            c if c.is_whitespace() => {}
+            c => return Err(IsbnError::InvalidCharacter(c)),
+        }
+    }
+    // X is only valid as the check digit of an ISBN-10
+    if let Some(position) = digits.find('X') {
+        if position != 9 || digits.len() != 10 {
+            return Err(IsbnError::InvalidCharacter('X'));
+        }
+    }
+    Ok(digits)
+}
+
+/// Returns the check digit for the first 9 digits of an ISBN-10.
+fn isbn10_check_digit(digits: &str) -> u8 {
+    let sum: u32 = digits
+        .bytes()
+        .zip((2..=10).rev())
+        .map(|(digit, weight)| u32::from(digit - b'0') * weight)
+        .sum();
+    match (11 - sum % 11) % 11 {
+        10 => b'X',
+        check_digit => b'0' + check_digit as u8,
+    }
+}
+
+/// Returns the check digit for the first 12 digits of an ISBN-13.
+fn isbn13_check_digit(digits: &str) -> u8 {
+    let sum: u32 = digits
+        .bytes()
+        .take(12)
+        .zip([1, 3].iter().cycle())
+        .map(|(digit, weight)| u32::from(digit - b'0') * weight)
+        .sum();
+    b'0' + ((10 - sum % 10) % 10) as u8
+}
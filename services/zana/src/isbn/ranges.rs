@@ -0,0 +1,120 @@
+/*!
+Registration group and registrant ranges used to hyphenate ISBNs.
+
+Ranges are embedded from `ranges.txt`, which is generated from the range data published by the
+International ISBN Agency, and are parsed once when they are first used.
+ */
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const RANGES: &str = include_str!("ranges.txt");
+/// Number of digits that ranges are defined for, after a prefix or a registration group.
+const RANGE_DIGITS: usize = 7;
+
+/// Rules of an EAN.UCC prefix (e.g. `978`) or a registration group (e.g. `978-0`).
+pub(crate) struct RangeGroup {
+    pub(crate) agency: String,
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    start: u32,
+    end: u32,
+    length: usize,
+}
+
+pub(crate) struct Ranges {
+    prefixes: HashMap<String, RangeGroup>,
+    groups: HashMap<String, RangeGroup>,
+}
+
+impl RangeGroup {
+    /// Returns the length of the element that starts at the beginning of `digits`,
+    /// or [`None`](None) if the range the digits fall in is not in use.
+    pub(crate) fn element_length(&self, digits: &str) -> Option<usize> {
+        let mut padded = String::from(digits.get(..RANGE_DIGITS).unwrap_or(digits));
+        while padded.len() < RANGE_DIGITS {
+            padded.push('0');
+        }
+        let value: u32 = padded.parse().ok()?;
+        self.rules
+            .iter()
+            .find(|rule| rule.start <= value && value <= rule.end)
+            .map(|rule| rule.length)
+            .filter(|length| *length > 0)
+    }
+}
+
+impl Ranges {
+    /// Returns the rules for the given EAN.UCC prefix, such as `978`.
+    pub(crate) fn prefix(&self, prefix: &str) -> Option<&RangeGroup> {
+        self.prefixes.get(prefix)
+    }
+
+    /// Returns the rules for the given registration group, such as `978-0`.
+    pub(crate) fn group(&self, group: &str) -> Option<&RangeGroup> {
+        self.groups.get(group)
+    }
+
+    fn parse(data: &str) -> Result<Self, String> {
+        let mut prefixes = HashMap::new();
+        let mut groups = HashMap::new();
+        let mut current: Option<(bool, String, RangeGroup)> = None;
+
+        for (index, line) in data.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let columns: Vec<&str> = line.split('\t').collect();
+            match columns.as_slice() {
+                [kind @ ("prefix" | "group"), name, agency] => {
+                    if let Some((is_prefix, name, group)) = current.take() {
+                        if is_prefix {
+                            prefixes.insert(name, group);
+                        } else {
+                            groups.insert(name, group);
+                        }
+                    }
+                    let group = RangeGroup {
+                        agency: String::from(*agency),
+                        rules: Vec::new(),
+                    };
+                    current = Some((*kind == "prefix", String::from(*name), group));
+                }
+                [range, length] => {
+                    let rule = parse_rule(range, length)
+                        .ok_or_else(|| format!("invalid rule on line {}", index + 1))?;
+                    match current.as_mut() {
+                        Some((_, _, group)) => group.rules.push(rule),
+                        None => return Err(format!("rule without group on line {}", index + 1)),
+                    }
+                }
+                _ => return Err(format!("invalid line {}", index + 1)),
+            }
+        }
+        if let Some((is_prefix, name, group)) = current {
+            if is_prefix {
+                prefixes.insert(name, group);
+            } else {
+                groups.insert(name, group);
+            }
+        }
+        Ok(Self { prefixes, groups })
+    }
+}
+
+fn parse_rule(range: &str, length: &str) -> Option<Rule> {
+    let (start, end) = range.split_once('-')?;
+    Some(Rule {
+        start: start.parse().ok()?,
+        end: end.parse().ok()?,
+        length: length.parse().ok()?,
+    })
+}
+
+/// Returns the embedded ranges, parsing them on first use.
+pub(crate) fn ranges() -> &'static Ranges {
+    static RANGES_DATA: OnceLock<Ranges> = OnceLock::new();
+    RANGES_DATA.get_or_init(|| Ranges::parse(RANGES).expect("embedded ISBN ranges are invalid"))
+}
@@ -6,28 +6,37 @@ book related APIs:
 
 Data is retrieved through calls being made by implementations of [`BookClient`](trait@BookClient).
 
+Books are queried by ISBN using [`Isbn`](struct@isbn::Isbn), which validates ISBN-10 and ISBN-13
+values before any request is made.
+
 ## Client for Google Books API
 
 When querying from Google Books API, one API calls is made to the _volumes_ endpoint,
 to retrieve data by ISBN of a book. In cases where no data is found by ISBN,
 then book title and author are used as a backup.
 [`Client`](struct@googlebooks::Client) is used to query data from Google Books API.
+A second call can be made to fetch the volume by its ID, for details such as the full
+description, as set by the [detail policy](googlebooks::ClientBuilder::detail_policy).
+
+Authors and titles are [normalized](normalize) before they are queried and matched, so that
+e.g. `Kadare, Ismail` matches `Ismail Kadare` and `Kështjella` matches `Keshtjella`.
 
 ### Example
 
 ```
 use zana::{Book, BookClient, ClientError};
 use zana::googlebooks::Client;
+use zana::isbn::Isbn;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let api_url = "https://www.googleapis.com";
     let api_key = "YOUR-API-KEY";
-    let isbn = "9780316387316";
+    let isbn: Isbn = "978-0-316-38731-6".parse()?;
 
     let client = Client::new(api_key, api_url)?;
 
-    match client.book_by_isbn(isbn).await {
+    match client.book_by_isbn(&isbn).await {
         Ok(book) => println!("book found ({}: {:?})", isbn, &book),
         Err(err) => eprintln!("could not fetch book by ISBN {:?}", err),
     };
@@ -42,20 +51,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 2. Fetch `work` of the book (A _work_ here being a logical collection of similar editions)
 3. Fetch ratings
 
+Books are queried by author and title with the `search` endpoint, followed by the same calls
+for the edition found.
+
+Books of several ISBNs can be queried at once with [`books_by_isbns`](BookClient::books_by_isbns),
+in which case the first call is made once for up to 50 ISBNs.
+
 ### Example
 
 ```
 use zana::{Book, BookClient, ClientError};
+use zana::isbn::Isbn;
 use zana::openlibrary::Client;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let api_url = "https://openlibrary.org";
-    let isbn = "9780316387316";
+    let isbn: Isbn = "978-0-316-38731-6".parse()?;
 
     let client = Client::new(api_url)?;
 
-    match client.book_by_isbn(isbn).await {
+    match client.book_by_isbn(&isbn).await {
         Ok(book) => println!("book found ({}: {:?})", isbn, &book),
         Err(err) => eprintln!("could not fetch book by ISBN {:?}", err),
     };
@@ -70,38 +86,227 @@ is returned from clients.
 
 For status codes that are not 200, [ClientError](enum@ClientError) is returned with more
 information about the source of the error.
+
+## Configuring clients
+
+Both clients can be created with a builder, to set timeouts, a user agent suffix, a proxy and
+default headers, or to share an existing [`reqwest::Client`](reqwest::Client) between clients.
+
+```
+use std::time::Duration;
+use zana::openlibrary::Client;
+
+# fn main() -> Result<(), zana::ClientError> {
+let client = Client::builder("https://openlibrary.org")
+    .timeout(Duration::from_secs(5))
+    .connect_timeout(Duration::from_secs(2))
+    .user_agent_suffix("my-app/1.0")
+    .build()?;
+# Ok(())
+# }
+```
+
+## Wrapping clients
+
+Clients can be wrapped by other implementations of [`BookClient`](trait@BookClient), that add
+behaviour on top of the wrapped client:
+- [`RetryingClient`](struct@retry::RetryingClient) retries failed requests with exponential backoff
+- [`CachingClient`](struct@cache::CachingClient) caches returned books in memory
+- [`CircuitBreaker`](struct@circuit::CircuitBreaker) rejects requests while the wrapped client keeps failing
+- [`RateLimiter`](struct@ratelimit::RateLimiter) limits the rate of requests to stay under quotas
+- [`CoalescingClient`](struct@coalesce::CoalescingClient) shares a request between concurrent identical lookups
+
+Several clients can also be combined into one:
+- [`FallbackClient`](struct@fallback::FallbackClient) queries the clients in turn until one of
+  them returns the book
+- [`MergingClient`](struct@merge::MergingClient) queries all clients and merges their books
+  field by field
+
+Ratings of several providers can be combined into a single rating, weighted by the number of
+ratings of each provider, with [`AggregatedRating`](struct@rating::AggregatedRating).
+
+## Features
+
+- `serde`: derives `Serialize` and `Deserialize` for [Book](struct@Book), [Rating](struct@Rating)
+  and the other public data types. Field names are serialized in `snake_case` as they are
+  named in the types, and are kept stable between releases.
+- `disk-cache`: adds `DiskStore` to the [`cache`](cache) module, a store for [`CachingClient`](struct@cache::CachingClient)
+  that keeps cached books in a file between runs. Enables the `serde` feature.
 */
 
 extern crate core;
 
-use std::time::Duration;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
 use thiserror::Error;
 
+use crate::isbn::Isbn;
+
+pub mod cache;
+pub mod circuit;
+pub mod coalesce;
+pub mod fallback;
 pub mod googlebooks;
+mod http;
+pub mod isbn;
+pub mod merge;
+pub mod normalize;
 pub mod openlibrary;
+pub mod ratelimit;
+pub mod rating;
+pub mod retry;
 
 /// An error that occurs for implementations of [BookClient][trait@BookClient].
 ///
 /// The error will contain different variants to make handling of errors easier.
 /// Some specific Http status codes (e.g. `429` _Too Many Requests_ or `404` _Not Found_) will have
 /// their own variant because of their importance or the custom handling they will require.
-#[derive(Error, Debug)]
+///
+/// [is_transient](ClientError::is_transient) and [is_retryable](ClientError::is_retryable)
+/// can be used to decide whether a failed request is worth repeating.
+///
+/// Errors can be cloned, so that a single result can be shared by several callers.
+/// Errors coming from [reqwest](reqwest) are shared between clones.
+#[derive(Error, Debug, Clone)]
 pub enum ClientError {
-    /// Occurs for any error that comes from [reqwest](reqwest) crate. This will include errors
-    /// when a timeout is reached, or no connection can be made to the specified endpoint.
+    /// Occurs for any error that comes from [reqwest](reqwest) crate, that is not a timeout,
+    /// connection or deserialization error.
     #[error("error coming from internal http client")]
-    InternalClient(#[from] reqwest::Error),
+    InternalClient(#[source] Arc<reqwest::Error>),
+    /// Occurs when a request is not completed before the timeout is reached.
+    #[error("request to external service timed out")]
+    Timeout(#[source] Arc<reqwest::Error>),
+    /// Occurs when no connection can be made to the external service.
+    #[error("could not connect to external service")]
+    Connect(#[source] Arc<reqwest::Error>),
+    /// Occurs when the response of the external service cannot be deserialized.
+    #[error("could not deserialize response from external service: {0}")]
+    Deserialization(String),
+    /// Occurs when the external service rejects the request because of missing or invalid
+    /// credentials, such as an invalid API key. Contains the status code and response body.
+    #[error("unauthorized request to external service")]
+    Unauthorized(u16, String),
+    /// Occurs when the input given to the client is not valid, and no request is made.
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
     /// Occurs when a 429 or (403 in some clients) status code is returned from the response.
+    /// Contains information about when the rate limit resets, if the external service provides it.
     #[error("rate limit exceeded for external service")]
-    RateLimitExceeded,
+    RateLimitExceeded(RateLimit),
     /// Occurs when queried book is not found
     #[error("book is not found")]
     NotFound,
     /// Occurs for any response that is not 200, 404 or 429 (403 included for some clients).
     #[error("generic http error that contains status code and response body")]
     Http(u16, String),
+    /// Occurs when requests are rejected without being sent, because the external service
+    /// has failed repeatedly. Contains the time left until requests are allowed again.
+    #[error("circuit is open for external service")]
+    CircuitOpen(Duration),
+    /// Occurs when the client does not support the kind of lookup, and no request is made.
+    /// Supported lookups are returned by [capabilities](BookClient::capabilities).
+    #[error("{0} lookup is not supported by the client")]
+    Unsupported(LookupKind),
+}
+
+impl ClientError {
+    /// Returns true if the error is caused by a condition that is expected to clear
+    /// on its own, such as a timeout, a connection failure, an exceeded rate limit or a
+    /// `408`/`5xx` status code returned from the external service.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ClientError::Timeout(_)
+            | ClientError::Connect(_)
+            | ClientError::RateLimitExceeded(_)
+            | ClientError::CircuitOpen(_) => true,
+            ClientError::Http(status_code, _) => is_transient_status(*status_code),
+            _ => false,
+        }
+    }
+
+    /// Returns true if repeating the same request, after a short backoff, may succeed.
+    ///
+    /// All transient errors are retryable, except for [RateLimitExceeded](ClientError::RateLimitExceeded),
+    /// since repeating a request before the rate limit resets only consumes more of the quota,
+    /// and [CircuitOpen](ClientError::CircuitOpen), since requests are rejected until the circuit
+    /// closes.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(
+            self,
+            ClientError::RateLimitExceeded(_) | ClientError::CircuitOpen(_)
+        ) && self.is_transient()
+    }
+
+    /// Returns how long to wait before making another request, if the error is
+    /// [RateLimitExceeded](ClientError::RateLimitExceeded) and the external service provided it,
+    /// or if the error is [CircuitOpen](ClientError::CircuitOpen).
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ClientError::RateLimitExceeded(rate_limit) => rate_limit.retry_after,
+            ClientError::CircuitOpen(retry_after) => Some(*retry_after),
+            _ => None,
+        }
+    }
+}
+
+/// Information about an exceeded rate limit, as returned from the external service.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Time to wait before making another request, taken from the `Retry-After` header
+    /// or from provider-specific hints in the response body.
+    pub retry_after: Option<Duration>,
+    /// Provider-specific reason for the exceeded limit, e.g. `dailyLimitExceeded` for Google Books
+    pub reason: Option<String>,
+}
+
+impl RateLimit {
+    /// Returns rate limit information from the headers of a response.
+    ///
+    /// The `Retry-After` header is supported both as a number of seconds and as an HTTP-date.
+    pub fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let retry_after = headers
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, SystemTime::now()));
+        Self {
+            retry_after,
+            reason: None,
+        }
+    }
+}
+
+/// Parses the value of a `Retry-After` header, relative to `now` for HTTP-date values.
+///
+/// Dates in the past result in a duration of zero.
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            ClientError::Timeout(Arc::new(err))
+        } else if err.is_connect() {
+            ClientError::Connect(Arc::new(err))
+        } else if err.is_decode() {
+            ClientError::Deserialization(err.to_string())
+        } else {
+            ClientError::InternalClient(Arc::new(err))
+        }
+    }
+}
+
+fn is_transient_status(status_code: u16) -> bool {
+    matches!(status_code, 408 | 500 | 502 | 503 | 504)
 }
 
 /// Book data retrieved from third-party services supported by the crate.
@@ -112,20 +317,43 @@ pub enum ClientError {
 /// [page_count](struct@Book.page_count) is returned as 0 if its not provided
 /// by the third-party service.
 ///
-/// [description](struct@Book.description) is returned as an empty string if its not
-/// provided by the third-party service.
+/// Text data like [title](struct@Book.title) or [description](struct@Book.description)
+/// is returned as an empty string, and list data like [authors](struct@Book.authors) is
+/// returned empty, if its not provided by the third-party service.
 ///
 /// [rating](struct@Book.rating) is optional, since in some cases books either may not have
 /// rating data available yet, or other third-party services that can be added in the future
 /// may not provide ratings at all.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Book {
+    /// Book title, empty if not provided by the third-party service
+    pub title: String,
+    /// Names of the book authors, in the order given by the third-party service
+    pub authors: Vec<String>,
+    /// Publisher name, empty if not provided by the third-party service
+    pub publisher: String,
+    /// Publishing date as returned by the third-party service (e.g. `2015`, `2015-09-08`
+    /// or `Sep 08, 2015`)
+    pub published_date: String,
+    /// Language code as returned by the third-party service. Google Books uses two-letter
+    /// ISO 639-1 codes (e.g. `en`), while OpenLibrary uses three-letter MARC codes (e.g. `eng`)
+    pub language: String,
+    /// Categories or subjects the book belongs to
+    pub categories: Vec<String>,
+    /// Identifiers of the book, such as ISBN-10 and ISBN-13
+    pub identifiers: Vec<Identifier>,
     /// Number of pages, 0 if not provided by the third-party service
     pub page_count: u32,
     /// Book description, empty if not provided by the third-party service
     pub description: String,
     /// Link to view the book at the third-party service
     pub provider_link: String,
+    /// Rating of the book, left out when serialized if the book has no rating
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub rating: Option<Rating>,
 }
 
@@ -133,18 +361,54 @@ pub struct Book {
 ///
 /// This data holds only the average rating as a floating point, and the number of
 /// ratings given.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rating {
     pub average_rating: f32,
     pub ratings_count: u32,
 }
 
+/// Type of [Identifier](struct@Identifier) attached to a book.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum IdentifierType {
+    Isbn10,
+    Isbn13,
+    Issn,
+    /// Library of Congress Control Number
+    Lccn,
+    /// OCLC (WorldCat) number
+    Oclc,
+    /// Any identifier type not known by the crate, with the name given by the third-party service
+    Other(String),
+}
+
+/// Identifier of a book, as returned by the third-party service.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Identifier {
+    pub identifier_type: IdentifierType,
+    pub value: String,
+}
+
 impl Book {
     /// Returns a Book with defaults for optional data.
     ///
     /// - rating is optional, and by default is [`None`](None)
+    /// - bibliographic data (title, authors, publisher etc.) is empty by default
     pub fn new(page_count: u32, description: &str, provider_link: &str) -> Self {
         Self {
+            title: String::new(),
+            authors: Vec::new(),
+            publisher: String::new(),
+            published_date: String::new(),
+            language: String::new(),
+            categories: Vec::new(),
+            identifiers: Vec::new(),
             page_count,
             description: String::from(description),
             provider_link: String::from(provider_link),
@@ -163,6 +427,14 @@ impl Book {
         book.rating = Some(rating);
         book
     }
+
+    /// Returns the value of the first identifier of the given type, if the book has one.
+    pub fn identifier(&self, identifier_type: &IdentifierType) -> Option<&str> {
+        self.identifiers
+            .iter()
+            .find(|identifier| &identifier.identifier_type == identifier_type)
+            .map(|identifier| identifier.value.as_str())
+    }
 }
 
 impl Rating {
@@ -179,6 +451,16 @@ impl Rating {
     }
 }
 
+impl Identifier {
+    /// Returns a new identifier of the given type.
+    pub fn new(identifier_type: IdentifierType, value: &str) -> Self {
+        Self {
+            identifier_type,
+            value: String::from(value),
+        }
+    }
+}
+
 /// A trait that describes implementations of API clients for third-party API services.
 ///
 /// This trait provides a way to access different APIs and returns the data in a standard format.
@@ -186,8 +468,9 @@ impl Rating {
 /// are differently configured to retrieve the data.
 /// This trait provides different ways of which the data can be retrieved.
 ///
+/// Clients declare the ways they support with [capabilities](BookClient::capabilities).
 /// In cases where a third-party API does not support one of the ways to retrieve data,
-/// then `unimplemented!` is used, to indicate that
+/// [Unsupported](ClientError::Unsupported) is returned, to indicate that
 /// a [Book](struct@Book) cannot not be queried using that functionality.
 ///
 /// When there's an error with communication/network, and the request cannot be completed,
@@ -196,19 +479,150 @@ impl Rating {
 #[async_trait]
 pub trait BookClient {
     /// Returns a book from the given ISBN.
-    async fn book_by_isbn(&self, isbn: &str) -> Result<Book, ClientError>;
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError>;
 
     /// Returns a book from author and title
     async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError>;
+
+    /// Returns the kinds of lookups supported by the client.
+    ///
+    /// By default lookups by ISBN and by author and title are supported.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(&[LookupKind::Isbn, LookupKind::AuthorAndTitle])
+    }
+
+    /// Returns a result for each of the given ISBNs, in the same order.
+    ///
+    /// Each ISBN has its own result, so a book that cannot be returned does not fail the lookup
+    /// of the other books.
+    ///
+    /// By default each book is queried with [`book_by_isbn`](BookClient::book_by_isbn), making
+    /// up to [`BATCH_CONCURRENCY`](BATCH_CONCURRENCY) requests at a time. Clients of services
+    /// that can return several books in one request override this method, and wrappers of
+    /// clients, such as [`RetryingClient`](crate::retry::RetryingClient), forward the ISBNs
+    /// to the wrapped client so that they are still looked up together.
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult>
+    where
+        Self: Sync,
+    {
+        // Futures are created up front, since the compiler cannot prove that a stream mapping
+        // borrowed ISBNs to futures is Send
+        let lookups: Vec<_> = isbns
+            .iter()
+            .map(|isbn| async move { IsbnResult::new(isbn, self.book_by_isbn(isbn).await) })
+            .collect();
+        stream::iter(lookups)
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
 }
 
-fn create_http_client() -> Result<reqwest::Client, reqwest::Error> {
-    let version: &str = option_env!("CARGO_PKG_VERSION").unwrap_or("1.0.0");
+/// Kind of lookup made with a [`BookClient`](trait@BookClient).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LookupKind {
+    /// Lookup by ISBN, with [`book_by_isbn`](BookClient::book_by_isbn) and
+    /// [`books_by_isbns`](BookClient::books_by_isbns)
+    Isbn,
+    /// Lookup by author and title, with [`book`](BookClient::book)
+    AuthorAndTitle,
+    /// Lookup by other identifiers of a book, such as the ID of the book at the provider
+    Identifiers,
+    /// Search returning several books that match a query
+    Search,
+    /// Lookup of the other editions of a book, such as its translations
+    Editions,
+}
 
-    reqwest::Client::builder()
-        .gzip(true)
-        .user_agent(format!("zana/{} (gzip)", version))
-        .timeout(Duration::from_secs(30))
-        .connect_timeout(Duration::from_secs(30))
-        .build()
+impl fmt::Display for LookupKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LookupKind::Isbn => "ISBN",
+            LookupKind::AuthorAndTitle => "author and title",
+            LookupKind::Identifiers => "identifier",
+            LookupKind::Search => "search",
+            LookupKind::Editions => "editions",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Kinds of lookups supported by a [`BookClient`](trait@BookClient).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    lookups: Vec<LookupKind>,
+}
+
+impl Capabilities {
+    /// Returns capabilities that support the given kinds of lookups.
+    pub fn new(lookups: &[LookupKind]) -> Self {
+        Self::default().with(lookups)
+    }
+
+    /// Returns the capabilities with the given kinds of lookups supported as well.
+    pub fn with(mut self, lookups: &[LookupKind]) -> Self {
+        for lookup in lookups {
+            if !self.lookups.contains(lookup) {
+                self.lookups.push(*lookup);
+            }
+        }
+        self
+    }
+
+    /// Returns the capabilities that support every lookup supported by either capabilities.
+    pub fn union(self, other: &Capabilities) -> Self {
+        self.with(&other.lookups)
+    }
+
+    /// Returns true if the kind of lookup is supported.
+    pub fn supports(&self, lookup: LookupKind) -> bool {
+        self.lookups.contains(&lookup)
+    }
+
+    /// Returns the supported kinds of lookups.
+    pub fn lookups(&self) -> &[LookupKind] {
+        &self.lookups
+    }
+}
+
+/// Maximum number of books queried at a time by
+/// [`books_by_isbns`](BookClient::books_by_isbns), when a client makes a request for each book.
+pub const BATCH_CONCURRENCY: usize = 4;
+
+/// Result of a single ISBN of a batch lookup made with
+/// [`books_by_isbns`](BookClient::books_by_isbns).
+#[derive(Debug, Clone)]
+pub struct IsbnResult {
+    pub isbn: Isbn,
+    pub result: Result<Book, ClientError>,
+}
+
+impl IsbnResult {
+    /// Returns a new result of the given ISBN.
+    pub fn new(isbn: &Isbn, result: Result<Book, ClientError>) -> Self {
+        Self {
+            isbn: isbn.clone(),
+            result,
+        }
+    }
+}
+
+/// Shared clients, e.g. a client used by more than one wrapper, are clients as well.
+#[async_trait]
+impl<C: BookClient + Send + Sync + ?Sized> BookClient for Arc<C> {
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        (**self).book_by_isbn(isbn).await
+    }
+
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        (**self).book(author, title).await
+    }
+
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        (**self).books_by_isbns(isbns).await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        (**self).capabilities()
+    }
 }
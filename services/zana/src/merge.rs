@@ -0,0 +1,298 @@
+/*!
+Combines the books returned by several providers into a single book using the
+[`MergingClient`](struct@MergingClient) implementation of [`BookClient`](trait@BookClient).
+
+All providers are queried concurrently, and each field of the returned book is taken from one of
+the books returned by the providers, based on the [`FieldPolicy`](enum@FieldPolicy) of the field
+in the [`MergePolicy`](struct@MergePolicy).
+
+Providers that fail, or do not support the kind of lookup, are skipped, as long as at least one
+provider returns the book.
+
+## Example
+
+```
+use zana::fallback::Provider;
+use zana::merge::{MergePolicy, MergingClient};
+use zana::{googlebooks, openlibrary};
+
+# fn main() -> Result<(), zana::ClientError> {
+let client = MergingClient::new(
+    vec![
+        Provider::new(
+            "googlebooks",
+            googlebooks::Client::new("<api_key>", "https://www.googleapis.com")?,
+        ),
+        Provider::new("openlibrary", openlibrary::Client::new("https://openlibrary.org")?),
+    ],
+    MergePolicy::default(),
+);
+# Ok(())
+# }
+```
+ */
+use async_trait::async_trait;
+use futures::future::join_all;
+
+use crate::fallback::{capabilities_of, Provider};
+use crate::isbn::Isbn;
+use crate::{Book, BookClient, Capabilities, ClientError, IsbnResult, LookupKind, Rating};
+
+/// How the value of a field is chosen from the books returned by the providers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldPolicy {
+    /// The first value that is not empty, in the order the providers are given
+    FirstNonEmpty,
+    /// The longest value, such as the longest text, the list with most items, the highest
+    /// page count or the rating with most ratings. Ties go to the first provider.
+    Longest,
+    /// The value from the provider with the given name, or the first value that is not empty
+    /// if that provider has no value
+    Preferred(String),
+}
+
+/// Policies of each field of the merged [`Book`](struct@Book).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergePolicy {
+    pub title: FieldPolicy,
+    pub authors: FieldPolicy,
+    pub publisher: FieldPolicy,
+    pub published_date: FieldPolicy,
+    pub language: FieldPolicy,
+    pub categories: FieldPolicy,
+    pub identifiers: FieldPolicy,
+    pub page_count: FieldPolicy,
+    pub description: FieldPolicy,
+    pub provider_link: FieldPolicy,
+    pub rating: FieldPolicy,
+}
+
+impl Default for MergePolicy {
+    /// Returns a policy that takes the longest description, the rating with most ratings,
+    /// and the first value that is not empty for every other field.
+    fn default() -> Self {
+        Self {
+            title: FieldPolicy::FirstNonEmpty,
+            authors: FieldPolicy::FirstNonEmpty,
+            publisher: FieldPolicy::FirstNonEmpty,
+            published_date: FieldPolicy::FirstNonEmpty,
+            language: FieldPolicy::FirstNonEmpty,
+            categories: FieldPolicy::FirstNonEmpty,
+            identifiers: FieldPolicy::FirstNonEmpty,
+            page_count: FieldPolicy::FirstNonEmpty,
+            description: FieldPolicy::Longest,
+            provider_link: FieldPolicy::FirstNonEmpty,
+            rating: FieldPolicy::Longest,
+        }
+    }
+}
+
+/// Value of a field that can be empty, and compared by its size.
+trait FieldValue: Clone + Default {
+    fn is_empty(&self) -> bool;
+    fn size(&self) -> usize;
+}
+
+impl FieldValue for String {
+    fn is_empty(&self) -> bool {
+        self.trim().is_empty()
+    }
+
+    fn size(&self) -> usize {
+        self.trim().chars().count()
+    }
+}
+
+impl<T: Clone> FieldValue for Vec<T> {
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl FieldValue for u32 {
+    fn is_empty(&self) -> bool {
+        *self == 0
+    }
+
+    fn size(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl FieldValue for Option<Rating> {
+    fn is_empty(&self) -> bool {
+        self.is_none()
+    }
+
+    fn size(&self) -> usize {
+        self.as_ref()
+            .map_or(0, |rating| rating.ratings_count as usize)
+    }
+}
+
+/// Returns the value of the field based on the policy, from books in the order of the providers.
+fn pick<T: FieldValue>(books: &[(&str, Book)], policy: &FieldPolicy, field: fn(&Book) -> &T) -> T {
+    let mut values = books
+        .iter()
+        .map(|(provider, book)| (*provider, field(book)))
+        .filter(|(_, value)| !value.is_empty());
+    let value = match policy {
+        FieldPolicy::FirstNonEmpty => values.next().map(|(_, value)| value),
+        FieldPolicy::Longest => values.map(|(_, value)| value).reduce(|longest, value| {
+            if value.size() > longest.size() {
+                value
+            } else {
+                longest
+            }
+        }),
+        FieldPolicy::Preferred(preferred) => {
+            let values: Vec<(&str, &T)> = values.collect();
+            values
+                .iter()
+                .find(|(provider, _)| provider == preferred)
+                .or_else(|| values.first())
+                .map(|(_, value)| *value)
+        }
+    };
+    value.cloned().unwrap_or_default()
+}
+
+/// Returns how useful the error is to the caller, when no provider returns the book.
+///
+/// An error of a provider that failed is more useful than a book not being found,
+/// which is more useful than a provider not supporting the lookup.
+fn error_rank(err: &ClientError) -> u8 {
+    match err {
+        ClientError::Unsupported(_) => 0,
+        ClientError::NotFound => 1,
+        _ => 2,
+    }
+}
+
+/// A [`BookClient`](trait@BookClient) that queries all providers and merges their books.
+pub struct MergingClient {
+    providers: Vec<Provider>,
+    policy: MergePolicy,
+}
+
+impl MergingClient {
+    /// Returns a new client that merges books of the providers, in the given order,
+    /// based on the policy.
+    pub fn new(providers: Vec<Provider>, policy: MergePolicy) -> Self {
+        Self { providers, policy }
+    }
+
+    /// Returns the providers, in the order they are merged.
+    pub fn providers(&self) -> &[Provider] {
+        &self.providers
+    }
+
+    fn merge(&self, results: Vec<Result<Book, ClientError>>) -> Result<Book, ClientError> {
+        let mut books = Vec::new();
+        let mut error = None;
+        for (provider, result) in self.providers.iter().zip(results) {
+            match result {
+                Ok(book) => books.push((provider.name(), book)),
+                Err(err) => {
+                    log::debug!(
+                        "provider {} did not return a book: {:?}",
+                        provider.name(),
+                        err
+                    );
+                    let is_more_useful = match &error {
+                        Some(error) => error_rank(&err) > error_rank(error),
+                        None => true,
+                    };
+                    if is_more_useful {
+                        error = Some(err);
+                    }
+                }
+            }
+        }
+        if books.is_empty() {
+            return Err(error.unwrap_or(ClientError::NotFound));
+        }
+
+        let policy = &self.policy;
+        let mut book = Book::new(
+            pick(&books, &policy.page_count, |book| &book.page_count),
+            &pick(&books, &policy.description, |book| &book.description),
+            &pick(&books, &policy.provider_link, |book| &book.provider_link),
+        );
+        book.title = pick(&books, &policy.title, |book| &book.title);
+        book.authors = pick(&books, &policy.authors, |book| &book.authors);
+        book.publisher = pick(&books, &policy.publisher, |book| &book.publisher);
+        book.published_date = pick(&books, &policy.published_date, |book| &book.published_date);
+        book.language = pick(&books, &policy.language, |book| &book.language);
+        book.categories = pick(&books, &policy.categories, |book| &book.categories);
+        book.identifiers = pick(&books, &policy.identifiers, |book| &book.identifiers);
+        book.rating = pick(&books, &policy.rating, |book| &book.rating);
+        Ok(book)
+    }
+}
+
+#[async_trait]
+impl BookClient for MergingClient {
+    /// Returns a book by ISBN, merged from the books returned by all providers.
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        let requests = self.providers.iter().map(|provider| async move {
+            provider
+                .client_for(LookupKind::Isbn)?
+                .book_by_isbn(isbn)
+                .await
+        });
+        self.merge(join_all(requests).await)
+    }
+
+    /// Returns a book by author and title, merged from the books returned by all providers.
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        let requests = self.providers.iter().map(|provider| async move {
+            provider
+                .client_for(LookupKind::AuthorAndTitle)?
+                .book(author, title)
+                .await
+        });
+        self.merge(join_all(requests).await)
+    }
+
+    /// Returns a result for each of the ISBNs, merged from the books returned by all providers.
+    ///
+    /// The ISBNs are sent to each provider as a single request.
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        let requests = self.providers.iter().map(|provider| async move {
+            match provider.client_for(LookupKind::Isbn) {
+                Ok(client) => client
+                    .books_by_isbns(isbns)
+                    .await
+                    .into_iter()
+                    .map(|result| result.result)
+                    .collect(),
+                Err(err) => vec![Err(err); isbns.len()],
+            }
+        });
+        let mut provider_results: Vec<_> = join_all(requests)
+            .await
+            .into_iter()
+            .map(Vec::into_iter)
+            .collect();
+        isbns
+            .iter()
+            .map(|isbn| {
+                let results = provider_results
+                    .iter_mut()
+                    .map(|results| results.next().unwrap_or(Err(ClientError::NotFound)))
+                    .collect();
+                IsbnResult::new(isbn, self.merge(results))
+            })
+            .collect()
+    }
+
+    /// Returns the lookups supported by any of the providers.
+    fn capabilities(&self) -> Capabilities {
+        capabilities_of(&self.providers)
+    }
+}
@@ -0,0 +1,165 @@
+/*!
+Normalizes titles and author names, and scores how similar they are, so that books can be
+matched regardless of trivial differences in how they are written.
+
+Text is normalized by:
+- folding it to lowercase ASCII where possible, so that `Kështjella` matches `Keshtjella`
+  and `Çajupi` matches `Cajupi`
+- replacing punctuation with spaces, and collapsing whitespace
+- reordering author names written as `Surname, Name`, so that `Kadare, Ismail` matches
+  `Ismail Kadare`
+- removing stop words from titles, such as `the` or `dhe`, unless the title has only stop words
+
+Similarity is scored with the Sørensen–Dice coefficient of the character bigrams of the
+normalized texts, which tolerates alternate spellings such as `Kadareja` and `Kadare`.
+Books found by author and title are scored with [`match_score`](match_score), and only returned
+when their score is at least a match threshold, such as
+[`DEFAULT_MATCH_THRESHOLD`](DEFAULT_MATCH_THRESHOLD).
+
+## Example
+
+```
+use zana::normalize;
+
+assert_eq!("ismail kadare", normalize::normalize_author("Kadare, Ismail"));
+assert_eq!("keshtjella", normalize::normalize_title("Kështjella"));
+assert_eq!(1.0, normalize::author_similarity("Kadare, Ismail", "Ismail Kadare"));
+assert!(normalize::title_similarity("The Blade Itself", "Blade itself!") > 0.9);
+```
+ */
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Words removed from titles before they are compared, in English and Albanian
+/// as folded by [`fold`](fold).
+const STOP_WORDS: [&str; 12] = [
+    "a", "an", "and", "of", "the", "dhe", "e", "i", "me", "ne", "se", "te",
+];
+/// Weight of the title in the match score of a book, when both author and title are requested
+const TITLE_WEIGHT: f32 = 0.6;
+/// Default minimum match score of a book returned for a query by author and title
+pub const DEFAULT_MATCH_THRESHOLD: f32 = 0.6;
+
+/// Returns the text in lowercase, with diacritics removed and letters that do not decompose,
+/// such as `ß` or `ø`, replaced by their closest ASCII letters.
+pub fn fold(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.nfkd().filter(|c| !is_combining_mark(*c)) {
+        match c {
+            'ß' => folded.push_str("ss"),
+            'æ' | 'Æ' => folded.push_str("ae"),
+            'œ' | 'Œ' => folded.push_str("oe"),
+            'þ' | 'Þ' => folded.push_str("th"),
+            'ø' | 'Ø' => folded.push('o'),
+            'đ' | 'Đ' => folded.push('d'),
+            'ł' | 'Ł' => folded.push('l'),
+            c => folded.extend(c.to_lowercase()),
+        }
+    }
+    folded
+}
+
+/// Returns the [folded](fold) text, with punctuation replaced by spaces and whitespace collapsed.
+pub fn normalize(text: &str) -> String {
+    fold(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Returns the [normalized](normalize) title without stop words.
+///
+/// Stop words are kept when the title has no other words, e.g. for a title such as `The`.
+pub fn normalize_title(title: &str) -> String {
+    let title = normalize(title);
+    let words: Vec<&str> = title
+        .split(' ')
+        .filter(|word| !STOP_WORDS.contains(word))
+        .collect();
+    if words.is_empty() {
+        title
+    } else {
+        words.join(" ")
+    }
+}
+
+/// Returns the [normalized](normalize) author name, with `Surname, Name` reordered
+/// to `Name Surname`.
+pub fn normalize_author(author: &str) -> String {
+    match author.split_once(',') {
+        Some((surname, name)) if !name.contains(',') && !name.trim().is_empty() => {
+            normalize(&format!("{} {}", name, surname))
+        }
+        _ => normalize(author),
+    }
+}
+
+/// Returns how similar the titles are, between 0 and 1, once [normalized](normalize_title).
+pub fn title_similarity(a: &str, b: &str) -> f32 {
+    similarity(&normalize_title(a), &normalize_title(b))
+}
+
+/// Returns how similar the author names are, between 0 and 1, once [normalized](normalize_author).
+///
+/// Words of the names are compared in alphabetical order, so that names written as
+/// `Surname Name` without a comma still match.
+pub fn author_similarity(a: &str, b: &str) -> f32 {
+    similarity(
+        &sorted_words(&normalize_author(a)),
+        &sorted_words(&normalize_author(b)),
+    )
+}
+
+/// Returns how well a book with the given title and authors matches the requested author
+/// and title, between 0 and 1.
+///
+/// The title is compared with the title of the book, and the author with the closest author
+/// of the book. When only one of them is requested, the score is based on it alone.
+pub fn match_score(author: &str, title: &str, book_title: &str, book_authors: &[String]) -> f32 {
+    let title_score = title_similarity(title, book_title);
+    let author_score = book_authors
+        .iter()
+        .map(|book_author| author_similarity(author, book_author))
+        .fold(0.0, f32::max);
+
+    match (normalize(author).is_empty(), normalize(title).is_empty()) {
+        (true, _) => title_score,
+        (_, true) => author_score,
+        _ => TITLE_WEIGHT * title_score + (1.0 - TITLE_WEIGHT) * author_score,
+    }
+}
+
+/// Returns the Sørensen–Dice coefficient of the character bigrams of both texts, between 0 and 1.
+///
+/// Texts are compared as they are, so they should be normalized beforehand.
+pub fn similarity(a: &str, b: &str) -> f32 {
+    if a == b {
+        return 1.0;
+    }
+    let a_bigrams = bigrams(a);
+    let mut b_bigrams = bigrams(b);
+    if a_bigrams.is_empty() || b_bigrams.is_empty() {
+        return 0.0;
+    }
+    let total = a_bigrams.len() + b_bigrams.len();
+    let mut shared = 0;
+    for bigram in a_bigrams {
+        if let Some(index) = b_bigrams.iter().position(|other| *other == bigram) {
+            b_bigrams.swap_remove(index);
+            shared += 1;
+        }
+    }
+    (2 * shared) as f32 / total as f32
+}
+
+fn bigrams(text: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+fn sorted_words(text: &str) -> String {
+    let mut words: Vec<&str> = text.split(' ').collect();
+    words.sort_unstable();
+    words.join(" ")
+}
@@ -6,36 +6,202 @@ Three different API calls are made to query all the needed data, and their data
 is then aggregated to return a single book.
 1. At first it queries the book by ISBN.
 2. Then queries the `work` endpoint, to retrieve more data about the book,
-its authors and description.
+   its authors and description.
 3. Queries ratings.
 
+Books are queried by author and title with the `search` endpoint, which returns the works that
+match best and their editions. Works are scored by their title and authors as with
+[`match_score`](crate::normalize::match_score), and an edition of the work with the highest score
+is queried from the `books` endpoint, followed by the work and ratings as for books queried by
+ISBN. If no work has a score of at least the [match threshold](ClientBuilder::match_threshold),
+the book is not found.
+
+Names of the authors are retrieved from the `authors` endpoint, since books and works
+only reference authors by their key.
+
+[`books_by_isbns`](BookClient::books_by_isbns) queries the books of up to 50 ISBNs at once from
+the `/api/books` endpoint, which also returns the names of the authors, so that only works and
+ratings are queried for each book.
+
+Other editions of a book, such as its translations, are listed from the `editions` endpoint
+of its work with [`editions_by_isbn`](Client::editions_by_isbn) or [`editions`](Client::editions).
+
+## Example
+
+```no_run
+use zana::isbn::Isbn;
+use zana::openlibrary::Client;
+
+# #[tokio::main]
+# async fn main() -> Result<(), Box<dyn std::error::Error>> {
+let client = Client::new("https://openlibrary.org")?;
+let isbn: Isbn = "9780316387316".parse()?;
+
+let work_editions = client.editions_by_isbn(&isbn).await?;
+for edition in work_editions.in_language("sq") {
+    println!("Albanian edition {} ({:?})", edition.title, edition.isbns);
+}
+# Ok(())
+# }
+```
+
 See example [here](../index.html#example-1).
  */
 
+use std::collections::HashMap;
+use std::time::Duration;
+
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
 use serde::Deserialize;
 
-use crate::{create_http_client, Book, BookClient, ClientError, Rating};
+use crate::http::{HttpClient, HttpConfig};
+use crate::isbn::Isbn;
+use crate::normalize::{self, DEFAULT_MATCH_THRESHOLD};
+use crate::{
+    Book, BookClient, Capabilities, ClientError, Identifier, IdentifierType, IsbnResult,
+    LookupKind, RateLimit, Rating, BATCH_CONCURRENCY,
+};
 
 const ISBN_PATH: &str = "/isbn";
+const BOOKS_PATH: &str = "/books/";
+const SEARCH_PATH: &str = "/search.json";
+const BOOKS_API_PATH: &str = "/api/books";
 const RATINGS_PATH: &str = "/ratings";
+const LANGUAGES_PATH: &str = "/languages/";
+const WORKS_PATH: &str = "/works/";
+const EDITIONS_PATH: &str = "/editions.json";
+/// Fields of the works returned by the `search` endpoint
+const SEARCH_FIELDS: &str = "title,author_name,cover_edition_key,edition_key";
+/// Number of works fetched for queries by author and title, to choose the best match from
+const MATCH_CANDIDATES: &str = "5";
+/// Number of editions requested at once from the `editions` endpoint
+const EDITIONS_PER_REQUEST: usize = 100;
+/// Keeps the number of requests made for works with a lot of editions bounded
+const MAX_EDITIONS: usize = 1000;
+/// ISO 639-1 codes of languages, and the MARC codes used by OpenLibrary for them
+const LANGUAGE_CODES: [(&str, &str); 24] = [
+    ("ar", "ara"),
+    ("bg", "bul"),
+    ("cs", "cze"),
+    ("da", "dan"),
+    ("de", "ger"),
+    ("el", "gre"),
+    ("en", "eng"),
+    ("es", "spa"),
+    ("fi", "fin"),
+    ("fr", "fre"),
+    ("hr", "hrv"),
+    ("hu", "hun"),
+    ("it", "ita"),
+    ("ja", "jpn"),
+    ("mk", "mac"),
+    ("nl", "dut"),
+    ("no", "nor"),
+    ("pl", "pol"),
+    ("pt", "por"),
+    ("ro", "rum"),
+    ("ru", "rus"),
+    ("sq", "alb"),
+    ("sr", "srp"),
+    ("tr", "tur"),
+];
+/// Keeps the URL of a single `/api/books` request at a reasonable length
+const BIBKEYS_PER_REQUEST: usize = 50;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 struct BookResponse {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    authors: Vec<EditionAuthor>,
+    #[serde(default)]
+    publishers: Vec<String>,
+    publish_date: Option<String>,
+    #[serde(default)]
+    languages: Vec<Key>,
+    #[serde(default)]
+    isbn_10: Vec<String>,
+    #[serde(default)]
+    isbn_13: Vec<String>,
+    #[serde(default)]
+    lccn: Vec<String>,
+    #[serde(default)]
+    oclc_numbers: Vec<String>,
     number_of_pages: Option<u32>,
     works: Vec<WorkIdentifier>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 struct WorkIdentifier {
     key: String,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+struct Key {
+    key: String,
+}
+
+/// Author of an edition, whose name is only returned by the `/api/books` endpoint
+#[derive(Deserialize, Debug, Clone)]
+struct EditionAuthor {
+    key: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SearchResponse {
+    #[serde(default)]
+    docs: Vec<SearchDoc>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SearchDoc {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    author_name: Vec<String>,
+    cover_edition_key: Option<String>,
+    #[serde(default)]
+    edition_key: Vec<String>,
+}
+
+impl SearchDoc {
+    /// Returns the key of the edition shown on the cover of the work, or of any of its editions.
+    fn edition_key(&self) -> Option<&str> {
+        self.cover_edition_key
+            .as_deref()
+            .or_else(|| self.edition_key.first().map(String::as_str))
+    }
+}
+
+/// Book of a single bibkey returned by the `/api/books` endpoint
+#[derive(Deserialize, Debug)]
+struct BibkeyResponse {
+    details: BookResponse,
+}
+
 #[derive(Deserialize, Debug)]
 struct WorkResponse {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    authors: Vec<WorkAuthor>,
+    #[serde(default)]
+    subjects: Vec<String>,
     description: Option<Description>,
 }
 
+#[derive(Deserialize, Debug)]
+struct WorkAuthor {
+    author: Key,
+}
+
+#[derive(Deserialize, Debug)]
+struct AuthorResponse {
+    name: String,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(untagged)]
 enum Description {
@@ -48,6 +214,117 @@ struct DescriptionMap {
     value: String,
 }
 
+#[derive(Deserialize, Debug)]
+struct EditionsResponse {
+    #[serde(default)]
+    size: usize,
+    #[serde(default)]
+    entries: Vec<EditionEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EditionEntry {
+    key: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    languages: Vec<Key>,
+    #[serde(default)]
+    publishers: Vec<String>,
+    publish_date: Option<String>,
+    #[serde(default)]
+    isbn_10: Vec<String>,
+    #[serde(default)]
+    isbn_13: Vec<String>,
+}
+
+/// An edition of a work, e.g. a translation or a reprint of a book.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Edition {
+    /// Key of the edition, e.g. `/books/OL27186692M`
+    pub key: String,
+    /// Title of the edition, empty if not provided
+    pub title: String,
+    /// MARC codes of the languages of the edition, e.g. `alb` or `eng`
+    pub languages: Vec<String>,
+    pub publishers: Vec<String>,
+    /// Publishing date as returned by OpenLibrary (e.g. `2015` or `Sep 08, 2015`)
+    pub publish_date: String,
+    /// Year of the publishing date, if it has one
+    pub publish_year: Option<u16>,
+    /// Valid ISBN-10 and ISBN-13 values of the edition, without duplicates
+    pub isbns: Vec<Isbn>,
+}
+
+impl Edition {
+    /// Returns true if the edition is in the given language, as an ISO 639-1 code (e.g. `sq`)
+    /// or a MARC code (e.g. `alb`).
+    pub fn has_language(&self, language: &str) -> bool {
+        let language = language.to_lowercase();
+        let code = marc_language_code(&language).unwrap_or(&language);
+        self.languages
+            .iter()
+            .any(|edition_language| edition_language == code)
+    }
+}
+
+impl From<EditionEntry> for Edition {
+    fn from(entry: EditionEntry) -> Self {
+        let publish_date = entry.publish_date.unwrap_or_default();
+        let mut isbns: Vec<Isbn> = Vec::new();
+        for isbn in entry.isbn_13.iter().chain(entry.isbn_10.iter()) {
+            match Isbn::parse(isbn) {
+                Ok(isbn) if !isbns.contains(&isbn) => isbns.push(isbn),
+                Ok(_) => {}
+                Err(err) => log::debug!("invalid ISBN {} of edition {}: {}", isbn, &entry.key, err),
+            }
+        }
+        Edition {
+            title: entry.title,
+            languages: entry
+                .languages
+                .iter()
+                .map(|language| language.key.trim_start_matches(LANGUAGES_PATH).to_string())
+                .collect(),
+            publishers: entry.publishers,
+            publish_year: publish_year(&publish_date),
+            publish_date,
+            isbns,
+            key: entry.key,
+        }
+    }
+}
+
+/// Editions of a work, as returned by [`editions`](Client::editions).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WorkEditions {
+    /// Key of the work, e.g. `/works/OL8400950W`
+    pub work_key: String,
+    pub editions: Vec<Edition>,
+}
+
+impl WorkEditions {
+    /// Returns the editions in the given language, as an ISO 639-1 code (e.g. `sq`)
+    /// or a MARC code (e.g. `alb`).
+    pub fn in_language(&self, language: &str) -> Vec<&Edition> {
+        self.editions
+            .iter()
+            .filter(|edition| edition.has_language(language))
+            .collect()
+    }
+}
+
+/// Returns the MARC code used by OpenLibrary for the language with the given ISO 639-1 code,
+/// e.g. `alb` for `sq`, if the language is known by the crate.
+pub fn marc_language_code(language: &str) -> Option<&'static str> {
+    LANGUAGE_CODES
+        .iter()
+        .find(|(iso_code, _)| iso_code.eq_ignore_ascii_case(language))
+        .map(|(_, marc_code)| *marc_code)
+}
+
 #[derive(Deserialize, Debug)]
 struct RatingResponse {
     summary: RatingSummary,
@@ -59,22 +336,101 @@ struct RatingSummary {
     count: Option<u32>,
 }
 
+/// Builder used to configure a [`Client`](struct@Client), created with
+/// [`Client::builder`](Client::builder).
+#[derive(Debug)]
+pub struct ClientBuilder {
+    api_url: String,
+    http_config: HttpConfig,
+    match_threshold: f32,
+}
+
+impl ClientBuilder {
+    /// Sets the timeout of each request, from when it is sent until the response body is read.
+    /// Defaults to 30 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.http_config.timeout = timeout;
+        self
+    }
+
+    /// Sets the timeout for connecting to the API. Defaults to 30 seconds.
+    ///
+    /// Not applied when a shared [`http_client`](ClientBuilder::http_client) is used.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.http_config.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Sets a suffix that is appended to the `zana/{version} (gzip)` user agent.
+    ///
+    /// Not applied when a shared [`http_client`](ClientBuilder::http_client) is used.
+    pub fn user_agent_suffix(mut self, suffix: &str) -> Self {
+        self.http_config.user_agent_suffix = Some(String::from(suffix));
+        self
+    }
+
+    /// Sets a proxy that all requests are sent through.
+    ///
+    /// Not applied when a shared [`http_client`](ClientBuilder::http_client) is used.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.http_config.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets headers that are sent with each request.
+    pub fn default_headers(mut self, headers: reqwest::header::HeaderMap) -> Self {
+        self.http_config.default_headers = headers;
+        self
+    }
+
+    /// Sets an existing HTTP client to send requests with, e.g. to share its connection pool
+    /// between clients. Its own settings are kept, except for the timeout and default headers.
+    pub fn http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_config.http_client = Some(http_client);
+        self
+    }
+
+    /// Sets the minimum score, between 0 and 1, of a work returned for a query by author
+    /// and title. Defaults to [`DEFAULT_MATCH_THRESHOLD`](DEFAULT_MATCH_THRESHOLD).
+    pub fn match_threshold(mut self, match_threshold: f32) -> Self {
+        self.match_threshold = match_threshold;
+        self
+    }
+
+    /// Returns a new client with the configured settings.
+    pub fn build(self) -> Result<Client, ClientError> {
+        Ok(Client {
+            api_url: self.api_url,
+            http_client: self.http_config.build()?,
+            match_threshold: self.match_threshold,
+        })
+    }
+}
+
 /// Client used to retrieve data from OpenLibrary API.
 pub struct Client {
     api_url: String,
-    http_client: reqwest::Client,
+    http_client: HttpClient,
+    match_threshold: f32,
 }
 
 impl Client {
     /// Returns a new client that will make requests to the given API URL.
     ///
     /// No API key is required for OpenLibrary.
+    ///
+    /// Use [`builder`](Client::builder) to change the default settings of the client.
     pub fn new(api_url: &str) -> Result<Self, ClientError> {
-        let http_client = create_http_client()?;
-        Ok(Client {
+        Self::builder(api_url).build()
+    }
+
+    /// Returns a builder for a client that will make requests to the given API URL.
+    pub fn builder(api_url: &str) -> ClientBuilder {
+        ClientBuilder {
             api_url: String::from(api_url),
-            http_client,
-        })
+            http_config: HttpConfig::default(),
+            match_threshold: DEFAULT_MATCH_THRESHOLD,
+        }
     }
 
     fn create_book(
@@ -83,6 +439,7 @@ impl Client {
         book_response: &BookResponse,
         work_response: &WorkResponse,
         rating_response: &RatingResponse,
+        authors: Vec<String>,
     ) -> Book {
         let description = match &work_response.description {
             Some(description) => match description {
@@ -97,6 +454,25 @@ impl Client {
             description,
             &provider_link,
         );
+        book.title = if book_response.title.is_empty() {
+            work_response.title.clone()
+        } else {
+            book_response.title.clone()
+        };
+        book.authors = authors;
+        book.publisher = book_response
+            .publishers
+            .first()
+            .cloned()
+            .unwrap_or_default();
+        book.published_date = book_response.publish_date.clone().unwrap_or_default();
+        book.language = book_response
+            .languages
+            .first()
+            .map(|language| language.key.trim_start_matches(LANGUAGES_PATH).to_string())
+            .unwrap_or_default();
+        book.categories = work_response.subjects.clone();
+        book.identifiers = create_identifiers(book_response);
 
         let average_rating = rating_response.summary.average.unwrap_or(0_f32);
         let ratings_count = rating_response.summary.count.unwrap_or(0);
@@ -121,8 +497,10 @@ impl Client {
         if status_code == 404 {
             Err(ClientError::NotFound)
         } else if status_code == 429 || status_code == 403 {
-            Err(ClientError::RateLimitExceeded)
-        } else if status_code < 200 || status_code >= 300 {
+            Err(ClientError::RateLimitExceeded(RateLimit::from_headers(
+                response.headers(),
+            )))
+        } else if !(200..300).contains(&status_code) {
             let response_body = response.text().await?;
             Err(ClientError::Http(status_code, response_body))
         } else {
@@ -134,7 +512,7 @@ impl Client {
         self.http_client.get(url).send().await
     }
 
-    async fn fetch_book_by_isbn(&self, isbn: &str) -> Result<BookResponse, ClientError> {
+    async fn fetch_book_by_isbn(&self, isbn: &Isbn) -> Result<BookResponse, ClientError> {
         let response = self
             .send_request(&format!("{}{}/{}.json", self.api_url, ISBN_PATH, isbn))
             .await?;
@@ -145,6 +523,137 @@ impl Client {
         Ok(self.handle_response(response).await?.json().await?)
     }
 
+    /// Returns the key of an edition of the work that best matches the author and title.
+    ///
+    /// Only the author and title that are not empty are searched for. If no work has a score
+    /// of at least the match threshold, the book is not found.
+    async fn search_edition(&self, author: &str, title: &str) -> Result<String, ClientError> {
+        let mut query_list: Vec<(&str, &str)> = Vec::with_capacity(4);
+        if !author.trim().is_empty() {
+            query_list.push(("author", author));
+        }
+        if !title.trim().is_empty() {
+            query_list.push(("title", title));
+        }
+        query_list.push(("fields", SEARCH_FIELDS));
+        query_list.push(("limit", MATCH_CANDIDATES));
+        let response = self
+            .http_client
+            .get(format!("{}{}", self.api_url, SEARCH_PATH))
+            .query(&query_list)
+            .send()
+            .await?;
+        let search_response: SearchResponse = self.handle_response(response).await?.json().await?;
+        let best_match = search_response
+            .docs
+            .iter()
+            .filter_map(|doc| {
+                doc.edition_key().map(|edition_key| {
+                    let score = normalize::match_score(author, title, &doc.title, &doc.author_name);
+                    (edition_key, score)
+                })
+            })
+            .reduce(|best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+
+        match best_match {
+            Some((edition_key, score)) if score >= self.match_threshold => {
+                log::debug!(
+                    "edition {} matches author {} and title {} with score {}",
+                    edition_key,
+                    author,
+                    title,
+                    score
+                );
+                Ok(String::from(edition_key))
+            }
+            Some((edition_key, score)) => {
+                log::debug!(
+                    "best edition {} for author {} and title {} has score {}, below threshold {}",
+                    edition_key,
+                    author,
+                    title,
+                    score,
+                    self.match_threshold
+                );
+                Err(ClientError::NotFound)
+            }
+            None => {
+                log::debug!(
+                    "no edition found for author {} and title {} on Open Library",
+                    author,
+                    title
+                );
+                Err(ClientError::NotFound)
+            }
+        }
+    }
+
+    async fn fetch_edition(&self, edition_key: &str) -> Result<BookResponse, ClientError> {
+        let response = self
+            .send_request(&format!(
+                "{}{}{}.json",
+                self.api_url, BOOKS_PATH, edition_key
+            ))
+            .await?;
+        Ok(self.handle_response(response).await?.json().await?)
+    }
+
+    /// Returns the books found for the given ISBNs, by their bibkey.
+    async fn fetch_books_by_bibkeys(
+        &self,
+        isbns: &[Isbn],
+    ) -> Result<HashMap<String, BibkeyResponse>, ClientError> {
+        let bibkeys: Vec<String> = isbns.iter().map(bibkey).collect();
+        let response = self
+            .send_request(&format!(
+                "{}{}?bibkeys={}&format=json&jscmd=details",
+                self.api_url,
+                BOOKS_API_PATH,
+                bibkeys.join(",")
+            ))
+            .await?;
+        Ok(self.handle_response(response).await?.json().await?)
+    }
+
+    /// Returns all editions of the work, requesting them a page at a time.
+    async fn fetch_editions(&self, work_path: &str) -> Result<Vec<Edition>, ClientError> {
+        let mut editions = Vec::new();
+        loop {
+            let response = self
+                .http_client
+                .get(format!("{}{}{}", self.api_url, work_path, EDITIONS_PATH))
+                .query(&[
+                    ("limit", EDITIONS_PER_REQUEST.to_string()),
+                    ("offset", editions.len().to_string()),
+                ])
+                .send()
+                .await?;
+            let editions_response: EditionsResponse =
+                self.handle_response(response).await?.json().await?;
+
+            let entries = editions_response.entries.len();
+            editions.extend(editions_response.entries.into_iter().map(Edition::from));
+            if entries < EDITIONS_PER_REQUEST || editions.len() >= editions_response.size {
+                return Ok(editions);
+            }
+            if editions.len() >= MAX_EDITIONS {
+                log::debug!(
+                    "only {} of {} editions of work {} returned",
+                    editions.len(),
+                    editions_response.size,
+                    work_path
+                );
+                return Ok(editions);
+            }
+        }
+    }
+
     async fn fetch_work(&self, work_path: &str) -> Result<WorkResponse, ClientError> {
         let response = self
             .send_request(&format!("{}{}.json", self.api_url, work_path))
@@ -162,13 +671,147 @@ impl Client {
         Ok(self.handle_response(response).await?.json().await?)
     }
 
-    async fn fetch_book(&self, isbn: &str) -> Result<Book, ClientError> {
+    async fn fetch_author(&self, author_path: &str) -> Result<AuthorResponse, ClientError> {
+        let response = self
+            .send_request(&format!("{}{}.json", self.api_url, author_path))
+            .await?;
+        Ok(self.handle_response(response).await?.json().await?)
+    }
+
+    /// Returns the names of the given authors, in the same order.
+    ///
+    /// Up to [`BATCH_CONCURRENCY`](BATCH_CONCURRENCY) authors are fetched at a time.
+    /// Authors that cannot be retrieved are skipped, since missing author names should not
+    /// prevent the rest of the book data from being returned.
+    async fn fetch_author_names(&self, author_paths: &[&str]) -> Vec<String> {
+        let lookups: Vec<_> = author_paths
+            .iter()
+            .map(|author_path| async move {
+                match self.fetch_author(author_path).await {
+                    Ok(author) => Some(author.name),
+                    Err(err) => {
+                        log::debug!("could not fetch author {}: {:?}", author_path, err);
+                        None
+                    }
+                }
+            })
+            .collect();
+        let names: Vec<Option<String>> = stream::iter(lookups)
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await;
+        names.into_iter().flatten().collect()
+    }
+
+    /// Returns the editions of a work by its key, e.g. `/works/OL8400950W` or `OL8400950W`.
+    ///
+    /// Editions are listed from the `editions` endpoint of the work, up to 1000 of them.
+    /// If the key is not a valid work key, an error is returned without making any request.
+    /// If an error occurs with the communication, an HTTP status code that is not 200 is returned,
+    /// the work is not found, or the rate limit is exceeded then an error is returned.
+    pub async fn editions(&self, work_key: &str) -> Result<WorkEditions, ClientError> {
+        let work_id = work_key.trim().trim_start_matches(WORKS_PATH);
+        let is_work_id = work_id
+            .strip_prefix("OL")
+            .and_then(|id| id.strip_suffix('W'))
+            .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()));
+        if !is_work_id {
+            return Err(ClientError::InvalidInput(format!(
+                "invalid work key: {}",
+                work_key
+            )));
+        }
+        let work_path = format!("{}{}", WORKS_PATH, work_id);
+        let editions = self.fetch_editions(&work_path).await?;
+        Ok(WorkEditions {
+            work_key: work_path,
+            editions,
+        })
+    }
+
+    /// Returns the editions of the work of the book with the given ISBN, including the book itself.
+    ///
+    /// If an error occurs with the communication, an HTTP status code that is not 200 is returned,
+    /// the book or its work is not found, or the rate limit is exceeded then an error is returned.
+    pub async fn editions_by_isbn(&self, isbn: &Isbn) -> Result<WorkEditions, ClientError> {
         let book_response = self.fetch_book_by_isbn(isbn).await?;
+        match book_response.works.first() {
+            Some(work) => self.editions(&work.key).await,
+            None => {
+                log::debug!(
+                    "no works identifier found for book ISBN({}) on Open Library",
+                    isbn
+                );
+                Err(ClientError::NotFound)
+            }
+        }
+    }
+
+    async fn fetch_book(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        let book_response = self.fetch_book_by_isbn(isbn).await?;
+        self.complete_book(&format!("ISBN({})", isbn), &book_response)
+            .await
+    }
 
+    async fn fetch_book_by_author_and_title(
+        &self,
+        author: &str,
+        title: &str,
+    ) -> Result<Book, ClientError> {
+        let edition_key = self.search_edition(author, title).await?;
+        let book_response = self.fetch_edition(&edition_key).await?;
+        self.complete_book(&edition_key, &book_response).await
+    }
+
+    /// Returns a book by ISBN, querying each group of ISBNs with a single request,
+    /// and completing the books found with their works and ratings.
+    async fn fetch_books(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        let mut book_responses = Vec::with_capacity(isbns.len());
+        for chunk in isbns.chunks(BIBKEYS_PER_REQUEST) {
+            match self.fetch_books_by_bibkeys(chunk).await {
+                Ok(found) => book_responses.extend(chunk.iter().map(|isbn| {
+                    found
+                        .get(&bibkey(isbn))
+                        .map(|bibkey_response| bibkey_response.details.clone())
+                        .ok_or(ClientError::NotFound)
+                })),
+                // Every book of the request fails with the same error
+                Err(err) => book_responses.extend(chunk.iter().map(|_| Err(err.clone()))),
+            }
+        }
+
+        let lookups: Vec<_> = isbns
+            .iter()
+            .zip(book_responses)
+            .map(|(isbn, book_response)| async move {
+                let result = match book_response {
+                    Ok(book_response) => {
+                        self.complete_book(&format!("ISBN({})", isbn), &book_response)
+                            .await
+                    }
+                    Err(err) => Err(err),
+                };
+                IsbnResult::new(isbn, result)
+            })
+            .collect();
+        stream::iter(lookups)
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Returns a book from the edition, and its work, ratings and authors.
+    ///
+    /// The edition is only used to identify the book in logs, e.g. by its ISBN.
+    async fn complete_book(
+        &self,
+        edition: &str,
+        book_response: &BookResponse,
+    ) -> Result<Book, ClientError> {
         if book_response.works.is_empty() {
             log::debug!(
-                "no works identifier found for book with ISBN({}) on Open Library",
-                isbn
+                "no works identifier found for book {} on Open Library",
+                edition
             );
             return Err(ClientError::NotFound);
         }
@@ -176,15 +819,77 @@ impl Client {
         let work_response = self.fetch_work(works_path).await?;
         let ratings_response = self.fetch_rating(works_path).await?;
 
+        let author_names: Option<Vec<String>> = book_response
+            .authors
+            .iter()
+            .map(|author| author.name.clone())
+            .collect();
+        if let Some(authors) = author_names.filter(|names| !names.is_empty()) {
+            return Ok(self.create_book(
+                works_path,
+                book_response,
+                &work_response,
+                &ratings_response,
+                authors,
+            ));
+        }
+
+        // Editions do not always reference their authors, in which case authors of the work are used
+        let author_paths: Vec<&str> = if book_response.authors.is_empty() {
+            work_response
+                .authors
+                .iter()
+                .map(|work_author| work_author.author.key.as_str())
+                .collect()
+        } else {
+            book_response
+                .authors
+                .iter()
+                .map(|author| author.key.as_str())
+                .collect()
+        };
+        let authors = self.fetch_author_names(&author_paths).await;
+
         Ok(self.create_book(
             works_path,
-            &book_response,
+            book_response,
             &work_response,
             &ratings_response,
+            authors,
         ))
     }
 }
 
+/// Returns the first four-digit number of the publishing date, e.g. `2015` for `Sep 08, 2015`.
+fn publish_year(publish_date: &str) -> Option<u16> {
+    publish_date
+        .split(|c: char| !c.is_ascii_digit())
+        .find(|number| number.len() == 4)
+        .and_then(|year| year.parse().ok())
+}
+
+/// Returns the key of the ISBN used by the `/api/books` endpoint.
+fn bibkey(isbn: &Isbn) -> String {
+    format!("ISBN:{}", isbn)
+}
+
+fn create_identifiers(book_response: &BookResponse) -> Vec<Identifier> {
+    let identifiers = [
+        (IdentifierType::Isbn10, &book_response.isbn_10),
+        (IdentifierType::Isbn13, &book_response.isbn_13),
+        (IdentifierType::Lccn, &book_response.lccn),
+        (IdentifierType::Oclc, &book_response.oclc_numbers),
+    ];
+    identifiers
+        .iter()
+        .flat_map(|(identifier_type, values)| {
+            values
+                .iter()
+                .map(|value| Identifier::new(identifier_type.clone(), value))
+        })
+        .collect()
+}
+
 #[async_trait]
 impl BookClient for Client {
     /// Returns a book by ISBN.
@@ -194,15 +899,46 @@ impl BookClient for Client {
     /// 2. /works
     /// 3. /ratings
     ///
+    /// Afterwards `/authors` is queried for each author of the book to retrieve their names.
+    ///
     /// If an error occurs with the communication, an HTTP status code that is not 200 is returned,
     /// the book is not found, or the rate limit is exceeded then an error is returned.
-    async fn book_by_isbn(&self, isbn: &str) -> Result<Book, ClientError> {
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
         self.fetch_book(isbn).await
     }
 
-    /// The method to retrieve book information by author and title is not supported by OpenLibrary,
-    /// so this method calls `unimplemented!` directly.
-    async fn book(&self, _author: &str, _title: &str) -> Result<Book, ClientError> {
-        unimplemented!("not supported by third-party");
+    /// Returns a result for each of the given ISBNs, in the same order.
+    ///
+    /// Books of up to 50 ISBNs are queried with a single request to `/api/books`, after which
+    /// `/works` and `/ratings` are queried for each book found.
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        self.fetch_books(isbns).await
+    }
+
+    /// Returns a book by author and title.
+    ///
+    /// Queries the `/search.json` endpoint for the work that best matches, and one of its
+    /// editions, after which the edition is queried from `/books`, followed by the same
+    /// endpoints as for books queried by ISBN.
+    /// If both author and title are empty, an error is returned without making any request.
+    /// If an error occurs with the communication, an HTTP status code that is not 200 is returned,
+    /// the book is not found, or the rate limit is exceeded then an error is returned.
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        if author.trim().is_empty() && title.trim().is_empty() {
+            return Err(ClientError::InvalidInput(String::from(
+                "author or title must be provided",
+            )));
+        }
+        self.fetch_book_by_author_and_title(author, title).await
+    }
+
+    /// Returns the capabilities of the client, which supports lookups by ISBN, by author
+    /// and title, and of the editions of a book, with [`editions`](Client::editions).
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::new(&[
+            LookupKind::Isbn,
+            LookupKind::AuthorAndTitle,
+            LookupKind::Editions,
+        ])
     }
 }
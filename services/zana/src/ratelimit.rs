@@ -0,0 +1,241 @@
+/*!
+Limits the rate of requests made by any [`BookClient`](trait@BookClient) using the
+[`RateLimiter`](struct@RateLimiter) wrapper, to stay under the quotas of external services.
+
+Requests are limited with a token bucket, that holds up to `burst` tokens and is refilled at
+the configured rate. Each request takes a token, and when the bucket is empty a request either
+waits for a token or fails right away with
+[`RateLimitExceeded`](crate::ClientError::RateLimitExceeded), as configured by
+[`ExceededBehavior`](enum@ExceededBehavior).
+
+Since the quotas are usually per provider, or per API key, each client should be wrapped by its
+own rate limiter. The rate limiter should wrap a [`CircuitBreaker`](crate::circuit::CircuitBreaker),
+and not the other way around, so that rejected requests are not counted as failures of the
+external service.
+
+## Example
+
+```
+use zana::googlebooks::Client;
+use zana::ratelimit::{RateLimitPolicy, RateLimiter};
+
+# fn main() -> Result<(), zana::ClientError> {
+let policy = RateLimitPolicy {
+    requests_per_second: 2.0,
+    burst: 5,
+    ..RateLimitPolicy::default()
+};
+let client = RateLimiter::new(
+    Client::new("<api_key>", "https://www.googleapis.com")?,
+    policy,
+);
+# Ok(())
+# }
+```
+ */
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::future::join_all;
+
+use crate::isbn::Isbn;
+use crate::{Book, BookClient, Capabilities, ClientError, IsbnResult, RateLimit};
+
+/// Reason of the [`RateLimit`](struct@RateLimit) returned when a request is rejected
+/// by a [`RateLimiter`](struct@RateLimiter), instead of by the external service.
+pub const CLIENT_RATE_LIMIT_REASON: &str = "clientRateLimit";
+
+/// What a [`RateLimiter`](struct@RateLimiter) does with a request when no token is available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExceededBehavior {
+    /// Waits until a token is available, unless the wait is longer than
+    /// [`max_wait`](RateLimitPolicy::max_wait)
+    Wait,
+    /// Fails right away with [`RateLimitExceeded`](ClientError::RateLimitExceeded)
+    Fail,
+}
+
+/// Configuration of how requests are limited by [`RateLimiter`](struct@RateLimiter).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitPolicy {
+    /// Rate at which tokens are added to the bucket
+    pub requests_per_second: f64,
+    /// Maximum number of tokens in the bucket, which is the number of requests
+    /// that can be made at once after a period without requests
+    pub burst: u32,
+    pub behavior: ExceededBehavior,
+    /// Longest wait for a token before the request fails,
+    /// when the behavior is [`Wait`](ExceededBehavior::Wait)
+    pub max_wait: Duration,
+}
+
+impl Default for RateLimitPolicy {
+    /// Returns a policy that allows 1 request per second with a burst of 5 requests,
+    /// and waits up to 10s for a token.
+    fn default() -> Self {
+        Self {
+            requests_per_second: 1.0,
+            burst: 5,
+            behavior: ExceededBehavior::Wait,
+            max_wait: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Available tokens, which are negative when waiting requests have reserved future tokens
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// A [`BookClient`](trait@BookClient) that limits the rate of requests to the wrapped client.
+pub struct RateLimiter<C> {
+    client: C,
+    policy: RateLimitPolicy,
+    bucket: Mutex<Bucket>,
+}
+
+impl<C: BookClient> RateLimiter<C> {
+    /// Returns a new client, with a full bucket, that limits requests to the given client
+    /// based on the policy.
+    pub fn new(client: C, policy: RateLimitPolicy) -> Self {
+        let bucket = Bucket {
+            tokens: f64::from(policy.burst),
+            refilled_at: Instant::now(),
+        };
+        Self {
+            client,
+            policy,
+            bucket: Mutex::new(bucket),
+        }
+    }
+
+    /// Returns a reference to the wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.client
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Bucket> {
+        // The bucket is left consistent by every operation, so it is still usable after a panic
+        self.bucket
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Takes a token from the bucket, and returns how long to wait until it is available,
+    /// or an error if the request should not wait.
+    fn reserve(&self) -> Result<Duration, ClientError> {
+        let rate = self.policy.requests_per_second;
+        let now = Instant::now();
+        let mut bucket = self.lock();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(f64::from(self.policy.burst));
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(Duration::ZERO);
+        }
+        let wait = if rate > 0.0 {
+            Duration::try_from_secs_f64((1.0 - bucket.tokens) / rate).unwrap_or(Duration::MAX)
+        } else {
+            Duration::MAX
+        };
+        match self.policy.behavior {
+            ExceededBehavior::Wait if wait <= self.policy.max_wait => {
+                bucket.tokens -= 1.0;
+                Ok(wait)
+            }
+            _ => Err(ClientError::RateLimitExceeded(RateLimit {
+                retry_after: Some(wait).filter(|wait| *wait != Duration::MAX),
+                reason: Some(String::from(CLIENT_RATE_LIMIT_REASON)),
+            })),
+        }
+    }
+
+    async fn limit<F, Fut>(&self, request: F) -> Result<Book, ClientError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Book, ClientError>>,
+    {
+        let wait = self.reserve()?;
+        if !wait.is_zero() {
+            log::debug!("waiting {:?} for rate limit", wait);
+            tokio::time::sleep(wait).await;
+        }
+        request().await
+    }
+}
+
+#[async_trait]
+impl<C: BookClient + Send + Sync> BookClient for RateLimiter<C> {
+    /// Returns a book by ISBN from the wrapped client, once the rate limit allows it.
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.limit(|| self.client.book_by_isbn(isbn)).await
+    }
+
+    /// Returns a book by author and title from the wrapped client, once the rate limit allows it.
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        self.limit(|| self.client.book(author, title)).await
+    }
+
+    /// Returns a result for each of the ISBNs from the wrapped client, once the rate limit
+    /// allows it.
+    ///
+    /// Each ISBN takes a token, since the wrapped client may make a request for each of them.
+    /// ISBNs that a token is available for right away are sent to the wrapped client as a single
+    /// request, and each of the others is sent on its own once its token is available, so that
+    /// requests stay within the rate. ISBNs that no token is available for fail with
+    /// [`RateLimitExceeded`](ClientError::RateLimitExceeded).
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        let mut results: Vec<Option<IsbnResult>> = isbns.iter().map(|_| None).collect();
+        let mut ready = Vec::new();
+        let mut waiting = Vec::new();
+        for (index, isbn) in isbns.iter().enumerate() {
+            match self.reserve() {
+                Ok(wait) if wait.is_zero() => ready.push(index),
+                Ok(wait) => waiting.push((index, wait)),
+                Err(err) => results[index] = Some(IsbnResult::new(isbn, Err(err))),
+            }
+        }
+
+        let ready_isbns: Vec<Isbn> = ready.iter().map(|&index| isbns[index].clone()).collect();
+        let ready_lookup = async {
+            if ready_isbns.is_empty() {
+                Vec::new()
+            } else {
+                self.client.books_by_isbns(&ready_isbns).await
+            }
+        };
+        let waiting_lookups: Vec<_> = waiting
+            .iter()
+            .map(|&(index, wait)| async move {
+                log::debug!("waiting {:?} for rate limit", wait);
+                tokio::time::sleep(wait).await;
+                let isbn = &isbns[index];
+                IsbnResult::new(isbn, self.client.book_by_isbn(isbn).await)
+            })
+            .collect();
+        let (ready_results, waiting_results) =
+            tokio::join!(ready_lookup, join_all(waiting_lookups));
+
+        let fetched = ready.into_iter().zip(ready_results).chain(
+            waiting
+                .into_iter()
+                .map(|(index, _)| index)
+                .zip(waiting_results),
+        );
+        for (index, result) in fetched {
+            results[index] = Some(result);
+        }
+        results.into_iter().flatten().collect()
+    }
+
+    /// Returns the capabilities of the wrapped client.
+    fn capabilities(&self) -> Capabilities {
+        self.client.capabilities()
+    }
+}
@@ -0,0 +1,105 @@
+/*!
+Combines the [`Rating`](struct@Rating) values of several providers into a single
+[`AggregatedRating`](struct@AggregatedRating).
+
+The combined average rating is weighted by the number of ratings of each provider, so a provider
+with a handful of ratings does not move the combined rating as much as a provider with thousands
+of them. The rating of each provider is kept alongside the combined rating.
+
+## Example
+
+```
+use zana::rating::{AggregatedRating, ProviderRating};
+use zana::Rating;
+
+let rating = AggregatedRating::new(vec![
+    ProviderRating::new("googlebooks", Rating::new(4.0, 10)),
+    ProviderRating::new("openlibrary", Rating::new(3.0, 30)),
+])
+.expect("rating expected");
+
+assert_eq!(3.25, rating.average_rating);
+assert_eq!(40, rating.ratings_count);
+assert_eq!(2, rating.providers.len());
+```
+ */
+use crate::Rating;
+
+/// A rating together with the name of the provider that returned it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProviderRating {
+    pub provider: String,
+    pub rating: Rating,
+}
+
+impl ProviderRating {
+    /// Returns a new rating of the provider with the given name, e.g. `googlebooks`.
+    pub fn new(provider: &str, rating: Rating) -> Self {
+        Self {
+            provider: String::from(provider),
+            rating,
+        }
+    }
+}
+
+/// Rating combined from the ratings of several providers.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AggregatedRating {
+    /// Average of the provider ratings, weighted by their number of ratings
+    pub average_rating: f32,
+    /// Total number of ratings of all providers
+    pub ratings_count: u32,
+    /// Ratings of each provider that has at least one rating, in the given order
+    pub providers: Vec<ProviderRating>,
+}
+
+impl AggregatedRating {
+    /// Returns the rating combined from the given provider ratings.
+    ///
+    /// Ratings without any ratings counted are skipped, and `None` is returned when no rating
+    /// is left to combine.
+    pub fn new(ratings: Vec<ProviderRating>) -> Option<Self> {
+        let providers: Vec<ProviderRating> = ratings
+            .into_iter()
+            .filter(|provider| provider.rating.ratings_count > 0)
+            .collect();
+        if providers.is_empty() {
+            return None;
+        }
+
+        // Summed as f64, so that large counts do not lose precision
+        let (weighted_sum, count) =
+            providers
+                .iter()
+                .fold((0.0, 0.0), |(weighted_sum, count), provider| {
+                    let ratings_count = f64::from(provider.rating.ratings_count);
+                    (
+                        weighted_sum + f64::from(provider.rating.average_rating) * ratings_count,
+                        count + ratings_count,
+                    )
+                });
+        let ratings_count = providers.iter().fold(0u32, |total, provider| {
+            total.saturating_add(provider.rating.ratings_count)
+        });
+        Some(Self {
+            average_rating: (weighted_sum / count) as f32,
+            ratings_count,
+            providers,
+        })
+    }
+
+    /// Returns the combined rating, without the ratings of each provider.
+    pub fn rating(&self) -> Rating {
+        Rating::new(self.average_rating, self.ratings_count)
+    }
+
+    /// Returns the rating of the provider with the given name, if it has one.
+    pub fn provider(&self, name: &str) -> Option<&Rating> {
+        self.providers
+            .iter()
+            .find(|provider| provider.provider == name)
+            .map(|provider| &provider.rating)
+    }
+}
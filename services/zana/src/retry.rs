@@ -0,0 +1,222 @@
+/*!
+Retries failed requests of any [`BookClient`](trait@BookClient) using the
+[`RetryingClient`](struct@RetryingClient) wrapper.
+
+Requests are retried only for errors that are [retryable](crate::ClientError::is_retryable),
+such as timeouts, connection failures and `5xx` status codes, with an exponential backoff and
+jitter between attempts, as configured by [`RetryPolicy`](struct@RetryPolicy).
+
+Exceeded rate limits are retried only when the external service tells how long to wait,
+and that wait fits in the policy.
+
+## Example
+
+```
+use zana::openlibrary::Client;
+use zana::retry::{RetryPolicy, RetryingClient};
+
+# fn main() -> Result<(), zana::ClientError> {
+let client = RetryingClient::new(Client::new("https://openlibrary.org")?, RetryPolicy::default());
+# Ok(())
+# }
+```
+ */
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use rand::Rng;
+
+use crate::isbn::Isbn;
+use crate::{Book, BookClient, Capabilities, ClientError, IsbnResult};
+
+/// Configuration of how failed requests are retried by [`RetryingClient`](struct@RetryingClient).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first request
+    pub max_attempts: u32,
+    /// Backoff before the first retry
+    pub initial_backoff: Duration,
+    /// Upper limit of the backoff between attempts
+    pub max_backoff: Duration,
+    /// Factor by which the backoff grows after each attempt
+    pub multiplier: f64,
+    /// Fraction of the backoff, between `0.0` and `1.0`, that is randomized.
+    /// `0.0` disables jitter, while `1.0` picks any backoff between zero and the computed backoff.
+    pub jitter: f64,
+    /// Longest wait accepted from a rate limit hint (e.g. a `Retry-After` header).
+    /// Exceeded rate limits with a longer wait, or without a hint, are not retried.
+    pub max_retry_after: Duration,
+    /// Total time budget for all attempts. No retry is started if its backoff would exceed
+    /// the budget. Requests that are already sent are not cancelled when the budget runs out.
+    pub max_elapsed_time: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    /// Returns a policy with 3 attempts, a backoff starting at 200ms that doubles up to 5s,
+    /// 50% jitter, and a time budget of 20s.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: 0.5,
+            max_retry_after: Duration::from_secs(10),
+            max_elapsed_time: Some(Duration::from_secs(20)),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the backoff before the given retry, where the first retry is `1`.
+    fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = if jitter > 0.0 {
+            1.0 - jitter * rand::thread_rng().gen::<f64>()
+        } else {
+            1.0
+        };
+        Duration::try_from_secs_f64(backoff * factor).unwrap_or(self.max_backoff)
+    }
+
+    /// Returns the wait before retrying after the given error,
+    /// or [`None`](None) if the error should not be retried.
+    fn wait_for(&self, err: &ClientError, retry: u32) -> Option<Duration> {
+        match err {
+            ClientError::RateLimitExceeded(rate_limit) => rate_limit
+                .retry_after
+                .filter(|retry_after| *retry_after <= self.max_retry_after),
+            err if err.is_retryable() => Some(self.backoff(retry)),
+            _ => None,
+        }
+    }
+}
+
+/// A [`BookClient`](trait@BookClient) that retries failed requests of the wrapped client.
+pub struct RetryingClient<C> {
+    client: C,
+    policy: RetryPolicy,
+}
+
+impl<C: BookClient> RetryingClient<C> {
+    /// Returns a new client that retries requests of the given client based on the policy.
+    pub fn new(client: C, policy: RetryPolicy) -> Self {
+        Self { client, policy }
+    }
+
+    /// Returns a reference to the wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.client
+    }
+
+    async fn retry<F, Fut>(&self, request: F) -> Result<Book, ClientError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<Book, ClientError>>,
+    {
+        let started_at = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let err = match request().await {
+                Ok(book) => return Ok(book),
+                Err(err) => err,
+            };
+            if attempt >= self.policy.max_attempts {
+                return Err(err);
+            }
+            let wait = match self.policy.wait_for(&err, attempt) {
+                Some(wait) => wait,
+                None => return Err(err),
+            };
+            if let Some(max_elapsed_time) = self.policy.max_elapsed_time {
+                if started_at.elapsed() + wait > max_elapsed_time {
+                    log::debug!("retry time budget exceeded after {} attempts", attempt);
+                    return Err(err);
+                }
+            }
+
+            log::debug!(
+                "retrying request in {:?} after attempt {} failed: {:?}",
+                wait,
+                attempt,
+                err
+            );
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[async_trait]
+impl<C: BookClient + Send + Sync> BookClient for RetryingClient<C> {
+    /// Returns a book by ISBN, retrying failed requests of the wrapped client.
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.retry(|| self.client.book_by_isbn(isbn)).await
+    }
+
+    /// Returns a book by author and title, retrying failed requests of the wrapped client.
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        self.retry(|| self.client.book(author, title)).await
+    }
+
+    /// Returns a result for each of the ISBNs, retrying the ISBNs whose requests failed
+    /// with the wrapped client.
+    ///
+    /// The ISBNs are sent to the wrapped client as a single request, and each retry sends only
+    /// the ISBNs that failed, after the longest wait of their errors.
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        let started_at = Instant::now();
+        let mut results = self.client.books_by_isbns(isbns).await;
+        let mut attempt = 1;
+        while attempt < self.policy.max_attempts {
+            let mut failed = Vec::new();
+            let mut wait = None;
+            for (index, result) in results.iter().enumerate() {
+                let err_wait = match &result.result {
+                    Err(err) => self.policy.wait_for(err, attempt),
+                    Ok(_) => None,
+                };
+                if let Some(err_wait) = err_wait {
+                    failed.push(index);
+                    wait = wait.max(Some(err_wait));
+                }
+            }
+            let wait = match wait {
+                Some(wait) => wait,
+                None => break,
+            };
+            if let Some(max_elapsed_time) = self.policy.max_elapsed_time {
+                if started_at.elapsed() + wait > max_elapsed_time {
+                    log::debug!("retry time budget exceeded after {} attempts", attempt);
+                    break;
+                }
+            }
+
+            log::debug!(
+                "retrying {} of {} isbns in {:?} after attempt {} failed",
+                failed.len(),
+                results.len(),
+                wait,
+                attempt
+            );
+            tokio::time::sleep(wait).await;
+            let retried: Vec<Isbn> = failed.iter().map(|index| isbns[*index].clone()).collect();
+            let retried_results = self.client.books_by_isbns(&retried).await;
+            for (index, result) in failed.into_iter().zip(retried_results) {
+                results[index] = result;
+            }
+            attempt += 1;
+        }
+        results
+    }
+
+    /// Returns the capabilities of the wrapped client.
+    fn capabilities(&self) -> Capabilities {
+        self.client.capabilities()
+    }
+}
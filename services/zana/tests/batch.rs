@@ -0,0 +1,246 @@
+mod util;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use zana::circuit::{CircuitBreaker, CircuitPolicy};
+use zana::fallback::{FallbackClient, FallbackPolicy, Provider};
+use zana::isbn::Isbn;
+use zana::merge::{MergePolicy, MergingClient};
+use zana::ratelimit::{ExceededBehavior, RateLimitPolicy, RateLimiter};
+use zana::retry::{RetryPolicy, RetryingClient};
+use zana::{Book, BookClient, ClientError, IsbnResult, BATCH_CONCURRENCY};
+
+use crate::util::TestClient;
+
+fn isbns(count: usize) -> Vec<Isbn> {
+    ["9780316387316", "9781591026419", "0316387312"]
+        .iter()
+        .cycle()
+        .take(count)
+        .map(|isbn| Isbn::parse(isbn).expect("invalid isbn"))
+        .collect()
+}
+
+#[tokio::test]
+async fn return_result_for_each_isbn_in_order() {
+    let client = TestClient::new(vec![
+        Ok(TestClient::default_book()),
+        Err(ClientError::NotFound),
+        Err(ClientError::Http(500, String::new())),
+    ]);
+    let isbns = isbns(3);
+
+    let results = client.books_by_isbns(&isbns).await;
+
+    assert_eq!(3, results.len());
+    assert_eq!(isbns[0], results[0].isbn);
+    assert_eq!(
+        TestClient::default_book(),
+        results[0].result.clone().expect("book expected")
+    );
+    assert_eq!(isbns[1], results[1].isbn);
+    assert!(matches!(results[1].result, Err(ClientError::NotFound)));
+    assert_eq!(isbns[2], results[2].isbn);
+    assert!(matches!(results[2].result, Err(ClientError::Http(500, _))));
+    assert_eq!(3, client.calls());
+}
+
+#[tokio::test]
+async fn return_no_results_without_isbns() {
+    let client = TestClient::new(vec![]);
+
+    assert!(client.books_by_isbns(&[]).await.is_empty());
+    assert_eq!(0, client.calls());
+}
+
+#[tokio::test]
+async fn query_books_with_bounded_concurrency() {
+    let delay = Duration::from_millis(100);
+    let client = TestClient::with_delay(vec![], delay);
+    let isbns = isbns(BATCH_CONCURRENCY * 2);
+
+    let start = Instant::now();
+    let results = client.books_by_isbns(&isbns).await;
+    let elapsed = start.elapsed();
+
+    assert_eq!(isbns.len(), results.len());
+    assert!(
+        elapsed >= delay * 2,
+        "more requests made at a time than allowed"
+    );
+    assert!(elapsed < delay * 4, "requests not made concurrently");
+}
+
+/// Book client that returns the given results of each ISBN in order, and a default book once
+/// all results of the ISBN are returned. Records the ISBNs of each batch it is queried with.
+#[derive(Default)]
+struct BatchClient {
+    results: Mutex<HashMap<Isbn, VecDeque<Result<Book, ClientError>>>>,
+    batches: Mutex<Vec<Vec<Isbn>>>,
+}
+
+impl BatchClient {
+    fn new(results: Vec<(&Isbn, Vec<Result<Book, ClientError>>)>) -> Arc<Self> {
+        let results = results
+            .into_iter()
+            .map(|(isbn, results)| (isbn.clone(), results.into()))
+            .collect();
+        Arc::new(Self {
+            results: Mutex::new(results),
+            ..Self::default()
+        })
+    }
+
+    fn batches(&self) -> Vec<Vec<Isbn>> {
+        self.batches.lock().expect("batches lock poisoned").clone()
+    }
+
+    fn next_result(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.results
+            .lock()
+            .expect("results lock poisoned")
+            .get_mut(isbn)
+            .and_then(VecDeque::pop_front)
+            .unwrap_or_else(|| Ok(TestClient::default_book()))
+    }
+}
+
+#[async_trait]
+impl BookClient for BatchClient {
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.next_result(isbn)
+    }
+
+    async fn book(&self, _author: &str, _title: &str) -> Result<Book, ClientError> {
+        Err(ClientError::NotFound)
+    }
+
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        self.batches
+            .lock()
+            .expect("batches lock poisoned")
+            .push(isbns.to_vec());
+        isbns
+            .iter()
+            .map(|isbn| IsbnResult::new(isbn, self.next_result(isbn)))
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn retry_only_failed_isbns_of_batch() {
+    let isbns = isbns(2);
+    let client = BatchClient::new(vec![
+        (&isbns[0], vec![Err(ClientError::Http(503, String::new()))]),
+        (&isbns[1], vec![Err(ClientError::NotFound)]),
+    ]);
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(1),
+        jitter: 0.0,
+        ..RetryPolicy::default()
+    };
+    let retrying_client = RetryingClient::new(Arc::clone(&client), policy);
+
+    let results = retrying_client.books_by_isbns(&isbns).await;
+
+    assert_eq!(
+        vec![isbns.clone(), vec![isbns[0].clone()]],
+        client.batches()
+    );
+    assert!(results[0].result.is_ok());
+    assert!(matches!(results[1].result, Err(ClientError::NotFound)));
+}
+
+#[tokio::test]
+async fn reject_batch_when_circuit_is_open() {
+    let isbns = isbns(2);
+    let client = BatchClient::new(vec![(
+        &isbns[0],
+        vec![Err(ClientError::Http(503, String::new()))],
+    )]);
+    let policy = CircuitPolicy {
+        failure_threshold: 1,
+        ..CircuitPolicy::default()
+    };
+    let circuit_breaker = CircuitBreaker::new(Arc::clone(&client), policy);
+
+    let results = circuit_breaker.books_by_isbns(&isbns).await;
+    assert!(matches!(results[0].result, Err(ClientError::Http(503, _))));
+    assert!(results[1].result.is_ok());
+
+    let results = circuit_breaker.books_by_isbns(&isbns).await;
+    assert_eq!(1, client.batches().len());
+    assert_eq!(2, results.len());
+    for result in results {
+        assert!(matches!(result.result, Err(ClientError::CircuitOpen(_))));
+    }
+}
+
+#[tokio::test]
+async fn reject_isbns_of_batch_without_rate_limit_tokens() {
+    let isbns = isbns(3);
+    let client = BatchClient::new(vec![]);
+    let policy = RateLimitPolicy {
+        requests_per_second: 0.001,
+        burst: 2,
+        behavior: ExceededBehavior::Fail,
+        ..RateLimitPolicy::default()
+    };
+    let rate_limiter = RateLimiter::new(Arc::clone(&client), policy);
+
+    let results = rate_limiter.books_by_isbns(&isbns).await;
+
+    assert_eq!(vec![isbns[..2].to_vec()], client.batches());
+    assert_eq!(3, results.len());
+    assert!(results[0].result.is_ok());
+    assert!(results[1].result.is_ok());
+    assert_eq!(isbns[2], results[2].isbn);
+    assert!(matches!(
+        results[2].result,
+        Err(ClientError::RateLimitExceeded(_))
+    ));
+}
+
+#[tokio::test]
+async fn fall_back_only_for_isbns_not_returned_by_batch() {
+    let isbns = isbns(2);
+    let first_client = BatchClient::new(vec![(&isbns[1], vec![Err(ClientError::NotFound)])]);
+    let second_client = BatchClient::new(vec![]);
+    let client = FallbackClient::new(
+        vec![
+            Provider::new("first", Arc::clone(&first_client)),
+            Provider::new("second", Arc::clone(&second_client)),
+        ],
+        FallbackPolicy::default(),
+    );
+
+    let results = client.books_by_isbns(&isbns).await;
+
+    assert_eq!(vec![isbns.clone()], first_client.batches());
+    assert_eq!(vec![vec![isbns[1].clone()]], second_client.batches());
+    assert!(results.iter().all(|result| result.result.is_ok()));
+}
+
+#[tokio::test]
+async fn merge_batches_of_all_providers() {
+    let isbns = isbns(2);
+    let first_client = BatchClient::new(vec![(&isbns[1], vec![Err(ClientError::NotFound)])]);
+    let second_client = BatchClient::new(vec![(&isbns[1], vec![Err(ClientError::NotFound)])]);
+    let client = MergingClient::new(
+        vec![
+            Provider::new("first", Arc::clone(&first_client)),
+            Provider::new("second", Arc::clone(&second_client)),
+        ],
+        MergePolicy::default(),
+    );
+
+    let results = client.books_by_isbns(&isbns).await;
+
+    assert_eq!(vec![isbns.clone()], first_client.batches());
+    assert_eq!(vec![isbns.clone()], second_client.batches());
+    assert!(results[0].result.is_ok());
+    assert!(matches!(results[1].result, Err(ClientError::NotFound)));
+}
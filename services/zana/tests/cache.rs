@@ -0,0 +1,235 @@
+mod util;
+
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use zana::cache::{CachePolicy, CachingClient};
+use zana::isbn::Isbn;
+use zana::{Book, BookClient, Capabilities, ClientError, IsbnResult};
+
+use crate::util::TestClient;
+
+fn create_policy(capacity: usize) -> CachePolicy {
+    CachePolicy {
+        capacity: NonZeroUsize::new(capacity).expect("capacity must not be zero"),
+        ..CachePolicy::default()
+    }
+}
+
+fn isbn(isbn: &str) -> Isbn {
+    Isbn::parse(isbn).expect("invalid isbn")
+}
+
+/// Book client that leaves the last book out of the results of every batch
+struct PartialBatchClient(TestClient);
+
+#[async_trait]
+impl BookClient for PartialBatchClient {
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.0.book_by_isbn(isbn).await
+    }
+
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        self.0.book(author, title).await
+    }
+
+    async fn books_by_isbns(&self, isbns: &[Isbn]) -> Vec<IsbnResult> {
+        let looked_up = isbns.len().saturating_sub(1);
+        self.0.books_by_isbns(&isbns[..looked_up]).await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.0.capabilities()
+    }
+}
+
+#[tokio::test]
+async fn return_cached_book_by_isbn() {
+    let client = CachingClient::new(TestClient::new(vec![]), create_policy(10));
+
+    for _ in 0..3 {
+        let book = client
+            .book_by_isbn(&isbn("9780316387316"))
+            .await
+            .expect("book expected");
+        assert_eq!(TestClient::default_book(), book);
+    }
+
+    assert_eq!(1, client.inner().calls());
+    let stats = client.stats();
+    assert_eq!(2, stats.hits);
+    assert_eq!(1, stats.misses);
+    assert_eq!(1, stats.entries);
+}
+
+#[tokio::test]
+async fn match_cached_book_by_author_and_title_ignoring_case() {
+    let client = CachingClient::new(TestClient::new(vec![]), create_policy(10));
+
+    client
+        .book("Joe Abercrombie", "The Blade Itself")
+        .await
+        .expect("book expected");
+    client
+        .book(" joe abercrombie", "THE BLADE ITSELF ")
+        .await
+        .expect("book expected");
+
+    assert_eq!(1, client.inner().calls());
+}
+
+#[tokio::test]
+async fn return_cached_not_found_error() {
+    let client = CachingClient::new(
+        TestClient::new(vec![Err(ClientError::NotFound)]),
+        create_policy(10),
+    );
+
+    for _ in 0..2 {
+        let returned_error = client
+            .book_by_isbn(&isbn("9780316387316"))
+            .await
+            .expect_err("error expected for missing book");
+        assert!(matches!(returned_error, ClientError::NotFound));
+    }
+
+    assert_eq!(1, client.inner().calls());
+    assert_eq!(1, client.stats().not_found_hits);
+}
+
+#[tokio::test]
+async fn do_not_cache_not_found_error_when_disabled() {
+    let policy = CachePolicy {
+        not_found_ttl: None,
+        ..create_policy(10)
+    };
+    let client = CachingClient::new(TestClient::new(vec![Err(ClientError::NotFound)]), policy);
+
+    assert!(client.book_by_isbn(&isbn("9780316387316")).await.is_err());
+    assert!(client.book_by_isbn(&isbn("9780316387316")).await.is_ok());
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn do_not_cache_other_errors() {
+    let client = CachingClient::new(
+        TestClient::new(vec![Err(ClientError::Http(503, String::new()))]),
+        create_policy(10),
+    );
+
+    assert!(client.book_by_isbn(&isbn("9780316387316")).await.is_err());
+    assert!(client.book_by_isbn(&isbn("9780316387316")).await.is_ok());
+    assert_eq!(2, client.inner().calls());
+    assert_eq!(1, client.stats().entries);
+}
+
+#[tokio::test]
+async fn refetch_book_after_ttl_expires() {
+    let policy = CachePolicy {
+        ttl: Duration::from_millis(20),
+        ..create_policy(10)
+    };
+    let client = CachingClient::new(TestClient::new(vec![]), policy);
+
+    client
+        .book_by_isbn(&isbn("9780316387316"))
+        .await
+        .expect("book expected");
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    client
+        .book_by_isbn(&isbn("9780316387316"))
+        .await
+        .expect("book expected");
+
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn use_separate_ttl_for_not_found_errors() {
+    let policy = CachePolicy {
+        not_found_ttl: Some(Duration::from_millis(20)),
+        ..create_policy(10)
+    };
+    let client = CachingClient::new(TestClient::new(vec![Err(ClientError::NotFound)]), policy);
+
+    assert!(client.book_by_isbn(&isbn("9780316387316")).await.is_err());
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert!(client.book_by_isbn(&isbn("9780316387316")).await.is_ok());
+    // The book itself is cached with the default TTL
+    assert!(client.book_by_isbn(&isbn("9780316387316")).await.is_ok());
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn evict_least_recently_used_book() {
+    let client = CachingClient::new(TestClient::new(vec![]), create_policy(2));
+    let first = isbn("9780316387316");
+    let second = isbn("0-306-40615-2");
+    let third = isbn("978-1-4028-9462-6");
+
+    client.book_by_isbn(&first).await.expect("book expected");
+    client.book_by_isbn(&second).await.expect("book expected");
+    // Makes the first book the most recently used
+    client.book_by_isbn(&first).await.expect("book expected");
+    client.book_by_isbn(&third).await.expect("book expected");
+    client.book_by_isbn(&first).await.expect("book expected");
+
+    assert_eq!(3, client.inner().calls());
+    let stats = client.stats();
+    assert_eq!(1, stats.evictions);
+    assert_eq!(2, stats.entries);
+
+    client.book_by_isbn(&second).await.expect("book expected");
+    assert_eq!(4, client.inner().calls());
+}
+
+#[tokio::test]
+async fn share_cache_between_tasks() {
+    let client = Arc::new(CachingClient::new(
+        TestClient::new(vec![]),
+        create_policy(10),
+    ));
+    client
+        .book_by_isbn(&isbn("9780316387316"))
+        .await
+        .expect("book expected");
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let client = Arc::clone(&client);
+            tokio::spawn(async move { client.book_by_isbn(&isbn("9780316387316")).await })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.expect("task panicked").expect("book expected");
+    }
+
+    assert_eq!(1, client.inner().calls());
+    assert_eq!(4, client.stats().hits);
+}
+
+#[tokio::test]
+async fn look_up_books_left_out_of_batch_instead_of_caching_them_as_not_found() {
+    let client = CachingClient::new(
+        PartialBatchClient(TestClient::new(vec![])),
+        create_policy(10),
+    );
+    let isbns = [isbn("9780316387316"), isbn("9780575094185")];
+
+    for _ in 0..2 {
+        let results = client.books_by_isbns(&isbns).await;
+        assert_eq!(2, results.len());
+        for (result, isbn) in results.into_iter().zip(&isbns) {
+            assert_eq!(isbn, &result.isbn);
+            assert_eq!(
+                TestClient::default_book(),
+                result.result.expect("book expected")
+            );
+        }
+    }
+
+    assert_eq!(2, client.inner().0.calls());
+    assert_eq!(2, client.stats().entries);
+}
@@ -0,0 +1,82 @@
+mod util;
+
+use std::sync::Arc;
+
+use zana::cache::{CachePolicy, CachingClient};
+use zana::circuit::{CircuitBreaker, CircuitPolicy};
+use zana::coalesce::CoalescingClient;
+use zana::ratelimit::{RateLimitPolicy, RateLimiter};
+use zana::retry::{RetryPolicy, RetryingClient};
+use zana::{googlebooks, openlibrary, BookClient, Capabilities, LookupKind};
+
+use crate::util::TestClient;
+
+#[test]
+fn clients_support_isbn_and_author_and_title_lookups() {
+    let googlebooks =
+        googlebooks::Client::new("key", "http://localhost").expect("could not create client");
+    let openlibrary =
+        openlibrary::Client::new("http://localhost").expect("could not create client");
+
+    for capabilities in [googlebooks.capabilities(), openlibrary.capabilities()] {
+        assert!(capabilities.supports(LookupKind::Isbn));
+        assert!(capabilities.supports(LookupKind::AuthorAndTitle));
+        assert!(!capabilities.supports(LookupKind::Search));
+    }
+}
+
+#[test]
+fn only_googlebooks_supports_identifier_lookups() {
+    let googlebooks =
+        googlebooks::Client::new("key", "http://localhost").expect("could not create client");
+    let openlibrary =
+        openlibrary::Client::new("http://localhost").expect("could not create client");
+
+    assert!(googlebooks.capabilities().supports(LookupKind::Identifiers));
+    assert!(!openlibrary.capabilities().supports(LookupKind::Identifiers));
+}
+
+#[test]
+fn only_openlibrary_supports_editions_lookups() {
+    let googlebooks =
+        googlebooks::Client::new("key", "http://localhost").expect("could not create client");
+    let openlibrary =
+        openlibrary::Client::new("http://localhost").expect("could not create client");
+
+    assert!(openlibrary.capabilities().supports(LookupKind::Editions));
+    assert!(!googlebooks.capabilities().supports(LookupKind::Editions));
+}
+
+#[test]
+fn wrappers_return_capabilities_of_wrapped_client() {
+    let capabilities = Capabilities::new(&[LookupKind::Isbn]);
+    let client = Arc::new(TestClient::with_capabilities(vec![], capabilities.clone()));
+
+    let wrapped = CachingClient::new(
+        RateLimiter::new(
+            CircuitBreaker::new(
+                RetryingClient::new(
+                    CoalescingClient::new(Arc::clone(&client)),
+                    RetryPolicy::default(),
+                ),
+                CircuitPolicy::default(),
+            ),
+            RateLimitPolicy::default(),
+        ),
+        CachePolicy::default(),
+    );
+
+    assert_eq!(capabilities, wrapped.capabilities());
+}
+
+#[test]
+fn combine_capabilities() {
+    let capabilities = Capabilities::new(&[LookupKind::Isbn, LookupKind::Isbn])
+        .union(&Capabilities::new(&[LookupKind::Search, LookupKind::Isbn]));
+
+    assert_eq!(
+        &[LookupKind::Isbn, LookupKind::Search],
+        capabilities.lookups()
+    );
+    assert!(!Capabilities::default().supports(LookupKind::Isbn));
+}
@@ -0,0 +1,196 @@
+mod util;
+
+use std::time::Duration;
+
+use zana::circuit::{CircuitBreaker, CircuitPolicy, CircuitState};
+use zana::isbn::Isbn;
+use zana::{Book, BookClient, ClientError};
+
+use crate::util::TestClient;
+
+fn create_policy(failure_threshold: u32, cool_down: Duration) -> CircuitPolicy {
+    CircuitPolicy {
+        failure_threshold,
+        cool_down,
+        success_threshold: 1,
+    }
+}
+
+fn isbn() -> Isbn {
+    Isbn::parse("9780316387316").expect("invalid isbn")
+}
+
+fn server_error() -> Result<Book, ClientError> {
+    Err(ClientError::Http(503, String::new()))
+}
+
+#[tokio::test]
+async fn open_circuit_after_consecutive_failures() {
+    let client = CircuitBreaker::new(
+        TestClient::new(vec![server_error(), server_error()]),
+        create_policy(2, Duration::from_secs(60)),
+    );
+
+    assert!(client.book_by_isbn(&isbn()).await.is_err());
+    assert_eq!(CircuitState::Closed, client.state());
+    assert!(client.book_by_isbn(&isbn()).await.is_err());
+    assert_eq!(CircuitState::Open, client.state());
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected when circuit is open");
+    assert!(matches!(returned_error, ClientError::CircuitOpen(_)));
+    assert!(returned_error.retry_after().is_some_and(|retry_after| {
+        retry_after > Duration::ZERO && retry_after <= Duration::from_secs(60)
+    }));
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn reset_failures_after_success() {
+    let client = CircuitBreaker::new(
+        TestClient::new(vec![
+            server_error(),
+            Ok(TestClient::default_book()),
+            server_error(),
+        ]),
+        create_policy(2, Duration::from_secs(60)),
+    );
+
+    for _ in 0..3 {
+        let _ = client.book_by_isbn(&isbn()).await;
+    }
+
+    assert_eq!(CircuitState::Closed, client.state());
+    assert_eq!(3, client.inner().calls());
+}
+
+#[tokio::test]
+async fn do_not_count_not_found_as_failure() {
+    let client = CircuitBreaker::new(
+        TestClient::new(vec![
+            Err(ClientError::NotFound),
+            Err(ClientError::NotFound),
+            Err(ClientError::InvalidInput(String::from("invalid"))),
+        ]),
+        create_policy(1, Duration::from_secs(60)),
+    );
+
+    for _ in 0..3 {
+        assert!(client.book("Author", "Title").await.is_err());
+    }
+
+    assert_eq!(CircuitState::Closed, client.state());
+}
+
+#[tokio::test]
+async fn count_unauthorized_as_failure() {
+    let client = CircuitBreaker::new(
+        TestClient::new(vec![Err(ClientError::Unauthorized(401, String::new()))]),
+        create_policy(1, Duration::from_secs(60)),
+    );
+
+    assert!(client.book_by_isbn(&isbn()).await.is_err());
+    assert_eq!(CircuitState::Open, client.state());
+}
+
+#[tokio::test]
+async fn close_circuit_after_successful_request_in_half_open_state() {
+    let client = CircuitBreaker::new(
+        TestClient::new(vec![server_error()]),
+        create_policy(1, Duration::from_millis(20)),
+    );
+
+    assert!(client.book_by_isbn(&isbn()).await.is_err());
+    assert_eq!(CircuitState::Open, client.state());
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert_eq!(CircuitState::HalfOpen, client.state());
+
+    let book = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect("book expected after cool-down");
+    assert_eq!(TestClient::default_book(), book);
+    assert_eq!(CircuitState::Closed, client.state());
+}
+
+#[tokio::test]
+async fn reopen_circuit_after_failed_request_in_half_open_state() {
+    let client = CircuitBreaker::new(
+        TestClient::new(vec![server_error(), server_error()]),
+        create_policy(1, Duration::from_millis(20)),
+    );
+
+    assert!(client.book_by_isbn(&isbn()).await.is_err());
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected from failed request");
+
+    assert!(matches!(returned_error, ClientError::Http(503, _)));
+    assert_eq!(CircuitState::Open, client.state());
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn reject_requests_with_retry_after_while_half_open_request_is_in_flight() {
+    let client = CircuitBreaker::new(
+        TestClient::with_delay(vec![server_error()], Duration::from_millis(100)),
+        create_policy(1, Duration::from_millis(200)),
+    );
+
+    assert!(client.book_by_isbn(&isbn()).await.is_err());
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let isbn = isbn();
+    let (probe, rejected) = tokio::join!(client.book_by_isbn(&isbn), async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        client.book_by_isbn(&isbn).await
+    });
+
+    assert!(probe.is_ok());
+    let returned_error = rejected.expect_err("error expected while request is in flight");
+    assert!(matches!(returned_error, ClientError::CircuitOpen(_)));
+    assert_eq!(Some(Duration::from_secs(1)), returned_error.retry_after());
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn require_consecutive_successes_to_close_circuit() {
+    let policy = CircuitPolicy {
+        success_threshold: 2,
+        ..create_policy(1, Duration::from_millis(20))
+    };
+    let client = CircuitBreaker::new(TestClient::new(vec![server_error()]), policy);
+
+    assert!(client.book_by_isbn(&isbn()).await.is_err());
+    tokio::time::sleep(Duration::from_millis(40)).await;
+
+    assert!(client.book_by_isbn(&isbn()).await.is_ok());
+    assert_eq!(CircuitState::HalfOpen, client.state());
+    assert!(client.book_by_isbn(&isbn()).await.is_ok());
+    assert_eq!(CircuitState::Closed, client.state());
+}
+
+#[tokio::test]
+async fn share_circuit_between_clients() {
+    let client = CircuitBreaker::new(
+        TestClient::new(vec![server_error(), server_error()]),
+        create_policy(2, Duration::from_secs(60)),
+    );
+    let shared_client = client.shared(TestClient::new(vec![server_error()]));
+
+    assert!(client.book_by_isbn(&isbn()).await.is_err());
+    assert!(shared_client.book_by_isbn(&isbn()).await.is_err());
+    assert_eq!(CircuitState::Open, client.state());
+    assert_eq!(CircuitState::Open, shared_client.state());
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected when circuit is open");
+    assert!(matches!(returned_error, ClientError::CircuitOpen(_)));
+    assert_eq!(1, client.inner().calls());
+    assert_eq!(1, shared_client.inner().calls());
+}
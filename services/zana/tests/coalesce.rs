@@ -0,0 +1,106 @@
+mod util;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use zana::coalesce::CoalescingClient;
+use zana::isbn::Isbn;
+use zana::{BookClient, ClientError};
+
+use crate::util::TestClient;
+
+const DELAY: Duration = Duration::from_millis(50);
+
+fn isbn(isbn: &str) -> Isbn {
+    Isbn::parse(isbn).expect("invalid isbn")
+}
+
+#[tokio::test]
+async fn share_request_between_concurrent_lookups() {
+    let client = CoalescingClient::new(TestClient::with_delay(vec![], DELAY));
+    let isbn = isbn("9780316387316");
+
+    let (first, second, third) = tokio::join!(
+        client.book_by_isbn(&isbn),
+        client.book_by_isbn(&isbn),
+        client.book_by_isbn(&isbn),
+    );
+
+    for book in [first, second, third] {
+        assert_eq!(TestClient::default_book(), book.expect("book expected"));
+    }
+    assert_eq!(1, client.inner().calls());
+    assert_eq!(0, client.in_flight());
+}
+
+#[tokio::test]
+async fn share_error_between_concurrent_lookups() {
+    let client = CoalescingClient::new(TestClient::with_delay(
+        vec![Err(ClientError::Http(503, String::from("unavailable")))],
+        DELAY,
+    ));
+
+    let (first, second) = tokio::join!(
+        client.book("Joe Abercrombie", "The Blade Itself"),
+        client.book("joe abercrombie ", "the blade itself"),
+    );
+
+    for result in [first, second] {
+        let returned_error = result.expect_err("error expected");
+        assert!(matches!(returned_error, ClientError::Http(503, _)));
+    }
+    assert_eq!(1, client.inner().calls());
+}
+
+#[tokio::test]
+async fn make_separate_requests_for_different_lookups() {
+    let client = CoalescingClient::new(TestClient::with_delay(vec![], DELAY));
+    let first_isbn = isbn("9780316387316");
+    let second_isbn = isbn("0-306-40615-2");
+
+    let (first, second) = tokio::join!(
+        client.book_by_isbn(&first_isbn),
+        client.book_by_isbn(&second_isbn),
+    );
+
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn make_new_request_after_previous_completes() {
+    let client = CoalescingClient::new(TestClient::new(vec![]));
+    let isbn = isbn("9780316387316");
+
+    client.book_by_isbn(&isbn).await.expect("book expected");
+    client.book_by_isbn(&isbn).await.expect("book expected");
+
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn continue_request_when_first_caller_is_cancelled() {
+    let client = Arc::new(CoalescingClient::new(TestClient::with_delay(vec![], DELAY)));
+    let isbn = isbn("9780316387316");
+
+    let first = {
+        let client = Arc::clone(&client);
+        let isbn = isbn.clone();
+        tokio::spawn(async move { client.book_by_isbn(&isbn).await })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let second = {
+        let client = Arc::clone(&client);
+        tokio::spawn(async move { client.book_by_isbn(&isbn).await })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    first.abort();
+
+    let book = second
+        .await
+        .expect("task panicked")
+        .expect("book expected after first caller is cancelled");
+    assert_eq!(TestClient::default_book(), book);
+    assert_eq!(2, client.inner().calls());
+}
@@ -0,0 +1,235 @@
+#![cfg(feature = "disk-cache")]
+
+mod util;
+
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use zana::cache::{
+    CacheEntry, CacheKey, CachePolicy, CacheQuery, CacheStore, CachingClient, DiskStore,
+};
+use zana::isbn::Isbn;
+use zana::{BookClient, ClientError};
+
+use crate::util::TestClient;
+
+/// Returns a path in the temporary directory that is removed when dropped.
+struct TempPath(PathBuf);
+
+impl TempPath {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("zana-{}-{}.jsonl", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        Self(path)
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn isbn_key(provider: &str, isbn: &str) -> CacheKey {
+    CacheKey {
+        provider: String::from(provider),
+        query: CacheQuery::Isbn(Isbn::parse(isbn).expect("invalid isbn")),
+    }
+}
+
+fn entry(ttl: Duration) -> CacheEntry {
+    CacheEntry {
+        book: Some(TestClient::default_book()),
+        expires_at: SystemTime::now() + ttl,
+    }
+}
+
+fn line_count(path: &TempPath) -> usize {
+    fs::read_to_string(&path.0)
+        .expect("could not read cache file")
+        .lines()
+        .count()
+}
+
+#[test]
+fn keep_entries_between_runs() {
+    let path = TempPath::new("keep-entries");
+    let key = isbn_key("googlebooks", "9780316387316");
+    let not_found_key = CacheKey {
+        provider: String::from("openlibrary"),
+        query: CacheQuery::book("Joe Abercrombie", "Missing"),
+    };
+    let not_found_entry = CacheEntry {
+        book: None,
+        expires_at: SystemTime::now() + Duration::from_secs(60),
+    };
+
+    {
+        let store = DiskStore::open(&path.0).expect("could not open store");
+        store.put(key.clone(), entry(Duration::from_secs(60)));
+        store.put(not_found_key.clone(), not_found_entry.clone());
+    }
+
+    let store = DiskStore::open(&path.0).expect("could not open store");
+    assert_eq!(2, store.len());
+    assert_eq!(
+        Some(TestClient::default_book()),
+        store.get(&key).and_then(|entry| entry.book)
+    );
+    assert_eq!(None, store.get(&isbn_key("openlibrary", "9780316387316")));
+    let cached = store.get(&not_found_key).expect("not found entry expected");
+    assert_eq!(None, cached.book);
+}
+
+#[test]
+fn drop_expired_and_removed_entries_when_opened() {
+    let path = TempPath::new("drop-expired");
+    let expired_key = isbn_key("googlebooks", "9780316387316");
+    let removed_key = isbn_key("googlebooks", "0-306-40615-2");
+    let kept_key = isbn_key("googlebooks", "978-1-4028-9462-6");
+
+    {
+        let store = DiskStore::open(&path.0).expect("could not open store");
+        store.put(expired_key.clone(), entry(Duration::from_millis(1)));
+        store.put(removed_key.clone(), entry(Duration::from_secs(60)));
+        store.put(kept_key.clone(), entry(Duration::from_secs(60)));
+        store.remove(&removed_key);
+    }
+    std::thread::sleep(Duration::from_millis(10));
+
+    let store = DiskStore::open(&path.0).expect("could not open store");
+    assert_eq!(1, store.len());
+    assert!(store.get(&kept_key).is_some());
+    assert_eq!(1, line_count(&path));
+}
+
+#[test]
+fn compact_replaced_entries() {
+    let path = TempPath::new("compact");
+    let key = isbn_key("googlebooks", "9780316387316");
+
+    let store = DiskStore::open(&path.0).expect("could not open store");
+    for _ in 0..3 {
+        store.put(key.clone(), entry(Duration::from_secs(60)));
+    }
+    assert_eq!(3, line_count(&path));
+
+    store.compact().expect("could not compact store");
+    assert_eq!(1, line_count(&path));
+    assert!(store.get(&key).is_some());
+}
+
+#[test]
+fn skip_invalid_and_unsupported_entries() {
+    let path = TempPath::new("skip-invalid");
+    let key = isbn_key("googlebooks", "9780316387316");
+    {
+        let store = DiskStore::open(&path.0).expect("could not open store");
+        store.put(key.clone(), entry(Duration::from_secs(60)));
+    }
+    let mut content = fs::read_to_string(&path.0).expect("could not read cache file");
+    content.push_str("{\"version\":99,\"provider\":\"googlebooks\"}\n");
+    content.push_str("{\"version\":1,\"provider\":\"goo");
+    fs::write(&path.0, content).expect("could not write cache file");
+
+    let store = DiskStore::open(&path.0).expect("could not open store");
+    assert_eq!(1, store.len());
+    assert!(store.get(&key).is_some());
+}
+
+#[test]
+fn evict_entries_that_expire_first_when_full() {
+    let path = TempPath::new("evict");
+    let capacity = NonZeroUsize::new(2).expect("capacity is not zero");
+    let first_key = isbn_key("googlebooks", "9780316387316");
+    let second_key = isbn_key("googlebooks", "0-306-40615-2");
+    let third_key = isbn_key("googlebooks", "978-1-4028-9462-6");
+
+    {
+        let store = DiskStore::open_with_capacity(&path.0, capacity).expect("could not open store");
+        assert!(!store.put(first_key.clone(), entry(Duration::from_secs(60))));
+        assert!(!store.put(second_key.clone(), entry(Duration::from_secs(30))));
+        assert!(!store.put(first_key.clone(), entry(Duration::from_secs(90))));
+        assert!(store.put(third_key.clone(), entry(Duration::from_secs(60))));
+        assert_eq!(2, store.len());
+        assert!(store.get(&second_key).is_none());
+    }
+
+    let store = DiskStore::open_with_capacity(&path.0, capacity).expect("could not open store");
+    assert_eq!(2, store.len());
+    assert!(store.get(&first_key).is_some());
+    assert!(store.get(&second_key).is_none());
+    assert!(store.get(&third_key).is_some());
+}
+
+#[test]
+fn do_not_report_eviction_when_only_expired_entries_are_removed() {
+    let path = TempPath::new("evict-expired");
+    let capacity = NonZeroUsize::new(2).expect("capacity is not zero");
+    let live_key = isbn_key("googlebooks", "9780316387316");
+    let expired_key = isbn_key("googlebooks", "0-306-40615-2");
+    let new_key = isbn_key("googlebooks", "978-1-4028-9462-6");
+
+    let store = DiskStore::open_with_capacity(&path.0, capacity).expect("could not open store");
+    assert!(!store.put(live_key.clone(), entry(Duration::from_secs(60))));
+    let expired = CacheEntry {
+        book: Some(TestClient::default_book()),
+        expires_at: SystemTime::now() - Duration::from_secs(1),
+    };
+    assert!(!store.put(expired_key.clone(), expired));
+    assert!(!store.put(new_key.clone(), entry(Duration::from_secs(60))));
+    assert_eq!(2, store.len());
+    assert!(store.get(&live_key).is_some());
+    assert!(store.get(&expired_key).is_none());
+}
+
+#[test]
+fn drop_entries_above_capacity_when_opened() {
+    let path = TempPath::new("reduce-capacity");
+    let kept_key = isbn_key("googlebooks", "9780316387316");
+    let dropped_key = isbn_key("googlebooks", "0-306-40615-2");
+
+    {
+        let store = DiskStore::open(&path.0).expect("could not open store");
+        store.put(kept_key.clone(), entry(Duration::from_secs(60)));
+        store.put(dropped_key.clone(), entry(Duration::from_secs(30)));
+    }
+
+    let capacity = NonZeroUsize::new(1).expect("capacity is not zero");
+    let store = DiskStore::open_with_capacity(&path.0, capacity).expect("could not open store");
+    assert_eq!(1, store.len());
+    assert!(store.get(&kept_key).is_some());
+    assert_eq!(1, line_count(&path));
+}
+
+#[tokio::test]
+async fn share_store_between_providers() {
+    let path = TempPath::new("share-store");
+    let store = Arc::new(DiskStore::open(&path.0).expect("could not open store"));
+    let isbn = Isbn::parse("9780316387316").expect("invalid isbn");
+
+    let googlebooks = CachingClient::with_store(
+        TestClient::new(vec![]),
+        "googlebooks",
+        Arc::clone(&store),
+        CachePolicy::default(),
+    );
+    let openlibrary = CachingClient::with_store(
+        TestClient::new(vec![Err(ClientError::NotFound)]),
+        "openlibrary",
+        Arc::clone(&store),
+        CachePolicy::default(),
+    );
+
+    assert!(googlebooks.book_by_isbn(&isbn).await.is_ok());
+    assert!(openlibrary.book_by_isbn(&isbn).await.is_err());
+    assert!(googlebooks.book_by_isbn(&isbn).await.is_ok());
+    assert!(openlibrary.book_by_isbn(&isbn).await.is_err());
+
+    assert_eq!(1, googlebooks.inner().calls());
+    assert_eq!(1, openlibrary.inner().calls());
+    assert_eq!(2, store.len());
+}
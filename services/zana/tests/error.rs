@@ -0,0 +1,77 @@
+use std::time::Duration;
+use zana::{ClientError, LookupKind, RateLimit};
+
+#[test]
+fn transient_errors() {
+    for status_code in [408, 500, 502, 503, 504] {
+        let error = ClientError::Http(status_code, String::new());
+        assert!(
+            error.is_transient(),
+            "{} expected to be transient",
+            status_code
+        );
+        assert!(
+            error.is_retryable(),
+            "{} expected to be retryable",
+            status_code
+        );
+    }
+}
+
+#[test]
+fn rate_limit_is_transient_but_not_retryable() {
+    let error = ClientError::RateLimitExceeded(RateLimit::default());
+    assert!(error.is_transient());
+    assert!(!error.is_retryable());
+}
+
+#[test]
+fn permanent_errors() {
+    let errors = [
+        ClientError::NotFound,
+        ClientError::InvalidInput(String::from("invalid")),
+        ClientError::Unauthorized(401, String::new()),
+        ClientError::Deserialization(String::from("invalid")),
+        ClientError::Http(400, String::new()),
+        ClientError::Http(501, String::new()),
+        ClientError::Unsupported(LookupKind::AuthorAndTitle),
+    ];
+    for error in errors {
+        assert!(
+            !error.is_transient(),
+            "{:?} expected not to be transient",
+            error
+        );
+        assert!(
+            !error.is_retryable(),
+            "{:?} expected not to be retryable",
+            error
+        );
+    }
+}
+
+#[test]
+fn retry_after_from_rate_limit_error() {
+    let error = ClientError::RateLimitExceeded(RateLimit {
+        retry_after: Some(Duration::from_secs(30)),
+        reason: None,
+    });
+    assert_eq!(Some(Duration::from_secs(30)), error.retry_after());
+    assert_eq!(None, ClientError::NotFound.retry_after());
+}
+
+#[test]
+fn circuit_open_is_transient_but_not_retryable() {
+    let error = ClientError::CircuitOpen(Duration::from_secs(10));
+    assert!(error.is_transient());
+    assert!(!error.is_retryable());
+    assert_eq!(Some(Duration::from_secs(10)), error.retry_after());
+}
+
+#[test]
+fn unsupported_error_names_the_lookup() {
+    assert_eq!(
+        "author and title lookup is not supported by the client",
+        ClientError::Unsupported(LookupKind::AuthorAndTitle).to_string()
+    );
+}
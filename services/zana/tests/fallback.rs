@@ -0,0 +1,192 @@
+mod util;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use zana::fallback::{ErrorClass, FallbackClient, FallbackPolicy, Provider};
+use zana::isbn::Isbn;
+use zana::{Book, BookClient, Capabilities, ClientError, LookupKind, RateLimit};
+
+use crate::util::TestClient;
+
+fn isbn() -> Isbn {
+    Isbn::parse("9780316387316").expect("invalid isbn")
+}
+
+fn other_book() -> Book {
+    Book::new(200, "Other description", "http://localhost/link/to/other")
+}
+
+fn create_client(
+    first: &Arc<TestClient>,
+    second: &Arc<TestClient>,
+    policy: FallbackPolicy,
+) -> FallbackClient {
+    FallbackClient::new(
+        vec![
+            Provider::new("first", Arc::clone(first)),
+            Provider::new("second", Arc::clone(second)),
+        ],
+        policy,
+    )
+}
+
+#[tokio::test]
+async fn return_book_from_first_provider() {
+    let first = Arc::new(TestClient::new(vec![]));
+    let second = Arc::new(TestClient::new(vec![]));
+    let client = create_client(&first, &second, FallbackPolicy::default());
+
+    let answer = client
+        .provider_book_by_isbn(&isbn())
+        .await
+        .expect("book expected");
+
+    assert_eq!("first", answer.provider);
+    assert_eq!(TestClient::default_book(), answer.book);
+    assert_eq!(0, second.calls());
+}
+
+#[tokio::test]
+async fn fall_back_on_default_error_classes() {
+    for err in [
+        ClientError::NotFound,
+        ClientError::RateLimitExceeded(RateLimit::default()),
+        ClientError::Http(503, String::new()),
+        ClientError::CircuitOpen(Duration::from_secs(1)),
+    ] {
+        let first = Arc::new(TestClient::new(vec![Err(err.clone())]));
+        let second = Arc::new(TestClient::new(vec![Ok(other_book())]));
+        let client = create_client(&first, &second, FallbackPolicy::default());
+
+        let answer = client
+            .provider_book("Joe Abercrombie", "The Blade Itself")
+            .await
+            .unwrap_or_else(|_| panic!("book expected after {:?}", err));
+
+        assert_eq!("second", answer.provider);
+        assert_eq!(other_book(), answer.book);
+    }
+}
+
+#[tokio::test]
+async fn return_error_that_is_not_fallen_back_on() {
+    let first = Arc::new(TestClient::new(vec![Err(ClientError::InvalidInput(
+        String::from("invalid"),
+    ))]));
+    let second = Arc::new(TestClient::new(vec![]));
+    let client = create_client(&first, &second, FallbackPolicy::default());
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected");
+
+    assert!(matches!(returned_error, ClientError::InvalidInput(_)));
+    assert_eq!(0, second.calls());
+}
+
+#[tokio::test]
+async fn fall_back_only_on_configured_error_classes() {
+    let policy = FallbackPolicy {
+        fallback_on: vec![ErrorClass::NotFound],
+    };
+    let first = Arc::new(TestClient::new(vec![Err(ClientError::RateLimitExceeded(
+        RateLimit::default(),
+    ))]));
+    let second = Arc::new(TestClient::new(vec![]));
+    let client = create_client(&first, &second, policy);
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected");
+
+    assert!(matches!(returned_error, ClientError::RateLimitExceeded(_)));
+    assert_eq!(0, second.calls());
+}
+
+#[tokio::test]
+async fn return_last_error_when_all_providers_fail() {
+    let first = Arc::new(TestClient::new(vec![Err(ClientError::NotFound)]));
+    let second = Arc::new(TestClient::new(vec![Err(ClientError::Http(
+        503,
+        String::new(),
+    ))]));
+    let client = create_client(&first, &second, FallbackPolicy::default());
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected");
+
+    assert!(matches!(returned_error, ClientError::Http(503, _)));
+    assert_eq!(1, first.calls());
+    assert_eq!(1, second.calls());
+}
+
+#[test]
+fn classify_errors() {
+    assert_eq!(ErrorClass::NotFound, ErrorClass::of(&ClientError::NotFound));
+    assert_eq!(
+        ErrorClass::Unavailable,
+        ErrorClass::of(&ClientError::Http(502, String::new()))
+    );
+    assert_eq!(
+        ErrorClass::Other,
+        ErrorClass::of(&ClientError::Http(400, String::new()))
+    );
+    assert_eq!(
+        ErrorClass::Other,
+        ErrorClass::of(&ClientError::Deserialization(String::new()))
+    );
+    assert_eq!(
+        ErrorClass::Unsupported,
+        ErrorClass::of(&ClientError::Unsupported(LookupKind::Isbn))
+    );
+}
+
+#[tokio::test]
+async fn skip_providers_that_do_not_support_the_lookup() {
+    let first = Arc::new(TestClient::with_capabilities(
+        vec![],
+        Capabilities::new(&[LookupKind::Isbn]),
+    ));
+    let second = Arc::new(TestClient::new(vec![Ok(other_book())]));
+    let client = create_client(&first, &second, FallbackPolicy::default());
+
+    let answer = client
+        .provider_book("Joe Abercrombie", "The Blade Itself")
+        .await
+        .expect("book expected");
+
+    assert_eq!("second", answer.provider);
+    assert_eq!(0, first.calls());
+}
+
+#[tokio::test]
+async fn return_unsupported_error_when_no_provider_supports_the_lookup() {
+    let first = Arc::new(TestClient::with_capabilities(
+        vec![],
+        Capabilities::default(),
+    ));
+    let second = Arc::new(TestClient::with_capabilities(
+        vec![],
+        Capabilities::new(&[LookupKind::AuthorAndTitle]),
+    ));
+    let client = create_client(&first, &second, FallbackPolicy::default());
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected");
+
+    assert!(matches!(
+        returned_error,
+        ClientError::Unsupported(LookupKind::Isbn)
+    ));
+    assert_eq!(0, first.calls());
+    assert_eq!(0, second.calls());
+    assert!(client.capabilities().supports(LookupKind::AuthorAndTitle));
+    assert!(!client.capabilities().supports(LookupKind::Isbn));
+}
@@ -2,10 +2,15 @@ mod util;
 
 use httpmock::prelude::*;
 use httpmock::Mock;
+use reqwest::header::{HeaderMap, HeaderValue};
 
-use crate::util::{get_sample, set_property_to_null};
-use zana::googlebooks::Client;
-use zana::{Book, BookClient, ClientError};
+use crate::util::{get_json_value, get_sample, has_query_param, set_property_to_null};
+use std::time::{Duration, SystemTime};
+use zana::googlebooks::{
+    Client, DetailPolicy, PrintType, Projection, QueryOptions, DEFAULT_MATCH_THRESHOLD,
+};
+use zana::isbn::Isbn;
+use zana::{Book, BookClient, ClientError, Identifier, IdentifierType, RateLimit};
 
 const API_KEY: &str = "b85a45ddd5a99124cf4ec9a74f93fcf1";
 const VOLUME_PATH: &str = "/books/v1/volumes";
@@ -17,6 +22,19 @@ fn create_client(server: &MockServer) -> Client {
 fn assert_book_equality(book: Book) {
     let rating = book.rating.expect("ratings should exist");
 
+    assert_eq!("The Blade Itself", book.title);
+    assert_eq!(vec!["Joe Abercrombie"], book.authors);
+    assert_eq!("Orbit", book.publisher);
+    assert_eq!("2015-09-08", book.published_date);
+    assert_eq!("en", book.language);
+    assert_eq!(vec!["Fiction"], book.categories);
+    assert_eq!(
+        vec![
+            Identifier::new(IdentifierType::Isbn10, "0316387312"),
+            Identifier::new(IdentifierType::Isbn13, "9780316387316"),
+        ],
+        book.identifiers
+    );
     assert_eq!(560, book.page_count);
     assert_eq!("The first novel in the First Law Trilogy", book.description);
     assert_eq!("https://localhost/books/v1/info", book.provider_link);
@@ -30,26 +48,83 @@ async fn assert_response(
     response: &str,
 ) -> Result<Book, ClientError> {
     let server = MockServer::start();
-    let m = create_mock(&server, &format!("isbn:{}", isbn), status_code, &response);
+    let m = create_mock(&server, &format!("isbn:{}", isbn), status_code, response);
 
     let client = create_client(&server);
-    let book = client.book_by_isbn(isbn).await;
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await;
     m.assert();
     book
 }
 
+fn create_mock_with_headers<'a>(
+    server: &'a MockServer,
+    query: &str,
+    status_code: u16,
+    headers: &[(&str, &str)],
+    response_body: &str,
+) -> Mock<'a> {
+    server.mock(|when, then| {
+        when.method(GET).path(VOLUME_PATH).query_param("q", query);
+        let mut then = then
+            .status(status_code)
+            .header("Content-Type", "application/json");
+        for (name, value) in headers {
+            then = then.header(*name, *value);
+        }
+        then.body(response_body);
+    })
+}
+
+async fn assert_rate_limit_response(
+    status_code: u16,
+    headers: &[(&str, &str)],
+    response_body: &str,
+) -> RateLimit {
+    let isbn = "9780316387316";
+    let server = MockServer::start();
+    let m = create_mock_with_headers(
+        &server,
+        &format!("isbn:{}", isbn),
+        status_code,
+        headers,
+        response_body,
+    );
+
+    let client = create_client(&server);
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await;
+    m.assert();
+    match book.expect_err("error not returned when expected for exceeded rate limit") {
+        ClientError::RateLimitExceeded(rate_limit) => rate_limit,
+        err => panic!("invalid error type returned {:?}", err),
+    }
+}
+
 fn create_mock<'a>(
     server: &'a MockServer,
     query: &str,
     status_code: u16,
     response_body: &str,
+) -> Mock<'a> {
+    create_mock_with_max_results(server, query, "5", status_code, response_body)
+}
+
+fn create_mock_with_max_results<'a>(
+    server: &'a MockServer,
+    query: &str,
+    max_results: &str,
+    status_code: u16,
+    response_body: &str,
 ) -> Mock<'a> {
     server.mock(|when, then| {
         when.method(GET)
             .path(VOLUME_PATH)
             .query_param("key", API_KEY)
             .query_param("q", query)
-            .query_param("maxResults", "1")
+            .query_param("maxResults", max_results)
             .query_param("fields", "items")
             .header("Accept-Encoding", "gzip");
         then.status(status_code)
@@ -68,15 +143,71 @@ async fn fetch_book_by_isbn() {
     assert_book_equality(book);
 }
 
+#[tokio::test]
+async fn fetch_book_by_isbn10_using_isbn13_query() {
+    let server = MockServer::start();
+    let m = create_mock(
+        &server,
+        "isbn:9780316387316",
+        200,
+        &get_sample("googlebooks_volume.json"),
+    );
+
+    let client = create_client(&server);
+    let book = client
+        .book_by_isbn(&Isbn::parse("ISBN-10: 0-316-38731-2").expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    m.assert();
+    assert_book_equality(book);
+}
+
+#[tokio::test]
+async fn skip_volumes_without_requested_isbn() {
+    let isbn = "9780316387316";
+
+    let book = assert_response(isbn, 200, &get_sample("googlebooks_volumes.json"))
+        .await
+        .expect("could not get book by isbn");
+    assert_book_equality(book);
+}
+
+#[tokio::test]
+async fn match_isbn13_with_isbn10_identifier() {
+    let mut volume = get_json_value("googlebooks_volume.json");
+    *volume
+        .pointer_mut("/items/0/volumeInfo/industryIdentifiers")
+        .expect("identifiers not part of the sample") =
+        serde_json::json!([{"type": "ISBN_10", "identifier": "0316387312"}]);
+
+    let book = assert_response("9780316387316", 200, &volume.to_string())
+        .await
+        .expect("could not get book by isbn");
+    assert_eq!(
+        vec![Identifier::new(IdentifierType::Isbn10, "0316387312")],
+        book.identifiers
+    );
+}
+
+#[tokio::test]
+async fn return_not_found_when_no_volume_has_requested_isbn() {
+    let isbn = "9780316387316";
+
+    let book = assert_response(isbn, 200, &get_sample("googlebooks_study_guide.json")).await;
+    assert!(matches!(book, Err(ClientError::NotFound)));
+}
+
 #[tokio::test]
 async fn fetch_book_by_name_and_author() {
     let author = "Joe Abercrombie";
     let title = "The Blade Itself";
 
     let server = MockServer::start();
-    let m = create_mock(
+    let m = create_mock_with_max_results(
         &server,
-        &format!("inauthor:{} intitle:{}", author, title),
+        r#"inauthor:"joe abercrombie" intitle:"the blade itself""#,
+        "10",
         200,
         &get_sample("googlebooks_volume.json"),
     );
@@ -91,15 +222,90 @@ async fn fetch_book_by_name_and_author() {
     assert_book_equality(book);
 }
 
+#[tokio::test]
+async fn return_best_match_by_name_and_author() {
+    let author = "Abercrombie, Joe";
+    let title = "the blade itself";
+
+    let server = MockServer::start();
+    let m = create_mock_with_max_results(
+        &server,
+        r#"inauthor:"joe abercrombie" intitle:"the blade itself""#,
+        "10",
+        200,
+        &get_sample("googlebooks_volumes.json"),
+    );
+
+    let client = create_client(&server);
+    let book_match = client
+        .best_match(author, title)
+        .await
+        .expect("could not get book by title and author");
+
+    m.assert();
+    assert_eq!(1.0, book_match.score);
+    assert_book_equality(book_match.book);
+}
+
+#[tokio::test]
+async fn return_not_found_when_no_volume_matches_name_and_author() {
+    let author = "Joe Abercrombie";
+    let title = "The Blade Itself";
+
+    let server = MockServer::start();
+    let m = create_mock_with_max_results(
+        &server,
+        r#"inauthor:"joe abercrombie" intitle:"the blade itself""#,
+        "10",
+        200,
+        &get_sample("googlebooks_study_guide.json"),
+    );
+
+    let client = create_client(&server);
+    let book = client.book(author, title).await;
+
+    m.assert();
+    assert!(matches!(book, Err(ClientError::NotFound)));
+}
+
+#[tokio::test]
+async fn return_match_above_configured_threshold() {
+    let author = "Joe Abercrombie";
+    let title = "The Blade Itself";
+
+    let server = MockServer::start();
+    let m = create_mock_with_max_results(
+        &server,
+        r#"inauthor:"joe abercrombie" intitle:"the blade itself""#,
+        "10",
+        200,
+        &get_sample("googlebooks_study_guide.json"),
+    );
+
+    let client = Client::builder(API_KEY, &format!("http://{}", &server.address()))
+        .match_threshold(0.2)
+        .build()
+        .expect("could not create client");
+    let book_match = client
+        .best_match(author, title)
+        .await
+        .expect("could not get book by title and author");
+
+    m.assert();
+    assert!(book_match.score >= 0.2 && book_match.score < DEFAULT_MATCH_THRESHOLD);
+    assert_eq!(
+        "Study Guide: The Blade Itself by Joe Abercrombie",
+        book_match.book.title
+    );
+}
+
 #[tokio::test]
 async fn handle_empty_book_response() {
     let isbn = "9780316387316";
 
     let book = assert_response(isbn, 200, "{}").await;
-    let _returned_error = book
-        .err()
-        .expect("error not returned when expected for missing book");
-    assert!(matches!(ClientError::NotFound, _returned_error));
+    let returned_error = book.expect_err("error not returned when expected for missing book");
+    assert!(matches!(returned_error, ClientError::NotFound));
 }
 
 #[tokio::test]
@@ -108,11 +314,13 @@ async fn return_rate_limit_error() {
 
     for status_code in [429, 403] {
         let book = assert_response(isbn, status_code, "").await;
-        let _returned_error = book.err().expect(&format!(
-            "error not returned when expected for status {}",
-            status_code
-        ));
-        assert!(matches!(ClientError::RateLimitExceeded, _returned_error));
+        let returned_error = book.err().unwrap_or_else(|| {
+            panic!(
+                "error not returned when expected for status {}",
+                status_code
+            )
+        });
+        assert!(matches!(returned_error, ClientError::RateLimitExceeded(_)));
     }
 }
 
@@ -170,6 +378,23 @@ async fn handle_response_with_null_page_count() {
     assert_eq!(0, book.page_count);
 }
 
+#[tokio::test]
+async fn handle_response_with_missing_authors() {
+    let isbn = "9780316387316";
+    let mut json_value = get_json_value("googlebooks_volume.json");
+    json_value
+        .pointer_mut("/items/0/volumeInfo")
+        .and_then(|v| v.as_object_mut())
+        .expect("volumeInfo not part of the sample")
+        .remove("authors");
+    let response = json_value.to_string();
+    let book = assert_response(isbn, 200, &response)
+        .await
+        .expect("could not get book by isbn");
+
+    assert!(book.authors.is_empty());
+}
+
 #[tokio::test]
 async fn handle_other_http_error() {
     let isbn = "9780316387316";
@@ -184,10 +409,12 @@ async fn handle_other_http_error() {
     );
 
     let client = create_client(&server);
-    let book = client.book_by_isbn(isbn).await;
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await;
 
     m.assert();
-    let returned_error = book.err().expect("error not returned when expected");
+    let returned_error = book.expect_err("error not returned when expected");
     match returned_error {
         ClientError::Http(status_code, _) => {
             assert_eq!(expected_status_code, status_code);
@@ -197,3 +424,599 @@ async fn handle_other_http_error() {
         }
     }
 }
+
+#[tokio::test]
+async fn return_unauthorized_error_on_invalid_api_key() {
+    let isbn = "9780316387316";
+    let response = r#"{"error": {"code": 400, "message": "API key not valid. Please pass a valid API key.",
+        "errors": [{"reason": "badRequest"}], "status": "INVALID_ARGUMENT",
+        "details": [{"reason": "API_KEY_INVALID", "domain": "googleapis.com"}]}}"#;
+
+    let book = assert_response(isbn, 400, response).await;
+    let returned_error = book.expect_err("error not returned when expected for invalid key");
+    assert!(matches!(returned_error, ClientError::Unauthorized(400, _)));
+}
+
+#[tokio::test]
+async fn return_unauthorized_error_when_api_is_not_enabled_for_key() {
+    let isbn = "9780316387316";
+    let response = r#"{"error": {"code": 403, "errors": [{"reason": "accessNotConfigured"}]}}"#;
+
+    let book = assert_response(isbn, 403, response).await;
+    let returned_error = book.expect_err("error not returned when expected for blocked key");
+    assert!(matches!(returned_error, ClientError::Unauthorized(403, _)));
+}
+
+#[tokio::test]
+async fn return_rate_limit_error_on_exceeded_quota() {
+    let isbn = "9780316387316";
+    let response = r#"{"error": {"code": 403, "errors": [{"reason": "dailyLimitExceeded"}]}}"#;
+
+    let book = assert_response(isbn, 403, response).await;
+    let returned_error = book.expect_err("error not returned when expected for exceeded quota");
+    assert!(matches!(returned_error, ClientError::RateLimitExceeded(_)));
+}
+
+#[tokio::test]
+async fn return_deserialization_error_on_invalid_response() {
+    let isbn = "9780316387316";
+
+    let book = assert_response(isbn, 200, "<html></html>").await;
+    let returned_error = book.expect_err("error not returned when expected for invalid response");
+    assert!(matches!(returned_error, ClientError::Deserialization(_)));
+}
+
+#[tokio::test]
+async fn return_connect_error_when_service_is_unreachable() {
+    // Nothing is expected to listen on port 1
+    let client = Client::new(API_KEY, "http://127.0.0.1:1").expect("could not create client");
+    let book = client
+        .book_by_isbn(&Isbn::parse("9780316387316").expect("invalid isbn"))
+        .await;
+
+    let returned_error =
+        book.expect_err("error not returned when expected for unreachable service");
+    assert!(matches!(returned_error, ClientError::Connect(_)));
+    assert!(returned_error.is_retryable());
+}
+
+#[tokio::test]
+async fn return_invalid_input_error_when_author_and_title_are_empty() {
+    let server = MockServer::start();
+    let m = server.mock(|when, then| {
+        when.method(GET);
+        then.status(200);
+    });
+    let client = create_client(&server);
+
+    for (author, title) in [(" ", ""), ("...", "!?")] {
+        let book = client.book(author, title).await;
+        let returned_error = book.expect_err("error not returned when expected for empty input");
+        assert!(matches!(returned_error, ClientError::InvalidInput(_)));
+    }
+    m.assert_hits(0);
+}
+
+#[tokio::test]
+async fn query_only_by_title_when_author_is_empty() {
+    let server = MockServer::start();
+    let m = create_mock_with_max_results(
+        &server,
+        r#"intitle:"the blade itself""#,
+        "10",
+        200,
+        &get_sample("googlebooks_volume.json"),
+    );
+
+    let client = create_client(&server);
+    let book = client
+        .book("-", "The Blade Itself")
+        .await
+        .expect("could not get book by title");
+
+    m.assert();
+    assert_book_equality(book);
+}
+
+#[tokio::test]
+async fn return_retry_after_in_seconds() {
+    let rate_limit = assert_rate_limit_response(429, &[("Retry-After", "120")], "").await;
+    assert_eq!(Some(Duration::from_secs(120)), rate_limit.retry_after);
+}
+
+#[tokio::test]
+async fn return_retry_after_as_http_date() {
+    let retry_at = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(300));
+    let rate_limit = assert_rate_limit_response(429, &[("Retry-After", &retry_at)], "").await;
+
+    let retry_after = rate_limit.retry_after.expect("retry after expected");
+    assert!(retry_after > Duration::from_secs(290) && retry_after <= Duration::from_secs(300));
+}
+
+#[tokio::test]
+async fn return_zero_retry_after_for_http_date_in_the_past() {
+    let rate_limit =
+        assert_rate_limit_response(429, &[("Retry-After", "Wed, 21 Oct 2015 07:28:00 GMT")], "")
+            .await;
+    assert_eq!(Some(Duration::ZERO), rate_limit.retry_after);
+}
+
+#[tokio::test]
+async fn return_retry_delay_and_reason_from_response_body() {
+    let response = r#"{"error": {"code": 429, "status": "RESOURCE_EXHAUSTED",
+        "errors": [{"reason": "rateLimitExceeded"}],
+        "details": [{"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "30s"}]}}"#;
+    let rate_limit = assert_rate_limit_response(429, &[], response).await;
+
+    assert_eq!(
+        RateLimit {
+            retry_after: Some(Duration::from_secs(30)),
+            reason: Some(String::from("rateLimitExceeded")),
+        },
+        rate_limit
+    );
+}
+
+#[tokio::test]
+async fn prefer_retry_after_header_over_response_body() {
+    let response = r#"{"error": {"details": [{"retryDelay": "30s"}]}}"#;
+    let rate_limit = assert_rate_limit_response(429, &[("Retry-After", "5")], response).await;
+    assert_eq!(Some(Duration::from_secs(5)), rate_limit.retry_after);
+}
+
+#[tokio::test]
+async fn return_reason_for_exceeded_daily_quota() {
+    let response = r#"{"error": {"code": 403, "errors": [{"reason": "dailyLimitExceeded"}]}}"#;
+    let rate_limit = assert_rate_limit_response(403, &[], response).await;
+
+    assert_eq!(Some(String::from("dailyLimitExceeded")), rate_limit.reason);
+    assert_eq!(None, rate_limit.retry_after);
+}
+
+#[tokio::test]
+async fn return_timeout_error_when_request_exceeds_timeout() {
+    let server = MockServer::start();
+    let m = server.mock(|when, then| {
+        when.method(GET).path(VOLUME_PATH);
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .delay(Duration::from_millis(500))
+            .body(get_sample("googlebooks_volume.json"));
+    });
+
+    let client = Client::builder(API_KEY, &format!("http://{}", &server.address()))
+        .timeout(Duration::from_millis(50))
+        .build()
+        .expect("could not create client");
+    let book = client
+        .book_by_isbn(&Isbn::parse("9780316387316").expect("invalid isbn"))
+        .await;
+
+    m.assert();
+    let returned_error = book.expect_err("error not returned when expected for slow response");
+    assert!(matches!(returned_error, ClientError::Timeout(_)));
+}
+
+#[tokio::test]
+async fn send_default_headers_and_user_agent_suffix() {
+    let server = MockServer::start();
+    let m = server.mock(|when, then| {
+        when.method(GET)
+            .path(VOLUME_PATH)
+            .header("x-request-id", "42")
+            .header(
+                "user-agent",
+                format!("zana/{} (gzip) books-lambda/1.0", env!("CARGO_PKG_VERSION")),
+            );
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(get_sample("googlebooks_volume.json"));
+    });
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-request-id", HeaderValue::from_static("42"));
+    let client = Client::builder(API_KEY, &format!("http://{}", &server.address()))
+        .user_agent_suffix("books-lambda/1.0")
+        .default_headers(headers)
+        .build()
+        .expect("could not create client");
+    let book = client
+        .book_by_isbn(&Isbn::parse("9780316387316").expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    m.assert();
+    assert_book_equality(book);
+}
+
+#[tokio::test]
+async fn send_configured_query_options() {
+    let isbn = "9780316387316";
+    let server = MockServer::start();
+    let m = server.mock(|when, then| {
+        when.method(GET)
+            .path(VOLUME_PATH)
+            .query_param("q", format!("isbn:{}", isbn))
+            .query_param("langRestrict", "sq")
+            .query_param("country", "AL")
+            .query_param("printType", "books")
+            .query_param("projection", "full");
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(get_sample("googlebooks_volume.json"));
+    });
+
+    let client = Client::builder(API_KEY, &format!("http://{}", &server.address()))
+        .query_options(QueryOptions {
+            lang_restrict: Some(String::from("sq")),
+            country: Some(String::from("AL")),
+            print_type: Some(PrintType::Books),
+            projection: Some(Projection::Full),
+        })
+        .build()
+        .expect("could not create client");
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    m.assert();
+    assert_book_equality(book);
+}
+
+#[tokio::test]
+async fn send_request_options_over_configured_options() {
+    let isbn = "9780316387316";
+    let server = MockServer::start();
+    let m = server.mock(|when, then| {
+        when.method(GET)
+            .path(VOLUME_PATH)
+            .query_param("q", format!("isbn:{}", isbn))
+            .query_param("langRestrict", "en")
+            .query_param("printType", "books")
+            .matches(|request| {
+                !has_query_param(request, "country") && !has_query_param(request, "projection")
+            });
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(get_sample("googlebooks_volume.json"));
+    });
+
+    let client = Client::builder(API_KEY, &format!("http://{}", &server.address()))
+        .query_options(QueryOptions {
+            lang_restrict: Some(String::from("sq")),
+            print_type: Some(PrintType::Books),
+            ..QueryOptions::default()
+        })
+        .build()
+        .expect("could not create client");
+    let request_options = QueryOptions {
+        lang_restrict: Some(String::from("en")),
+        ..QueryOptions::default()
+    };
+    let book = client
+        .with_options(&request_options)
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    m.assert();
+    assert_book_equality(book);
+    assert_eq!(
+        Some(String::from("sq")),
+        client.query_options().lang_restrict,
+        "options of the client should not change"
+    );
+}
+
+#[tokio::test]
+async fn do_not_send_options_that_are_not_set() {
+    let isbn = "9780316387316";
+    let server = MockServer::start();
+    let m = server.mock(|when, then| {
+        when.method(GET)
+            .path(VOLUME_PATH)
+            .query_param("q", format!("isbn:{}", isbn))
+            .matches(|request| {
+                ["langRestrict", "country", "printType", "projection"]
+                    .iter()
+                    .all(|name| !has_query_param(request, name))
+            });
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(get_sample("googlebooks_volume.json"));
+    });
+
+    let client = create_client(&server);
+    client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    m.assert();
+}
+
+#[test]
+fn parse_print_type_and_projection() {
+    assert_eq!(PrintType::Books, "Books".parse().expect("valid print type"));
+    assert_eq!(Projection::Lite, "lite".parse().expect("valid projection"));
+    assert!(matches!(
+        "ebooks".parse::<PrintType>(),
+        Err(ClientError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        "compact".parse::<Projection>(),
+        Err(ClientError::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn format_query_options_as_query_parameters() {
+    let options = QueryOptions {
+        lang_restrict: Some(String::from("sq")),
+        country: Some(String::from("AL")),
+        print_type: Some(PrintType::Books),
+        ..QueryOptions::default()
+    };
+
+    assert_eq!(
+        "langRestrict=sq&country=AL&printType=books",
+        options.to_string()
+    );
+    assert_eq!("", QueryOptions::default().to_string());
+}
+
+const VOLUME_ID: &str = "wwspvAEACAAJ";
+
+fn create_volume_mock<'a>(
+    server: &'a MockServer,
+    status_code: u16,
+    response_body: &str,
+) -> Mock<'a> {
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("{}/{}", VOLUME_PATH, VOLUME_ID))
+            .query_param("key", API_KEY);
+        then.status(status_code)
+            .header("Content-Type", "application/json")
+            .body(response_body);
+    })
+}
+
+fn create_detail_client(server: &MockServer, detail_policy: DetailPolicy) -> Client {
+    Client::builder(API_KEY, &format!("http://{}", &server.address()))
+        .detail_policy(detail_policy)
+        .build()
+        .expect("could not create client")
+}
+
+fn assert_detailed_book(book: &Book) {
+    assert_eq!(
+        "The first novel in the First Law Trilogy. Logen Ninefingers, infamous barbarian, has finally run out of luck.",
+        book.description
+    );
+    assert_eq!(vec!["Fiction / Fantasy / Epic", "Fiction"], book.categories);
+}
+
+#[tokio::test]
+async fn fetch_book_by_volume_id() {
+    let server = MockServer::start();
+    let m = create_volume_mock(&server, 200, &get_sample("googlebooks_volume_details.json"));
+
+    let client = create_client(&server);
+    let book = client
+        .book_by_volume_id(VOLUME_ID)
+        .await
+        .expect("could not get book by volume id");
+
+    m.assert();
+    assert_detailed_book(&book);
+    assert_eq!("The Blade Itself", book.title);
+    assert_eq!(560, book.page_count);
+    assert_eq!(
+        Some("9780316387316"),
+        book.identifier(&IdentifierType::Isbn13)
+    );
+}
+
+#[tokio::test]
+async fn add_main_category_of_volume_to_categories() {
+    let mut volume = get_json_value("googlebooks_volume_details.json");
+    volume["volumeInfo"]["categories"] = serde_json::json!(["Fiction"]);
+
+    let server = MockServer::start();
+    let m = create_volume_mock(&server, 200, &volume.to_string());
+
+    let client = create_client(&server);
+    let book = client
+        .book_by_volume_id(VOLUME_ID)
+        .await
+        .expect("could not get book by volume id");
+
+    m.assert();
+    assert_eq!(vec!["Fiction / Fantasy / Epic", "Fiction"], book.categories);
+}
+
+#[tokio::test]
+async fn return_not_found_for_unknown_volume_id() {
+    let server = MockServer::start();
+    let m = create_volume_mock(&server, 404, "");
+
+    let client = create_client(&server);
+    let book = client.book_by_volume_id(VOLUME_ID).await;
+
+    m.assert();
+    assert!(matches!(book, Err(ClientError::NotFound)));
+}
+
+#[tokio::test]
+async fn return_error_for_invalid_volume_id() {
+    let server = MockServer::start();
+    let m = server.mock(|when, then| {
+        when.method(GET);
+        then.status(200);
+    });
+
+    let client = create_client(&server);
+    for volume_id in ["", "  ", "../volumes?q=isbn", "wwspvAEACAAJ/"] {
+        let book = client.book_by_volume_id(volume_id).await;
+        assert!(
+            matches!(book, Err(ClientError::InvalidInput(_))),
+            "invalid input expected for {:?}",
+            volume_id
+        );
+    }
+    m.assert_hits(0);
+}
+
+#[tokio::test]
+async fn fetch_details_of_incomplete_volume_found_by_isbn() {
+    let isbn = "9780316387316";
+    let json_value =
+        set_property_to_null("googlebooks_volume.json", "/items/0/volumeInfo/description");
+
+    let server = MockServer::start();
+    let search_mock = create_mock(
+        &server,
+        &format!("isbn:{}", isbn),
+        200,
+        &json_value.to_string(),
+    );
+    let volume_mock =
+        create_volume_mock(&server, 200, &get_sample("googlebooks_volume_details.json"));
+
+    let client = create_detail_client(&server, DetailPolicy::WhenIncomplete);
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    search_mock.assert();
+    volume_mock.assert();
+    assert_detailed_book(&book);
+}
+
+#[tokio::test]
+async fn do_not_fetch_details_of_complete_volume_found_by_isbn() {
+    let isbn = "9780316387316";
+
+    let server = MockServer::start();
+    let search_mock = create_mock(
+        &server,
+        &format!("isbn:{}", isbn),
+        200,
+        &get_sample("googlebooks_volume.json"),
+    );
+    let volume_mock =
+        create_volume_mock(&server, 200, &get_sample("googlebooks_volume_details.json"));
+
+    let client = create_detail_client(&server, DetailPolicy::WhenIncomplete);
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    search_mock.assert();
+    volume_mock.assert_hits(0);
+    assert_book_equality(book);
+}
+
+#[tokio::test]
+async fn always_fetch_details_of_volume_found_by_isbn() {
+    let isbn = "9780316387316";
+
+    let server = MockServer::start();
+    let search_mock = create_mock(
+        &server,
+        &format!("isbn:{}", isbn),
+        200,
+        &get_sample("googlebooks_volume.json"),
+    );
+    let volume_mock =
+        create_volume_mock(&server, 200, &get_sample("googlebooks_volume_details.json"));
+
+    let client = create_detail_client(&server, DetailPolicy::Always);
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    search_mock.assert();
+    volume_mock.assert();
+    assert_detailed_book(&book);
+}
+
+#[tokio::test]
+async fn fetch_full_details_of_volume_found_by_isbn_with_lite_projection() {
+    let isbn = "9780316387316";
+    let json_value =
+        set_property_to_null("googlebooks_volume.json", "/items/0/volumeInfo/description");
+
+    let server = MockServer::start();
+    let search_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path(VOLUME_PATH)
+            .query_param("q", format!("isbn:{}", isbn))
+            .query_param("country", "AL")
+            .query_param("projection", "lite");
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(json_value.to_string());
+    });
+    let volume_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("{}/{}", VOLUME_PATH, VOLUME_ID))
+            .query_param("country", "AL")
+            .query_param("projection", "full")
+            .matches(|request| !has_query_param(request, "langRestrict"));
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(get_sample("googlebooks_volume_details.json"));
+    });
+
+    let client = Client::builder(API_KEY, &format!("http://{}", &server.address()))
+        .detail_policy(DetailPolicy::WhenIncomplete)
+        .query_options(QueryOptions {
+            lang_restrict: Some(String::from("en")),
+            country: Some(String::from("AL")),
+            projection: Some(Projection::Lite),
+            ..QueryOptions::default()
+        })
+        .build()
+        .expect("could not create client");
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    search_mock.assert();
+    volume_mock.assert();
+    assert_detailed_book(&book);
+}
+
+#[tokio::test]
+async fn return_volume_found_by_isbn_when_details_fail() {
+    let isbn = "9780316387316";
+    let json_value =
+        set_property_to_null("googlebooks_volume.json", "/items/0/volumeInfo/pageCount");
+
+    let server = MockServer::start();
+    let search_mock = create_mock(
+        &server,
+        &format!("isbn:{}", isbn),
+        200,
+        &json_value.to_string(),
+    );
+    let volume_mock = create_volume_mock(&server, 503, "");
+
+    let client = create_detail_client(&server, DetailPolicy::WhenIncomplete);
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    search_mock.assert();
+    volume_mock.assert();
+    assert_eq!(0, book.page_count);
+    assert_eq!("The first novel in the First Law Trilogy", book.description);
+}
@@ -0,0 +1,186 @@
+use zana::isbn::{Isbn, IsbnError, IsbnParts};
+
+#[test]
+fn parse_isbn13() {
+    let isbn = Isbn::parse("9780316387316").expect("could not parse isbn");
+    assert_eq!("9780316387316", isbn.as_str());
+    assert_eq!("9780316387316", isbn.to_string());
+}
+
+#[test]
+fn parse_isbn10() {
+    let isbn = Isbn::parse("0316387312").expect("could not parse isbn");
+    assert_eq!("9780316387316", isbn.to_isbn13());
+    assert_eq!(Some(String::from("0316387312")), isbn.to_isbn10());
+}
+
+#[test]
+fn parse_isbn10_with_x_check_digit() {
+    for input in ["080442957X", "080442957x"] {
+        let isbn = Isbn::parse(input).expect("could not parse isbn");
+        assert_eq!("9780804429573", isbn.as_str());
+        assert_eq!(Some(String::from("080442957X")), isbn.to_isbn10());
+    }
+}
+
+#[test]
+fn parse_isbn_with_separators_and_prefixes() {
+    for input in [
+        "978-0-316-38731-6",
+        "978 0 316 38731 6",
+        " 9780316387316 ",
+        "ISBN 978-0-316-38731-6",
+        "isbn:9780316387316",
+        "ISBN-13: 978-0-316-38731-6",
+        "ISBN13 9780316387316",
+        "ISBN-10: 0-316-38731-2",
+        "ISBN 0\u{2013}316\u{2013}38731\u{2013}2",
+    ] {
+        let isbn = Isbn::parse(input).unwrap_or_else(|_| panic!("could not parse {}", input));
+        assert_eq!("9780316387316", isbn.as_str());
+    }
+}
+
+#[test]
+fn isbn10_and_isbn13_of_same_book_are_equal() {
+    let isbn10: Isbn = "0316387312".parse().expect("could not parse isbn");
+    let isbn13: Isbn = "9780316387316".parse().expect("could not parse isbn");
+    assert_eq!(isbn10, isbn13);
+}
+
+#[test]
+fn isbn13_with_979_prefix_has_no_isbn10() {
+    let isbn = Isbn::parse("979-10-90636-07-1").expect("could not parse isbn");
+    assert_eq!("9791090636071", isbn.as_str());
+    assert_eq!(None, isbn.to_isbn10());
+}
+
+#[test]
+fn return_error_on_invalid_checksum() {
+    for input in ["9780316387317", "0316387313"] {
+        assert_eq!(Err(IsbnError::InvalidChecksum), Isbn::parse(input));
+    }
+}
+
+#[test]
+fn return_error_on_invalid_length() {
+    assert_eq!(Err(IsbnError::InvalidLength(0)), Isbn::parse(""));
+    assert_eq!(
+        Err(IsbnError::InvalidLength(12)),
+        Isbn::parse("978031638731")
+    );
+}
+
+#[test]
+fn return_error_on_invalid_character() {
+    assert_eq!(
+        Err(IsbnError::InvalidCharacter('a')),
+        Isbn::parse("978031638731a")
+    );
+    assert_eq!(
+        Err(IsbnError::InvalidCharacter('X')),
+        Isbn::parse("978031638731X")
+    );
+    assert_eq!(
+        Err(IsbnError::InvalidCharacter('X')),
+        Isbn::parse("03163X7312")
+    );
+}
+
+#[test]
+fn return_error_on_invalid_isbn13_prefix() {
+    assert_eq!(Err(IsbnError::InvalidPrefix), Isbn::parse("9770316387316"));
+}
+
+#[test]
+fn hyphenate_isbn() {
+    for (input, expected) in [
+        ("9780316387316", "978-0-316-38731-6"),
+        ("9781591026419", "978-1-59102-641-9"),
+        ("9782070360024", "978-2-07-036002-4"),
+        ("9783161484100", "978-3-16-148410-0"),
+        ("9791090636071", "979-10-90636-07-1"),
+    ] {
+        let isbn = Isbn::parse(input).expect("could not parse isbn");
+        assert_eq!(Some(String::from(expected)), isbn.hyphenated());
+    }
+}
+
+#[test]
+fn hyphenate_isbn10() {
+    let isbn = Isbn::parse("9780316387316").expect("could not parse isbn");
+    assert_eq!(
+        Some(String::from("0-316-38731-2")),
+        isbn.hyphenated_isbn10()
+    );
+
+    let isbn = Isbn::parse("9791090636071").expect("could not parse isbn");
+    assert_eq!(None, isbn.hyphenated_isbn10());
+}
+
+#[test]
+fn hyphenate_isbn_in_split_english_language_ranges() {
+    for (input, expected) in [
+        ("9781732123403", "978-1-7321234-0-3"),
+        ("9781861978769", "978-1-86197-876-9"),
+        ("9781867301233", "978-1-8673-0123-3"),
+        ("9781904633129", "978-1-904633-12-9"),
+        ("9781987654325", "978-1-9876-5432-5"),
+        ("9781998990016", "978-1-9989900-1-6"),
+    ] {
+        let isbn = Isbn::parse(input).expect("could not parse isbn");
+        assert_eq!(Some(String::from(expected)), isbn.hyphenated());
+    }
+
+    let isbn = Isbn::parse("9781861978769").expect("could not parse isbn");
+    assert_eq!(
+        Some(String::from("1-86197-876-6")),
+        isbn.hyphenated_isbn10()
+    );
+}
+
+#[test]
+fn hyphenate_isbn_in_other_registration_group() {
+    for (input, expected) in [
+        ("9789170551239", "978-91-7055-123-9"),
+        ("9789129543216", "978-91-29-54321-6"),
+    ] {
+        let isbn = Isbn::parse(input).expect("could not parse isbn");
+        assert_eq!(Some(String::from(expected)), isbn.hyphenated());
+    }
+}
+
+#[test]
+#[ignore = "ranges.txt only has a subset of the registration groups until it is regenerated with tools/isbn_ranges"]
+fn hyphenate_isbn_in_registration_group_outside_of_subset() {
+    // 978-88 is the registration group of the Italian language area
+    let isbn = Isbn::parse("9788804668237").expect("could not parse isbn");
+    assert_eq!(Some(String::from("978-88-04-66823-7")), isbn.hyphenated());
+}
+
+#[test]
+fn isbn_parts_for_albanian_registration_group() {
+    let isbn = Isbn::parse("99927-1-011-X").expect("could not parse isbn");
+    let parts = isbn.parts().expect("parts expected for isbn");
+
+    assert_eq!(
+        IsbnParts {
+            prefix: String::from("978"),
+            registration_group: String::from("99927"),
+            registration_group_agency: String::from("Albania"),
+            registrant: String::from("1"),
+            publication: String::from("011"),
+            check_digit: '1',
+        },
+        parts
+    );
+    assert_eq!(Some(String::from("978-99927-1-011-1")), isbn.hyphenated());
+}
+
+#[test]
+fn no_parts_for_unused_range() {
+    // 978-6600000 is a registration group range that is not in use
+    let isbn = Isbn::parse("9786600000008").expect("could not parse isbn");
+    assert_eq!(None, isbn.parts());
+    assert_eq!(None, isbn.hyphenated());
+}
@@ -0,0 +1,178 @@
+mod util;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use zana::fallback::Provider;
+use zana::isbn::Isbn;
+use zana::merge::{FieldPolicy, MergePolicy, MergingClient};
+use zana::{Book, BookClient, Capabilities, ClientError, LookupKind, Rating};
+
+use crate::util::TestClient;
+
+fn isbn() -> Isbn {
+    Isbn::parse("9780316387316").expect("invalid isbn")
+}
+
+fn first_book() -> Book {
+    let mut book = Book::new(0, "Short description", "http://localhost/first");
+    book.title = String::from("The Blade Itself");
+    book.rating = Some(Rating {
+        average_rating: 4.0,
+        ratings_count: 10,
+    });
+    book
+}
+
+fn second_book() -> Book {
+    let mut book = Book::new(
+        544,
+        "A much longer description of the book",
+        "http://localhost/second",
+    );
+    book.title = String::from("The Blade Itself (First Law)");
+    book.authors = vec![String::from("Joe Abercrombie")];
+    book.rating = Some(Rating {
+        average_rating: 4.2,
+        ratings_count: 250,
+    });
+    book
+}
+
+fn create_client(
+    first: &Arc<TestClient>,
+    second: &Arc<TestClient>,
+    policy: MergePolicy,
+) -> MergingClient {
+    MergingClient::new(
+        vec![
+            Provider::new("first", Arc::clone(first)),
+            Provider::new("second", Arc::clone(second)),
+        ],
+        policy,
+    )
+}
+
+#[tokio::test]
+async fn merge_books_with_default_policy() {
+    let first = Arc::new(TestClient::new(vec![Ok(first_book())]));
+    let second = Arc::new(TestClient::new(vec![Ok(second_book())]));
+    let client = create_client(&first, &second, MergePolicy::default());
+
+    let book = client.book_by_isbn(&isbn()).await.expect("book expected");
+
+    assert_eq!("The Blade Itself", book.title);
+    assert_eq!(vec![String::from("Joe Abercrombie")], book.authors);
+    assert_eq!(544, book.page_count);
+    assert_eq!("A much longer description of the book", book.description);
+    assert_eq!("http://localhost/first", book.provider_link);
+    assert_eq!(second_book().rating, book.rating);
+    assert_eq!(1, first.calls());
+    assert_eq!(1, second.calls());
+}
+
+#[tokio::test]
+async fn merge_books_with_preferred_provider() {
+    let first = Arc::new(TestClient::new(vec![Ok(first_book())]));
+    let second = Arc::new(TestClient::new(vec![Ok(second_book())]));
+    let policy = MergePolicy {
+        title: FieldPolicy::Preferred(String::from("second")),
+        provider_link: FieldPolicy::Preferred(String::from("second")),
+        description: FieldPolicy::Preferred(String::from("first")),
+        authors: FieldPolicy::Preferred(String::from("first")),
+        ..MergePolicy::default()
+    };
+    let client = create_client(&first, &second, policy);
+
+    let book = client
+        .book("Joe Abercrombie", "The Blade Itself")
+        .await
+        .expect("book expected");
+
+    assert_eq!("The Blade Itself (First Law)", book.title);
+    assert_eq!("http://localhost/second", book.provider_link);
+    assert_eq!("Short description", book.description);
+    // The preferred provider has no authors, so they are taken from the other provider
+    assert_eq!(vec![String::from("Joe Abercrombie")], book.authors);
+}
+
+#[tokio::test]
+async fn skip_failed_providers() {
+    let first = Arc::new(TestClient::new(vec![Err(ClientError::Http(
+        503,
+        String::new(),
+    ))]));
+    let second = Arc::new(TestClient::new(vec![Ok(second_book())]));
+    let client = create_client(&first, &second, MergePolicy::default());
+
+    let book = client.book_by_isbn(&isbn()).await.expect("book expected");
+
+    assert_eq!(second_book(), book);
+}
+
+#[tokio::test]
+async fn return_error_when_all_providers_fail() {
+    let first = Arc::new(TestClient::new(vec![Err(ClientError::NotFound)]));
+    let second = Arc::new(TestClient::new(vec![Err(ClientError::Http(
+        503,
+        String::new(),
+    ))]));
+    let client = create_client(&first, &second, MergePolicy::default());
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected");
+
+    assert!(
+        matches!(returned_error, ClientError::Http(503, _)),
+        "unexpected error: {:?}",
+        returned_error
+    );
+}
+
+#[tokio::test]
+async fn return_not_found_when_no_provider_has_the_book() {
+    let first = Arc::new(TestClient::new(vec![Err(ClientError::NotFound)]));
+    let second = Arc::new(TestClient::new(vec![Err(ClientError::NotFound)]));
+    let client = create_client(&first, &second, MergePolicy::default());
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected");
+
+    assert!(matches!(returned_error, ClientError::NotFound));
+}
+
+#[tokio::test]
+async fn query_providers_concurrently() {
+    let delay = Duration::from_millis(200);
+    let first = Arc::new(TestClient::with_delay(vec![Ok(first_book())], delay));
+    let second = Arc::new(TestClient::with_delay(vec![Ok(second_book())], delay));
+    let client = create_client(&first, &second, MergePolicy::default());
+
+    let start = Instant::now();
+    client.book_by_isbn(&isbn()).await.expect("book expected");
+
+    assert!(start.elapsed() < delay * 2, "providers queried in sequence");
+}
+
+#[tokio::test]
+async fn skip_providers_that_do_not_support_the_lookup() {
+    let first = Arc::new(TestClient::with_capabilities(
+        vec![Ok(first_book())],
+        Capabilities::new(&[LookupKind::AuthorAndTitle]),
+    ));
+    let second = Arc::new(TestClient::new(vec![Err(ClientError::NotFound)]));
+    let client = create_client(&first, &second, MergePolicy::default());
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected");
+
+    // Not finding the book is more useful to the caller than the lookup not being supported
+    assert!(matches!(returned_error, ClientError::NotFound));
+    assert_eq!(0, first.calls());
+}
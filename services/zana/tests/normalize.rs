@@ -0,0 +1,79 @@
+use zana::normalize::{
+    author_similarity, fold, normalize, normalize_author, normalize_title, similarity,
+    title_similarity,
+};
+
+#[test]
+fn fold_diacritics() {
+    assert_eq!("keshtjella", fold("Kështjella"));
+    assert_eq!("cajupi", fold("Çajupi"));
+    assert_eq!("garcia marquez", fold("García Márquez"));
+}
+
+#[test]
+fn fold_letters_that_do_not_decompose() {
+    assert_eq!("strasse", fold("Straße"));
+    assert_eq!("lodz", fold("Łódź"));
+    assert_eq!("oresund", fold("Øresund"));
+    assert_eq!("aesop", fold("Æsop"));
+}
+
+#[test]
+fn normalize_punctuation_and_whitespace() {
+    assert_eq!(
+        "harry potter and the philosopher s stone",
+        normalize("  Harry Potter and the Philosopher's Stone!")
+    );
+    assert_eq!(
+        "gjenerali i ushtrise se vdekur",
+        normalize("Gjenerali   i ushtrisë së vdekur")
+    );
+}
+
+#[test]
+fn normalize_title_without_stop_words() {
+    assert_eq!("blade itself", normalize_title("The Blade Itself"));
+    assert_eq!("prilli thyer", normalize_title("Prilli i thyer"));
+    assert_eq!("lord rings", normalize_title("The Lord of the Rings"));
+}
+
+#[test]
+fn keep_stop_words_when_title_has_no_other_words() {
+    assert_eq!("the", normalize_title("The"));
+    assert_eq!("e", normalize_title("E"));
+}
+
+#[test]
+fn normalize_author_written_with_surname_first() {
+    assert_eq!("ismail kadare", normalize_author("Kadare, Ismail"));
+    assert_eq!("joe abercrombie", normalize_author("Joe Abercrombie"));
+    assert_eq!("naim frasheri", normalize_author("Frashëri,Naim"));
+}
+
+#[test]
+fn keep_author_with_several_commas() {
+    assert_eq!("smith john jr", normalize_author("Smith, John, Jr."));
+    assert_eq!("kadare", normalize_author("Kadare,"));
+}
+
+#[test]
+fn score_similarity() {
+    assert_eq!(1.0, similarity("kadare", "kadare"));
+    assert_eq!(0.0, similarity("kadare", ""));
+    assert_eq!(0.0, similarity("ab", "cd"));
+    assert!(similarity("kadareja", "kadare") > 0.8);
+}
+
+#[test]
+fn score_title_similarity() {
+    assert_eq!(1.0, title_similarity("Kështjella", "KESHTJELLA"));
+    assert_eq!(1.0, title_similarity("The Blade Itself", "Blade itself"));
+    assert!(title_similarity("The Blade Itself", "Before They Are Hanged") < 0.5);
+}
+
+#[test]
+fn score_author_similarity() {
+    assert_eq!(1.0, author_similarity("Kadare, Ismail", "Ismail Kadare"));
+    assert_eq!(1.0, author_similarity("Kadare Ismail", "Ismail Kadarë"));
+    assert!(author_similarity("Ismail Kadare", "Joe Abercrombie") < 0.5);
+}
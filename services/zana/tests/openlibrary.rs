@@ -2,14 +2,20 @@ mod util;
 
 use httpmock::prelude::*;
 use httpmock::Mock;
-use zana::{Book, BookClient, ClientError, Rating};
+use std::time::Duration;
+use zana::{Book, BookClient, ClientError, Identifier, IdentifierType, Rating};
 
-use crate::util::{get_json_value, get_sample};
-use zana::openlibrary::Client;
+use crate::util::{get_json_value, get_sample, has_query_param};
+use zana::cache::{CachePolicy, CachingClient};
+use zana::circuit::{CircuitBreaker, CircuitPolicy};
+use zana::isbn::Isbn;
+use zana::openlibrary::{marc_language_code, Client};
+use zana::retry::{RetryPolicy, RetryingClient};
 
 const ISBN_PATH: &str = "/isbn";
 const RATINGS_PATH: &str = "/ratings";
 const WORKS_PATH: &str = "/works/OL8400950W";
+const AUTHOR_PATH: &str = "/authors/OL2801083A";
 
 fn create_client(server: &MockServer) -> Client {
     Client::new(&format!("http://{}", &server.address())).expect("could not create client")
@@ -35,7 +41,24 @@ fn create_default_expected_book(port: u16) -> Book {
     let description = "Logen Ninefingers, infamous barbarian, has finally run out of luck.";
 
     let provider_link = format!("http://127.0.0.1:{}/works/{}", port, works_id);
-    Book::new_with_rating(542, description, &provider_link, ratings)
+    let mut book = Book::new_with_rating(542, description, &provider_link, ratings);
+    book.title = String::from("The blade itself");
+    book.authors = vec![String::from("Joe Abercrombie")];
+    book.published_date = String::from("2015");
+    book.language = String::from("eng");
+    book.categories = vec![
+        String::from("Fiction, fantasy, general"),
+        String::from("Fantasy fiction"),
+        String::from("Fantasy"),
+        String::from("Fiction"),
+    ];
+    book.identifiers = vec![
+        Identifier::new(IdentifierType::Isbn10, "0316387312"),
+        Identifier::new(IdentifierType::Isbn13, "9780316387316"),
+        Identifier::new(IdentifierType::Lccn, "2015017611"),
+        Identifier::new(IdentifierType::Oclc, "908838780"),
+    ];
+    book
 }
 
 async fn assert_successful_fetch(
@@ -61,15 +84,23 @@ async fn assert_successful_fetch(
         ratings_sample,
     );
 
+    let author_mock = create_mock(
+        &server,
+        &format!("{}.json", AUTHOR_PATH),
+        200,
+        &get_sample("openlibrary_author.json"),
+    );
+
     let client = create_client(&server);
     let book = client
-        .book_by_isbn(isbn)
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
         .await
         .expect("could not get book by isbn");
 
     isbn_mock.assert();
     works_mock.assert();
     ratings_mock.assert();
+    author_mock.assert();
     (server, book)
 }
 
@@ -78,7 +109,7 @@ async fn fetch_book_by_isbn() {
     let isbn = "9780316387316";
 
     let (server, book) = assert_successful_fetch(
-        &isbn,
+        isbn,
         &get_sample("openlibrary_isbn.json"),
         &get_sample("openlibrary_works.json"),
         &get_sample("openlibrary_ratings.json"),
@@ -92,13 +123,14 @@ async fn handle_response_with_description_as_string() {
     let isbn = "9780316387316";
 
     let mut json_value = get_json_value("openlibrary_works.json");
-    json_value
+    *json_value
         .pointer_mut("/description")
-        .map(|v| *v = "Logen Ninefingers, infamous barbarian, has finally run out of luck.".into());
+        .expect("description not part of the sample") =
+        "Logen Ninefingers, infamous barbarian, has finally run out of luck.".into();
     let response = json_value.to_string();
 
     let (server, book) = assert_successful_fetch(
-        &isbn,
+        isbn,
         &get_sample("openlibrary_isbn.json"),
         &response,
         &get_sample("openlibrary_ratings.json"),
@@ -119,7 +151,7 @@ async fn handle_response_with_no_description() {
     let response = json_value.to_string();
 
     let (server, book) = assert_successful_fetch(
-        &isbn,
+        isbn,
         &get_sample("openlibrary_isbn.json"),
         &response,
         &get_sample("openlibrary_ratings.json"),
@@ -146,7 +178,7 @@ async fn handle_response_with_no_ratings() {
     let response = json_value.to_string();
 
     let (server, book) = assert_successful_fetch(
-        &isbn,
+        isbn,
         &get_sample("openlibrary_isbn.json"),
         &get_sample("openlibrary_works.json"),
         &response,
@@ -169,7 +201,7 @@ async fn handle_response_with_no_number_of_pages() {
     let response = json_value.to_string();
 
     let (_, book) = assert_successful_fetch(
-        &isbn,
+        isbn,
         &response,
         &get_sample("openlibrary_works.json"),
         &get_sample("openlibrary_ratings.json"),
@@ -178,6 +210,121 @@ async fn handle_response_with_no_number_of_pages() {
     assert_eq!(0, book.page_count);
 }
 
+#[tokio::test]
+async fn use_work_authors_when_book_has_no_authors() {
+    let isbn = "9780316387316";
+
+    let mut json_value = get_json_value("openlibrary_isbn.json");
+    json_value
+        .as_object_mut()
+        .expect("sample expected to be an object")
+        .remove("authors");
+    let response = json_value.to_string();
+
+    let (server, book) = assert_successful_fetch(
+        isbn,
+        &response,
+        &get_sample("openlibrary_works.json"),
+        &get_sample("openlibrary_ratings.json"),
+    )
+    .await;
+    assert_eq!(create_default_expected_book(server.port()), book);
+}
+
+#[tokio::test]
+async fn skip_authors_that_cannot_be_fetched() {
+    let isbn = "9780316387316";
+
+    let server = MockServer::start();
+    create_mock(
+        &server,
+        &format!("{}/{}.json", ISBN_PATH, isbn),
+        200,
+        &get_sample("openlibrary_isbn.json"),
+    );
+    create_mock(
+        &server,
+        &format!("{}.json", WORKS_PATH),
+        200,
+        &get_sample("openlibrary_works.json"),
+    );
+    create_mock(
+        &server,
+        &format!("{}{}.json", WORKS_PATH, RATINGS_PATH),
+        200,
+        &get_sample("openlibrary_ratings.json"),
+    );
+    let author_mock = create_mock(&server, &format!("{}.json", AUTHOR_PATH), 500, "");
+
+    let client = create_client(&server);
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    author_mock.assert();
+    assert!(book.authors.is_empty());
+    assert_eq!("The blade itself", book.title);
+}
+
+#[tokio::test]
+async fn fetch_authors_concurrently_in_order() {
+    let isbn = "9780316387316";
+    let mut book_response = get_json_value("openlibrary_isbn.json");
+    book_response["authors"] = serde_json::json!([
+        {"key": "/authors/OL1A"},
+        {"key": "/authors/OL2A"}
+    ]);
+
+    let server = MockServer::start();
+    create_mock(
+        &server,
+        &format!("{}/{}.json", ISBN_PATH, isbn),
+        200,
+        &book_response.to_string(),
+    );
+    create_mock(
+        &server,
+        &format!("{}.json", WORKS_PATH),
+        200,
+        &get_sample("openlibrary_works.json"),
+    );
+    create_mock(
+        &server,
+        &format!("{}{}.json", WORKS_PATH, RATINGS_PATH),
+        200,
+        &get_sample("openlibrary_ratings.json"),
+    );
+    let author_mocks: Vec<_> = [
+        ("OL1A", "First Author", 300),
+        ("OL2A", "Second Author", 200),
+    ]
+    .iter()
+    .map(|(key, name, delay)| {
+        server.mock(|when, then| {
+            when.method(GET).path(format!("/authors/{}.json", key));
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .delay(Duration::from_millis(*delay))
+                .body(format!(r#"{{"name":"{}"}}"#, name));
+        })
+    })
+    .collect();
+
+    let client = create_client(&server);
+    let start = std::time::Instant::now();
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get book by isbn");
+
+    author_mocks
+        .iter()
+        .for_each(|author_mock| author_mock.assert());
+    assert_eq!(vec!["First Author", "Second Author"], book.authors);
+    assert!(start.elapsed() < Duration::from_millis(450));
+}
+
 #[tokio::test]
 async fn no_book_returned_on_404_from_isbn_call() {
     let isbn = "9780316387316";
@@ -186,13 +333,14 @@ async fn no_book_returned_on_404_from_isbn_call() {
     let isbn_mock = create_mock(&server, &format!("{}/{}.json", ISBN_PATH, isbn), 404, "");
 
     let client = create_client(&server);
-    let book = client.book_by_isbn(isbn).await;
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await;
 
     isbn_mock.assert();
-    let _returned_error = book
-        .err()
-        .expect("error not returned when expected for missing book on isbn call");
-    assert!(matches!(ClientError::NotFound, _returned_error));
+    let returned_error =
+        book.expect_err("error not returned when expected for missing book on isbn call");
+    assert!(matches!(returned_error, ClientError::NotFound));
 }
 
 #[tokio::test]
@@ -201,9 +349,10 @@ async fn no_book_returned_when_works_key_missing() {
 
     let mut json_value = get_json_value("openlibrary_isbn.json");
     json_value
-        .pointer_mut("/works/0")
+        .pointer_mut("/works")
+        .and_then(|works| works.as_array_mut())
         .expect("works key not part of the sample")
-        .take();
+        .clear();
     let response = json_value.to_string();
 
     let server = MockServer::start();
@@ -215,13 +364,13 @@ async fn no_book_returned_when_works_key_missing() {
     );
 
     let client = create_client(&server);
-    let book = client.book_by_isbn(isbn).await;
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await;
 
     isbn_mock.assert();
-    let _returned_error = book
-        .err()
-        .expect("error not returned when expected for missing works key");
-    assert!(matches!(ClientError::NotFound, _returned_error));
+    let returned_error = book.expect_err("error not returned when expected for missing works key");
+    assert!(matches!(returned_error, ClientError::NotFound));
 }
 
 #[tokio::test]
@@ -238,17 +387,42 @@ async fn return_rate_limit_error() {
         );
 
         let client = create_client(&server);
-        let book = client.book_by_isbn(isbn).await;
+        let book = client
+            .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+            .await;
 
         isbn_mock.assert();
-        let _returned_error = book.err().expect(&format!(
-            "error not returned when expected for status {}",
-            status_code
-        ));
-        assert!(matches!(ClientError::RateLimitExceeded, _returned_error));
+        let returned_error = book.err().unwrap_or_else(|| {
+            panic!(
+                "error not returned when expected for status {}",
+                status_code
+            )
+        });
+        assert!(matches!(returned_error, ClientError::RateLimitExceeded(_)));
     }
 }
 
+#[tokio::test]
+async fn return_retry_after_on_rate_limit_error() {
+    let isbn = "9780316387316";
+
+    let server = MockServer::start();
+    let isbn_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("{}/{}.json", ISBN_PATH, isbn));
+        then.status(429).header("Retry-After", "60");
+    });
+
+    let client = create_client(&server);
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await;
+
+    isbn_mock.assert();
+    let returned_error = book.expect_err("error not returned when expected");
+    assert_eq!(Some(Duration::from_secs(60)), returned_error.retry_after());
+}
+
 #[tokio::test]
 async fn handle_other_http_error() {
     let isbn = "9780316387316";
@@ -263,10 +437,12 @@ async fn handle_other_http_error() {
         );
 
         let client = create_client(&server);
-        let book = client.book_by_isbn(isbn).await;
+        let book = client
+            .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+            .await;
 
         isbn_mock.assert();
-        let returned_error = book.err().expect("error not returned when expected");
+        let returned_error = book.expect_err("error not returned when expected");
         match returned_error {
             ClientError::Http(response_status_code, _) => {
                 assert_eq!(status_code, response_status_code);
@@ -279,12 +455,490 @@ async fn handle_other_http_error() {
 }
 
 #[tokio::test]
-#[should_panic]
-async fn panics_when_querying_book_by_author_and_title() {
-    let author = "Author 1";
-    let title = "Title 1";
+async fn fetch_book_by_author_and_title() {
+    let server = MockServer::start();
+    let search_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/search.json")
+            .query_param("author", "Joe Abercrombie")
+            .query_param("title", "The Blade Itself")
+            .query_param("limit", "5");
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(get_sample("openlibrary_search.json"));
+    });
+    let edition_mock = create_mock(
+        &server,
+        "/books/OL27186692M.json",
+        200,
+        &get_sample("openlibrary_isbn.json"),
+    );
+    let works_mock = create_mock(
+        &server,
+        &format!("{}.json", WORKS_PATH),
+        200,
+        &get_sample("openlibrary_works.json"),
+    );
+    let ratings_mock = create_mock(
+        &server,
+        &format!("{}{}.json", WORKS_PATH, RATINGS_PATH),
+        200,
+        &get_sample("openlibrary_ratings.json"),
+    );
+    let author_mock = create_mock(
+        &server,
+        &format!("{}.json", AUTHOR_PATH),
+        200,
+        &get_sample("openlibrary_author.json"),
+    );
+
+    let client = create_client(&server);
+    let book = client
+        .book("Joe Abercrombie", "The Blade Itself")
+        .await
+        .expect("could not get book by author and title");
+
+    search_mock.assert();
+    edition_mock.assert();
+    works_mock.assert();
+    ratings_mock.assert();
+    author_mock.assert();
+    assert_eq!(create_default_expected_book(server.port()), book);
+}
+
+#[tokio::test]
+async fn fetch_book_by_author_and_title_from_any_edition() {
+    let server = MockServer::start();
+    let search_mock = create_mock(
+        &server,
+        "/search.json",
+        200,
+        r#"{"numFound":1,"docs":[{"title":"The Blade Itself","author_name":["Joe Abercrombie"],"edition_key":["OL7925413M"]}]}"#,
+    );
+    let edition_mock = create_mock(&server, "/books/OL7925413M.json", 404, "");
+
+    let client = create_client(&server);
+    let book = client.book("Joe Abercrombie", "The Blade Itself").await;
+
+    search_mock.assert();
+    edition_mock.assert();
+    assert!(matches!(book, Err(ClientError::NotFound)));
+}
+
+#[tokio::test]
+async fn fetch_edition_of_best_matching_work() {
+    let server = MockServer::start();
+    let search_mock = create_mock(
+        &server,
+        "/search.json",
+        200,
+        r#"{"numFound":2,"docs":[
+            {"title":"Before They Are Hanged","author_name":["Joe Abercrombie"],"edition_key":["OL1M"]},
+            {"title":"The Blade Itself","author_name":["Abercrombie, Joe"],"edition_key":["OL2M"]}
+        ]}"#,
+    );
+    let edition_mock = create_mock(&server, "/books/OL2M.json", 404, "");
+
+    let client = create_client(&server);
+    let book = client.book("Joe Abercrombie", "The Blade Itself").await;
+
+    search_mock.assert();
+    edition_mock.assert();
+    assert!(matches!(book, Err(ClientError::NotFound)));
+}
+
+#[tokio::test]
+async fn no_book_returned_when_no_work_matches_author_and_title() {
+    let server = MockServer::start();
+    let search_mock = create_mock(
+        &server,
+        "/search.json",
+        200,
+        r#"{"numFound":1,"docs":[{"title":"Kështjella","author_name":["Ismail Kadare"],"edition_key":["OL1M"]}]}"#,
+    );
+    let edition_mock = create_mock(&server, "/books/OL1M.json", 200, "{}");
+
+    let client = create_client(&server);
+    let book = client.book("Joe Abercrombie", "The Blade Itself").await;
+
+    search_mock.assert();
+    edition_mock.assert_hits(0);
+    assert!(matches!(book, Err(ClientError::NotFound)));
+}
+
+#[tokio::test]
+async fn search_only_by_title_when_author_is_empty() {
+    let server = MockServer::start();
+    let search_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/search.json")
+            .query_param("title", "The Blade Itself")
+            .matches(|request| !has_query_param(request, "author"));
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(get_sample("openlibrary_search.json"));
+    });
+    let edition_mock = create_mock(&server, "/books/OL27186692M.json", 404, "");
+
+    let client = create_client(&server);
+    let book = client.book(" ", "The Blade Itself").await;
+
+    search_mock.assert();
+    edition_mock.assert();
+    assert!(matches!(book, Err(ClientError::NotFound)));
+}
+
+#[tokio::test]
+async fn no_book_returned_when_search_has_no_results() {
+    let server = MockServer::start();
+    let search_mock = create_mock(&server, "/search.json", 200, r#"{"numFound":0,"docs":[]}"#);
+
+    let client = create_client(&server);
+    let book = client.book("Author 1", "Title 1").await;
+
+    search_mock.assert();
+    assert!(matches!(book, Err(ClientError::NotFound)));
+}
+
+#[tokio::test]
+async fn return_invalid_input_error_when_author_and_title_are_empty() {
+    let server = MockServer::start();
+    let search_mock = create_mock(&server, "/search.json", 200, "{}");
+
+    let client = create_client(&server);
+    let book = client.book(" ", "").await;
+
+    search_mock.assert_hits(0);
+    assert!(matches!(book, Err(ClientError::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn fetch_book_by_isbn_with_shared_http_client() {
+    let isbn = "9780316387316";
+
+    let server = MockServer::start();
+    let isbn_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path(format!("{}/{}.json", ISBN_PATH, isbn))
+            .header("user-agent", "shared-client");
+        then.status(404);
+    });
+
+    let http_client = reqwest::Client::builder()
+        .user_agent("shared-client")
+        .build()
+        .expect("could not create http client");
+    let client = Client::builder(&format!("http://{}", &server.address()))
+        .http_client(http_client)
+        .build()
+        .expect("could not create client");
+    let book = client
+        .book_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await;
+
+    isbn_mock.assert();
+    assert!(matches!(book, Err(ClientError::NotFound)));
+}
+
+#[tokio::test]
+async fn fetch_books_by_isbns_with_single_request() {
+    let isbns = [
+        Isbn::parse("9780316387316").expect("invalid isbn"),
+        Isbn::parse("9781591026419").expect("invalid isbn"),
+    ];
+
+    let server = MockServer::start();
+    let books_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/books")
+            .query_param("bibkeys", "ISBN:9780316387316,ISBN:9781591026419")
+            .query_param("format", "json")
+            .query_param("jscmd", "details");
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(get_sample("openlibrary_books_api.json"));
+    });
+    let works_mock = create_mock(
+        &server,
+        &format!("{}.json", WORKS_PATH),
+        200,
+        &get_sample("openlibrary_works.json"),
+    );
+    let ratings_mock = create_mock(
+        &server,
+        &format!("{}{}.json", WORKS_PATH, RATINGS_PATH),
+        200,
+        &get_sample("openlibrary_ratings.json"),
+    );
+    let author_mock = create_mock(
+        &server,
+        &format!("{}.json", AUTHOR_PATH),
+        200,
+        &get_sample("openlibrary_author.json"),
+    );
+
+    let client = create_client(&server);
+    let results = client.books_by_isbns(&isbns).await;
+
+    books_mock.assert();
+    works_mock.assert();
+    ratings_mock.assert();
+    // Names of the authors are returned by the books endpoint
+    author_mock.assert_hits(0);
+    assert_eq!(2, results.len());
+    assert_eq!(isbns[0], results[0].isbn);
+    assert_eq!(
+        create_default_expected_book(server.port()),
+        results[0].result.clone().expect("book expected")
+    );
+    assert_eq!(isbns[1], results[1].isbn);
+    assert!(matches!(results[1].result, Err(ClientError::NotFound)));
+}
+
+#[tokio::test]
+async fn fetch_books_by_isbns_with_single_request_through_wrappers() {
+    let isbns = [
+        Isbn::parse("9780316387316").expect("invalid isbn"),
+        Isbn::parse("9781591026419").expect("invalid isbn"),
+    ];
+
+    let server = MockServer::start();
+    let books_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/api/books")
+            .query_param("bibkeys", "ISBN:9780316387316,ISBN:9781591026419");
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(get_sample("openlibrary_books_api.json"));
+    });
+    let isbn_mock = create_mock(
+        &server,
+        &format!("{}/9780316387316.json", ISBN_PATH),
+        200,
+        "",
+    );
+    create_mock(
+        &server,
+        &format!("{}.json", WORKS_PATH),
+        200,
+        &get_sample("openlibrary_works.json"),
+    );
+    create_mock(
+        &server,
+        &format!("{}{}.json", WORKS_PATH, RATINGS_PATH),
+        200,
+        &get_sample("openlibrary_ratings.json"),
+    );
+
+    let client = CachingClient::new(
+        CircuitBreaker::new(
+            RetryingClient::new(create_client(&server), RetryPolicy::default()),
+            CircuitPolicy::default(),
+        ),
+        CachePolicy::default(),
+    );
+    let results = client.books_by_isbns(&isbns).await;
+    let cached_results = client.books_by_isbns(&isbns).await;
+
+    books_mock.assert();
+    isbn_mock.assert_hits(0);
+    assert_eq!(2, results.len());
+    assert!(results[0].result.is_ok());
+    assert!(matches!(results[1].result, Err(ClientError::NotFound)));
+    assert_eq!(
+        results[0].result.clone().expect("book expected"),
+        cached_results[0].result.clone().expect("book expected")
+    );
+    assert!(matches!(
+        cached_results[1].result,
+        Err(ClientError::NotFound)
+    ));
+}
+
+#[tokio::test]
+async fn return_error_for_each_isbn_when_books_request_fails() {
+    let isbns = [
+        Isbn::parse("9780316387316").expect("invalid isbn"),
+        Isbn::parse("9781591026419").expect("invalid isbn"),
+    ];
+
+    let server = MockServer::start();
+    let books_mock = create_mock(&server, "/api/books", 429, "");
+
+    let client = create_client(&server);
+    let results = client.books_by_isbns(&isbns).await;
+
+    books_mock.assert();
+    assert_eq!(2, results.len());
+    for result in results {
+        assert!(
+            matches!(result.result, Err(ClientError::RateLimitExceeded(_))),
+            "unexpected result: {:?}",
+            result
+        );
+    }
+}
+
+const EDITIONS_PATH: &str = "/works/OL8400950W/editions.json";
+
+fn create_editions_mock<'a>(server: &'a MockServer, offset: &str, response_body: &str) -> Mock<'a> {
+    server.mock(|when, then| {
+        when.method(GET)
+            .path(EDITIONS_PATH)
+            .query_param("limit", "100")
+            .query_param("offset", offset);
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(response_body);
+    })
+}
+
+#[tokio::test]
+async fn fetch_editions_by_isbn() {
+    let isbn = "9780316387316";
+    let server = MockServer::start();
+    let isbn_mock = create_mock(
+        &server,
+        &format!("{}/{}.json", ISBN_PATH, isbn),
+        200,
+        &get_sample("openlibrary_isbn.json"),
+    );
+    let editions_mock =
+        create_editions_mock(&server, "0", &get_sample("openlibrary_editions.json"));
+
+    let client = create_client(&server);
+    let work_editions = client
+        .editions_by_isbn(&Isbn::parse(isbn).expect("invalid isbn"))
+        .await
+        .expect("could not get editions by isbn");
+
+    isbn_mock.assert();
+    editions_mock.assert();
+    assert_eq!("/works/OL8400950W", work_editions.work_key);
+    assert_eq!(3, work_editions.editions.len());
+
+    let albanian = work_editions.in_language("sq");
+    assert_eq!(1, albanian.len());
+    assert_eq!("/books/OL32101577M", albanian[0].key);
+    assert_eq!("Tehu", albanian[0].title);
+    assert_eq!(vec!["alb"], albanian[0].languages);
+    assert_eq!(vec!["Dudaj"], albanian[0].publishers);
+    assert_eq!(Some(2019), albanian[0].publish_year);
+    assert_eq!(
+        vec![Isbn::parse("9789928221841").expect("invalid isbn")],
+        albanian[0].isbns
+    );
+
+    let english = work_editions.in_language("eng");
+    assert_eq!(1, english.len());
+    assert_eq!("Sep 08, 2015", english[0].publish_date);
+    assert_eq!(Some(2015), english[0].publish_year);
+    // ISBN-10 and ISBN-13 of the same book are returned once
+    assert_eq!(
+        vec![Isbn::parse(isbn).expect("invalid isbn")],
+        english[0].isbns
+    );
+}
+
+#[tokio::test]
+async fn skip_invalid_isbns_of_editions() {
+    let server = MockServer::start();
+    create_editions_mock(&server, "0", &get_sample("openlibrary_editions.json"));
+
+    let client = create_client(&server);
+    let work_editions = client
+        .editions("OL8400950W")
+        .await
+        .expect("could not get editions");
+
+    let german = work_editions.in_language("de");
+    assert_eq!(1, german.len());
+    assert_eq!(
+        vec![Isbn::parse("3453523636").expect("invalid isbn")],
+        german[0].isbns
+    );
+    assert_eq!(None, german[0].publish_year);
+    assert!(work_editions.in_language("fr").is_empty());
+}
+
+#[tokio::test]
+async fn fetch_all_pages_of_editions() {
+    let entry = serde_json::json!({
+        "key": "/books/OL1M",
+        "title": "The Blade Itself",
+        "languages": [{"key": "/languages/eng"}],
+    });
+    let first_page = serde_json::json!({
+        "size": 101,
+        "entries": vec![entry.clone(); 100],
+    });
+    let second_page = serde_json::json!({
+        "size": 101,
+        "entries": [entry],
+    });
+
+    let server = MockServer::start();
+    let first_mock = create_editions_mock(&server, "0", &first_page.to_string());
+    let second_mock = create_editions_mock(&server, "100", &second_page.to_string());
+
+    let client = create_client(&server);
+    let work_editions = client
+        .editions("/works/OL8400950W")
+        .await
+        .expect("could not get editions");
+
+    first_mock.assert();
+    second_mock.assert();
+    assert_eq!(101, work_editions.editions.len());
+}
+
+#[tokio::test]
+async fn return_not_found_for_unknown_work() {
+    let server = MockServer::start();
+    let editions_mock = create_mock(&server, EDITIONS_PATH, 404, "");
+
+    let client = create_client(&server);
+    let returned_error = client
+        .editions("OL8400950W")
+        .await
+        .expect_err("error expected for unknown work");
+
+    editions_mock.assert();
+    assert!(matches!(returned_error, ClientError::NotFound));
+}
 
+#[tokio::test]
+async fn return_error_for_invalid_work_key() {
     let server = MockServer::start();
+    let m = server.mock(|when, then| {
+        when.method(GET);
+        then.status(200);
+    });
+
     let client = create_client(&server);
-    let _ = client.book(author, title).await;
+    for work_key in [
+        "",
+        "OLW",
+        "OL8400950M",
+        "/works/OL8400950W/../../isbn",
+        "OL84W0W",
+    ] {
+        let returned_error = client
+            .editions(work_key)
+            .await
+            .expect_err("error expected for invalid work key");
+        assert!(
+            matches!(returned_error, ClientError::InvalidInput(_)),
+            "invalid input expected for {:?}",
+            work_key
+        );
+    }
+    m.assert_hits(0);
+}
+
+#[test]
+fn map_language_codes_to_marc_codes() {
+    assert_eq!(Some("alb"), marc_language_code("sq"));
+    assert_eq!(Some("eng"), marc_language_code("EN"));
+    assert_eq!(None, marc_language_code("xx"));
 }
@@ -0,0 +1,184 @@
+mod util;
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use zana::isbn::Isbn;
+use zana::ratelimit::{ExceededBehavior, RateLimitPolicy, RateLimiter, CLIENT_RATE_LIMIT_REASON};
+use zana::{Book, BookClient, Capabilities, ClientError};
+
+use crate::util::TestClient;
+
+fn create_policy(
+    requests_per_second: f64,
+    burst: u32,
+    behavior: ExceededBehavior,
+) -> RateLimitPolicy {
+    RateLimitPolicy {
+        requests_per_second,
+        burst,
+        behavior,
+        max_wait: Duration::from_secs(1),
+    }
+}
+
+fn isbn() -> Isbn {
+    Isbn::parse("9780316387316").expect("invalid isbn")
+}
+
+/// Book client that records when each request is made
+struct TimedClient {
+    client: TestClient,
+    requested_at: Mutex<Vec<Instant>>,
+}
+
+impl TimedClient {
+    fn new() -> Self {
+        Self {
+            client: TestClient::new(vec![]),
+            requested_at: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn requested_at(&self) -> Vec<Instant> {
+        self.requested_at
+            .lock()
+            .expect("requests lock poisoned")
+            .clone()
+    }
+
+    fn record(&self) {
+        self.requested_at
+            .lock()
+            .expect("requests lock poisoned")
+            .push(Instant::now());
+    }
+}
+
+#[async_trait]
+impl BookClient for TimedClient {
+    async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+        self.record();
+        self.client.book_by_isbn(isbn).await
+    }
+
+    async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
+        self.record();
+        self.client.book(author, title).await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.client.capabilities()
+    }
+}
+
+#[tokio::test]
+async fn allow_burst_without_waiting() {
+    let client = RateLimiter::new(
+        TestClient::new(vec![]),
+        create_policy(1.0, 3, ExceededBehavior::Fail),
+    );
+
+    for _ in 0..3 {
+        client.book_by_isbn(&isbn()).await.expect("book expected");
+    }
+
+    assert_eq!(3, client.inner().calls());
+}
+
+#[tokio::test]
+async fn fail_when_bucket_is_empty() {
+    let client = RateLimiter::new(
+        TestClient::new(vec![]),
+        create_policy(1.0, 1, ExceededBehavior::Fail),
+    );
+
+    client.book_by_isbn(&isbn()).await.expect("book expected");
+    let returned_error = client
+        .book("Author", "Title")
+        .await
+        .expect_err("error expected when bucket is empty");
+
+    match returned_error {
+        ClientError::RateLimitExceeded(rate_limit) => {
+            assert_eq!(Some(CLIENT_RATE_LIMIT_REASON), rate_limit.reason.as_deref());
+            let retry_after = rate_limit.retry_after.expect("retry after expected");
+            assert!(retry_after > Duration::ZERO && retry_after <= Duration::from_secs(1));
+        }
+        err => panic!("invalid error type returned: {:?}", err),
+    }
+    assert_eq!(1, client.inner().calls());
+}
+
+#[tokio::test]
+async fn wait_for_token_when_bucket_is_empty() {
+    let client = RateLimiter::new(
+        TestClient::new(vec![]),
+        create_policy(20.0, 1, ExceededBehavior::Wait),
+    );
+
+    let started_at = Instant::now();
+    for _ in 0..3 {
+        client.book_by_isbn(&isbn()).await.expect("book expected");
+    }
+
+    // The second and third requests wait 50ms each for a token
+    assert!(started_at.elapsed() >= Duration::from_millis(90));
+    assert_eq!(3, client.inner().calls());
+}
+
+#[tokio::test]
+async fn fail_when_wait_exceeds_max_wait() {
+    let policy = RateLimitPolicy {
+        max_wait: Duration::from_millis(100),
+        ..create_policy(1.0, 1, ExceededBehavior::Wait)
+    };
+    let client = RateLimiter::new(TestClient::new(vec![]), policy);
+
+    client.book_by_isbn(&isbn()).await.expect("book expected");
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected when wait is too long");
+
+    assert!(matches!(returned_error, ClientError::RateLimitExceeded(_)));
+    assert_eq!(1, client.inner().calls());
+}
+
+#[tokio::test]
+async fn refill_tokens_over_time() {
+    let client = RateLimiter::new(
+        TestClient::new(vec![]),
+        create_policy(20.0, 1, ExceededBehavior::Fail),
+    );
+
+    client.book_by_isbn(&isbn()).await.expect("book expected");
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    client
+        .book_by_isbn(&isbn())
+        .await
+        .expect("book expected after refill");
+
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn space_requests_of_batch_by_rate() {
+    let client = RateLimiter::new(
+        TimedClient::new(),
+        create_policy(20.0, 1, ExceededBehavior::Wait),
+    );
+
+    let results = client.books_by_isbns(&[isbn(), isbn(), isbn()]).await;
+
+    assert_eq!(3, results.len());
+    assert!(results.iter().all(|result| result.result.is_ok()));
+    let mut requested_at = client.inner().requested_at();
+    requested_at.sort();
+    assert_eq!(3, requested_at.len());
+    // The second and third requests wait 50ms each for a token
+    for pair in requested_at.windows(2) {
+        assert!(pair[1].duration_since(pair[0]) >= Duration::from_millis(40));
+    }
+}
@@ -0,0 +1,70 @@
+use zana::rating::{AggregatedRating, ProviderRating};
+use zana::Rating;
+
+#[test]
+fn weight_average_rating_by_ratings_count() {
+    let rating = AggregatedRating::new(vec![
+        ProviderRating::new("googlebooks", Rating::new(5.0, 2)),
+        ProviderRating::new("openlibrary", Rating::new(3.0, 198)),
+    ])
+    .expect("rating expected");
+
+    assert!((rating.average_rating - 3.02).abs() < 0.0001);
+    assert_eq!(200, rating.ratings_count);
+    assert_eq!(
+        vec![
+            ProviderRating::new("googlebooks", Rating::new(5.0, 2)),
+            ProviderRating::new("openlibrary", Rating::new(3.0, 198)),
+        ],
+        rating.providers
+    );
+}
+
+#[test]
+fn return_single_provider_rating() {
+    let rating = AggregatedRating::new(vec![ProviderRating::new(
+        "openlibrary",
+        Rating::new(4.5, 23),
+    )])
+    .expect("rating expected");
+
+    assert_eq!(Rating::new(4.5, 23), rating.rating());
+    assert_eq!(Some(&Rating::new(4.5, 23)), rating.provider("openlibrary"));
+    assert_eq!(None, rating.provider("googlebooks"));
+}
+
+#[test]
+fn skip_ratings_without_count() {
+    let rating = AggregatedRating::new(vec![
+        ProviderRating::new("googlebooks", Rating::new(1.0, 0)),
+        ProviderRating::new("openlibrary", Rating::new(4.0, 10)),
+    ])
+    .expect("rating expected");
+
+    assert_eq!(Rating::new(4.0, 10), rating.rating());
+    assert_eq!(1, rating.providers.len());
+}
+
+#[test]
+fn return_none_without_ratings() {
+    assert_eq!(None, AggregatedRating::new(vec![]));
+    assert_eq!(
+        None,
+        AggregatedRating::new(vec![ProviderRating::new(
+            "googlebooks",
+            Rating::new(0.0, 0)
+        )])
+    );
+}
+
+#[test]
+fn saturate_total_ratings_count() {
+    let rating = AggregatedRating::new(vec![
+        ProviderRating::new("googlebooks", Rating::new(4.0, u32::MAX)),
+        ProviderRating::new("openlibrary", Rating::new(4.0, u32::MAX)),
+    ])
+    .expect("rating expected");
+
+    assert_eq!(u32::MAX, rating.ratings_count);
+    assert_eq!(4.0, rating.average_rating);
+}
@@ -0,0 +1,152 @@
+mod util;
+
+use std::time::{Duration, Instant};
+
+use zana::isbn::Isbn;
+use zana::retry::{RetryPolicy, RetryingClient};
+use zana::{BookClient, ClientError, RateLimit};
+
+use crate::util::TestClient;
+
+fn create_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(10),
+        jitter: 0.0,
+        ..RetryPolicy::default()
+    }
+}
+
+fn isbn() -> Isbn {
+    Isbn::parse("9780316387316").expect("invalid isbn")
+}
+
+fn server_error() -> Result<zana::Book, ClientError> {
+    Err(ClientError::Http(503, String::new()))
+}
+
+#[tokio::test]
+async fn return_book_after_transient_errors() {
+    let client = RetryingClient::new(
+        TestClient::new(vec![server_error(), server_error()]),
+        create_policy(3),
+    );
+
+    let book = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect("book expected after retries");
+
+    assert_eq!(TestClient::default_book(), book);
+    assert_eq!(3, client.inner().calls());
+}
+
+#[tokio::test]
+async fn return_last_error_when_attempts_are_exhausted() {
+    let client = RetryingClient::new(
+        TestClient::new(vec![server_error(), server_error(), server_error()]),
+        create_policy(2),
+    );
+
+    let returned_error = client
+        .book("Joe Abercrombie", "The Blade Itself")
+        .await
+        .expect_err("error expected when attempts are exhausted");
+
+    assert!(matches!(returned_error, ClientError::Http(503, _)));
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn do_not_retry_errors_that_are_not_retryable() {
+    let client = RetryingClient::new(
+        TestClient::new(vec![Err(ClientError::NotFound)]),
+        create_policy(3),
+    );
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected when book is not found");
+
+    assert!(matches!(returned_error, ClientError::NotFound));
+    assert_eq!(1, client.inner().calls());
+}
+
+#[tokio::test]
+async fn retry_rate_limit_error_after_retry_after() {
+    let rate_limit = RateLimit {
+        retry_after: Some(Duration::from_millis(20)),
+        reason: None,
+    };
+    let client = RetryingClient::new(
+        TestClient::new(vec![Err(ClientError::RateLimitExceeded(rate_limit))]),
+        create_policy(3),
+    );
+
+    let started_at = Instant::now();
+    client
+        .book_by_isbn(&isbn())
+        .await
+        .expect("book expected after rate limit resets");
+
+    assert!(started_at.elapsed() >= Duration::from_millis(20));
+    assert_eq!(2, client.inner().calls());
+}
+
+#[tokio::test]
+async fn do_not_retry_rate_limit_error_without_retry_after() {
+    let client = RetryingClient::new(
+        TestClient::new(vec![Err(ClientError::RateLimitExceeded(
+            RateLimit::default(),
+        ))]),
+        create_policy(3),
+    );
+
+    let returned_error = client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected when rate limit is exceeded");
+
+    assert!(matches!(returned_error, ClientError::RateLimitExceeded(_)));
+    assert_eq!(1, client.inner().calls());
+}
+
+#[tokio::test]
+async fn do_not_retry_rate_limit_error_with_long_retry_after() {
+    let rate_limit = RateLimit {
+        retry_after: Some(Duration::from_secs(60)),
+        reason: None,
+    };
+    let client = RetryingClient::new(
+        TestClient::new(vec![Err(ClientError::RateLimitExceeded(rate_limit))]),
+        create_policy(3),
+    );
+
+    client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected when retry after exceeds the policy");
+    assert_eq!(1, client.inner().calls());
+}
+
+#[tokio::test]
+async fn stop_retrying_when_time_budget_is_exceeded() {
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(50),
+        max_backoff: Duration::from_millis(50),
+        max_elapsed_time: Some(Duration::from_millis(75)),
+        ..create_policy(5)
+    };
+    let client = RetryingClient::new(
+        TestClient::new(vec![server_error(), server_error(), server_error()]),
+        policy,
+    );
+
+    client
+        .book_by_isbn(&isbn())
+        .await
+        .expect_err("error expected when time budget is exceeded");
+    assert_eq!(2, client.inner().calls());
+}
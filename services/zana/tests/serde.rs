@@ -0,0 +1,69 @@
+#![cfg(feature = "serde")]
+
+use serde_json::json;
+use zana::isbn::Isbn;
+use zana::{Book, Identifier, IdentifierType, Rating};
+
+fn create_book() -> Book {
+    let mut book = Book::new_with_rating(
+        542,
+        "Logen Ninefingers, infamous barbarian, has finally run out of luck.",
+        "https://openlibrary.org/works/OL8400950W",
+        Rating::new(4.5, 23),
+    );
+    book.title = String::from("The Blade Itself");
+    book.authors = vec![String::from("Joe Abercrombie")];
+    book.identifiers = vec![
+        Identifier::new(IdentifierType::Isbn13, "9780316387316"),
+        Identifier::new(IdentifierType::Other(String::from("OTHER")), "UOM:123"),
+    ];
+    book
+}
+
+#[test]
+fn serialize_book_with_stable_field_names() {
+    let value = serde_json::to_value(create_book()).expect("could not serialize book");
+
+    assert_eq!(
+        json!({
+            "title": "The Blade Itself",
+            "authors": ["Joe Abercrombie"],
+            "publisher": "",
+            "published_date": "",
+            "language": "",
+            "categories": [],
+            "identifiers": [
+                {"identifier_type": "isbn13", "value": "9780316387316"},
+                {"identifier_type": {"other": "OTHER"}, "value": "UOM:123"}
+            ],
+            "page_count": 542,
+            "description": "Logen Ninefingers, infamous barbarian, has finally run out of luck.",
+            "provider_link": "https://openlibrary.org/works/OL8400950W",
+            "rating": {"average_rating": 4.5, "ratings_count": 23}
+        }),
+        value
+    );
+}
+
+#[test]
+fn deserialize_serialized_book() {
+    let book = create_book();
+    let serialized = serde_json::to_string(&book).expect("could not serialize book");
+    let deserialized: Book = serde_json::from_str(&serialized).expect("could not deserialize book");
+
+    assert_eq!(book, deserialized);
+}
+
+#[test]
+fn serialize_isbn_as_canonical_string() {
+    let isbn = Isbn::parse("0-316-38731-2").expect("could not parse isbn");
+    assert_eq!(
+        "\"9780316387316\"",
+        serde_json::to_string(&isbn).expect("could not serialize isbn")
+    );
+    assert_eq!(
+        isbn,
+        serde_json::from_str::<Isbn>("\"0316387312\"").expect("could not deserialize isbn")
+    );
+    assert!(serde_json::from_str::<Isbn>("\"0316387313\"").is_err());
+}
@@ -1,4 +1,14 @@
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
 use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use zana::isbn::Isbn;
+use zana::{Book, BookClient, Capabilities, ClientError, LookupKind};
 
 #[cfg(test)]
 pub fn get_sample(sample: &str) -> String {
@@ -16,7 +26,96 @@ pub fn set_property_to_null(sample: &str, pointer: &str) -> serde_json::Value {
     let mut v: serde_json::Value =
         serde_json::from_str(&get_sample(sample)).expect("could not parse json");
     v.pointer_mut(pointer)
-        .expect(&format!("{} not part of the sample", pointer))
+        .unwrap_or_else(|| panic!("{} not part of the sample", pointer))
         .take();
     v
 }
+
+#[cfg(test)]
+pub fn has_query_param(request: &httpmock::prelude::HttpMockRequest, name: &str) -> bool {
+    request
+        .query_params
+        .as_ref()
+        .is_some_and(|params| params.iter().any(|(param, _)| param == name))
+}
+
+/// Book client that returns the given responses in order, and a default book once
+/// all responses are returned.
+#[cfg(test)]
+pub struct TestClient {
+    responses: Mutex<VecDeque<Result<Book, ClientError>>>,
+    calls: AtomicUsize,
+    delay: Duration,
+    capabilities: Option<Capabilities>,
+}
+
+#[cfg(test)]
+impl TestClient {
+    pub fn new(responses: Vec<Result<Book, ClientError>>) -> Self {
+        Self {
+            responses: Mutex::new(responses.into()),
+            calls: AtomicUsize::new(0),
+            delay: Duration::ZERO,
+            capabilities: None,
+        }
+    }
+
+    /// Returns a client that supports only the given capabilities
+    pub fn with_capabilities(
+        responses: Vec<Result<Book, ClientError>>,
+        capabilities: Capabilities,
+    ) -> Self {
+        Self {
+            capabilities: Some(capabilities),
+            ..Self::new(responses)
+        }
+    }
+
+    /// Returns a client that waits for the given delay before returning each response
+    pub fn with_delay(responses: Vec<Result<Book, ClientError>>, delay: Duration) -> Self {
+        Self {
+            delay,
+            ..Self::new(responses)
+        }
+    }
+
+    pub fn default_book() -> Book {
+        Book::new(100, "Book description", "http://localhost/link/to/book")
+    }
+
+    /// Returns the number of requests made to the client
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    async fn next_response(&self) -> Result<Book, ClientError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        self.responses
+            .lock()
+            .expect("responses lock poisoned")
+            .pop_front()
+            .unwrap_or_else(|| Ok(Self::default_book()))
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl BookClient for TestClient {
+    async fn book_by_isbn(&self, _isbn: &Isbn) -> Result<Book, ClientError> {
+        self.next_response().await
+    }
+
+    async fn book(&self, _author: &str, _title: &str) -> Result<Book, ClientError> {
+        self.next_response().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        match &self.capabilities {
+            Some(capabilities) => capabilities.clone(),
+            None => Capabilities::new(&[LookupKind::Isbn, LookupKind::AuthorAndTitle]),
+        }
+    }
+}
@@ -1,68 +1,306 @@
 /*!
 Queries book data from providers supported by [`zana`](zana).
-It uses [`RequestType`](enum@RequestType) to decide on which provider to use.
+It uses [`RequestType`](enum@RequestType) to decide on which provider to query first.
+When the book is not available from that provider, the other providers are queried as well,
+if the request falls back to them.
+
+Google Books can be queried with [`QueryOptions`](struct@QueryOptions), such as the language of
+returned volumes, when the client is created with a client for each set of options.
+
+Other editions of a book, such as its translations, are listed by an
+[`EditionsClient`](trait@EditionsClient), when the client is created with one.
 */
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
 use crate::http::{RequestType, ResponseError};
-use zana::{Book, BookClient};
+use zana::fallback::{FallbackClient, FallbackPolicy, Provider, ProviderBook};
+use zana::googlebooks::QueryOptions;
+use zana::isbn::Isbn;
+use zana::openlibrary::{self, Edition, WorkEditions};
+use zana::rating::{AggregatedRating, ProviderRating};
+use zana::{Book, BookClient, ClientError, LookupKind, Rating};
 
 pub type BookApiClient = dyn BookClient + Send + Sync;
 
+/// Returns a Google Books client that sends the given options with each query.
+///
+/// Clients may be created for a single request, so clients created for different options
+/// should share their circuit breaker and cache, with results cached per options.
+pub type GoogleBooksFactory = dyn Fn(&QueryOptions) -> Box<BookApiClient> + Send + Sync;
+
+/// Client used to list the editions of the work of a book.
+#[async_trait]
+pub trait EditionsClient {
+    /// Returns the editions of the work of the book with the given ISBN.
+    async fn editions_by_isbn(&self, isbn: &Isbn) -> Result<WorkEditions, ClientError>;
+}
+
+#[async_trait]
+impl EditionsClient for openlibrary::Client {
+    async fn editions_by_isbn(&self, isbn: &Isbn) -> Result<WorkEditions, ClientError> {
+        openlibrary::Client::editions_by_isbn(self, isbn).await
+    }
+}
+
+/// Shared clients, e.g. a client that also looks up books, are editions clients as well.
+#[async_trait]
+impl<C: EditionsClient + Send + Sync + ?Sized> EditionsClient for Arc<C> {
+    async fn editions_by_isbn(&self, isbn: &Isbn) -> Result<WorkEditions, ClientError> {
+        (**self).editions_by_isbn(isbn).await
+    }
+}
+
+/// Editions of a book in a single language.
+#[derive(Debug, Clone)]
+pub struct LanguageEditions {
+    /// ISO 639-1 code of the language, e.g. `sq`
+    pub language: String,
+    pub editions: Vec<Edition>,
+}
+
+/// Editions of a book in each of the requested languages.
+#[derive(Debug, Clone)]
+pub struct BookEditions {
+    /// Key of the work of the book, e.g. `/works/OL8400950W`
+    pub work_key: String,
+    pub languages: Vec<LanguageEditions>,
+}
+
+/// Maximum number of sets of options that Google Books clients are kept for. Clients for other
+/// options are created for a single request, so that requests cannot grow the memory unbounded,
+/// and rely on the factory to share the circuit breaker and cache between clients.
+const MAX_LOCALIZED_CLIENTS: usize = 16;
+
+/// Maximum time that the other provider is queried for its rating of a book, so that the
+/// combined rating adds little to the time of a request.
+const RATING_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Maximum time of a request for a book, including the fallback to the other provider and its
+/// rating, kept below the API Gateway timeout of 29 seconds.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(25);
+
 /// Client used to fetch books from different providers.
 /// Acts as a container for different clients supported by [`zana`](zana).
 pub struct Client {
-    googlebooks_client: Box<BookApiClient>,
-    openlibrary_client: Box<BookApiClient>,
+    providers: Arc<Providers>,
+    googlebooks_factory: Option<Box<GoogleBooksFactory>>,
+    localized_providers: Mutex<HashMap<QueryOptions, Arc<Providers>>>,
+    editions_client: Option<Box<dyn EditionsClient + Send + Sync>>,
+}
+
+/// Options of a request for a book with [`fetch_book_with_rating`](Client::fetch_book_with_rating).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FetchOptions {
+    /// Options of Google Books queries, such as the language of returned volumes
+    pub query_options: QueryOptions,
+    /// Whether the rating combined from all providers is returned with the book
+    pub aggregate_rating: bool,
+    /// Whether the other provider is queried when the requested one does not return the book
+    pub fallback: bool,
+}
+
+/// Clients of each provider, and the clients that fall back from each provider to the others.
+struct Providers {
+    googlebooks: Arc<BookApiClient>,
+    openlibrary: Arc<BookApiClient>,
+    googlebooks_client: FallbackClient,
+    openlibrary_client: FallbackClient,
+}
+
+impl Providers {
+    fn new(googlebooks: Arc<BookApiClient>, openlibrary: Arc<BookApiClient>) -> Self {
+        let provider = |request_type: RequestType, client: &Arc<BookApiClient>| {
+            Provider::new(request_type.provider(), Arc::clone(client))
+        };
+        Self {
+            googlebooks_client: FallbackClient::new(
+                vec![
+                    provider(RequestType::GoogleBooks, &googlebooks),
+                    provider(RequestType::OpenLibrary, &openlibrary),
+                ],
+                FallbackPolicy::default(),
+            ),
+            openlibrary_client: FallbackClient::new(
+                vec![
+                    provider(RequestType::OpenLibrary, &openlibrary),
+                    provider(RequestType::GoogleBooks, &googlebooks),
+                ],
+                FallbackPolicy::default(),
+            ),
+            googlebooks,
+            openlibrary,
+        }
+    }
+
+    fn client_from_type(&self, request_type: &RequestType) -> &FallbackClient {
+        match request_type {
+            RequestType::GoogleBooks => &self.googlebooks_client,
+            RequestType::OpenLibrary => &self.openlibrary_client,
+        }
+    }
+
+    /// Returns the client of the provider alone, without falling back to the others.
+    fn provider_client(&self, request_type: &RequestType) -> &BookApiClient {
+        match request_type {
+            RequestType::GoogleBooks => self.googlebooks.as_ref(),
+            RequestType::OpenLibrary => self.openlibrary.as_ref(),
+        }
+    }
+}
+
+/// A book, the provider that returned it, and the rating combined from all providers.
+#[derive(Debug, Clone)]
+pub struct RatedBook {
+    pub answer: ProviderBook,
+    pub rating: Option<AggregatedRating>,
+}
+
+/// Query used to look up a book, with ISBN taking precedence over title and author.
+enum Lookup<'a> {
+    Isbn(Isbn),
+    TitleAndAuthor { title: &'a str, author: &'a str },
+}
+
+impl<'a> Lookup<'a> {
+    fn parse(isbn: &str, title: &'a str, author: &'a str) -> Result<Self, ResponseError> {
+        if !isbn.is_empty() {
+            let isbn = Isbn::parse(isbn)
+                .map_err(|err| ResponseError::InvalidParameter(format!("Invalid ISBN: {}", err)))?;
+            Ok(Lookup::Isbn(isbn))
+        } else if !author.is_empty() && !title.is_empty() {
+            Ok(Lookup::TitleAndAuthor { title, author })
+        } else {
+            Err(ResponseError::MissingParameter(String::from(
+                "Either ISBN or title and author must be provided",
+            )))
+        }
+    }
+
+    async fn book(&self, client: &BookApiClient) -> Result<Book, ClientError> {
+        match self {
+            Lookup::Isbn(isbn) => client.book_by_isbn(isbn).await,
+            Lookup::TitleAndAuthor { title, author } => client.book(author, title).await,
+        }
+    }
+
+    async fn provider_book(&self, client: &FallbackClient) -> Result<ProviderBook, ClientError> {
+        match self {
+            Lookup::Isbn(isbn) => client.provider_book_by_isbn(isbn).await,
+            Lookup::TitleAndAuthor { title, author } => client.provider_book(author, title).await,
+        }
+    }
 }
 
 impl Client {
     /// Returns a new client that contains [`zana`](zana) clients for each provider.
+    ///
+    /// Each provider falls back to the other one, when the book is not found, or the provider
+    /// is rate limited or unavailable.
     pub fn new(
         googlebooks_client: Box<BookApiClient>,
         openlibrary_client: Box<BookApiClient>,
     ) -> Self {
         Self {
-            googlebooks_client,
-            openlibrary_client,
+            providers: Arc::new(Providers::new(
+                Arc::from(googlebooks_client),
+                Arc::from(openlibrary_client),
+            )),
+            googlebooks_factory: None,
+            localized_providers: Mutex::new(HashMap::new()),
+            editions_client: None,
         }
     }
 
-    fn client_from_type(&self, request_type: &RequestType) -> &BookApiClient {
-        match request_type {
-            RequestType::GoogleBooks => self.googlebooks_client.as_ref(),
-            RequestType::OpenLibrary => self.openlibrary_client.as_ref(),
+    /// Returns the client, that lists the editions of books with the given client.
+    ///
+    /// Without an editions client, listing editions is not supported.
+    pub fn with_editions_client(
+        mut self,
+        editions_client: Box<dyn EditionsClient + Send + Sync>,
+    ) -> Self {
+        self.editions_client = Some(editions_client);
+        self
+    }
+
+    /// Returns the client, that creates Google Books clients with the given factory for
+    /// requests with [`QueryOptions`](struct@QueryOptions).
+    ///
+    /// Without a factory, the options of requests are ignored.
+    pub fn with_googlebooks_factory(
+        mut self,
+        googlebooks_factory: Box<GoogleBooksFactory>,
+    ) -> Self {
+        self.googlebooks_factory = Some(googlebooks_factory);
+        self
+    }
+
+    fn client_from_type(&self, request_type: &RequestType) -> &FallbackClient {
+        self.providers.client_from_type(request_type)
+    }
+
+    /// Returns the providers used for requests with the given options.
+    fn providers(&self, options: &QueryOptions) -> Arc<Providers> {
+        let googlebooks_factory = match &self.googlebooks_factory {
+            Some(googlebooks_factory) if !options.is_empty() => googlebooks_factory,
+            _ => return Arc::clone(&self.providers),
+        };
+        let mut localized_providers = self.lock();
+        if let Some(providers) = localized_providers.get(options) {
+            return Arc::clone(providers);
+        }
+
+        tracing::debug!("creating Google Books client for {:?}", options);
+        let providers = Arc::new(Providers::new(
+            Arc::from(googlebooks_factory(options)),
+            Arc::clone(&self.providers.openlibrary),
+        ));
+        if localized_providers.len() < MAX_LOCALIZED_CLIENTS {
+            localized_providers.insert(options.clone(), Arc::clone(&providers));
         }
+        providers
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<QueryOptions, Arc<Providers>>> {
+        // Providers are only ever added, so the map is still usable after a panic
+        self.localized_providers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
     }
 
-    /// Returns a book by ISBN
+    /// Returns a book by ISBN, and the provider that returned it
     ///
-    /// Based on [`RequestType`](enum@RequestType) the correct provider will be used
+    /// Based on [`RequestType`](enum@RequestType) the correct provider will be queried first
     /// to fetch the book by ISBN.
     /// If any there are communication problems, an HTTP status code that is not 200 is returned,
-    /// or the book is not found, an error is returned.
+    /// or the book is not found by any provider, an error is returned.
     pub async fn fetch_by_isbn(
         &self,
         request_type: &RequestType,
-        isbn: &str,
-    ) -> Result<Book, ResponseError> {
+        isbn: &Isbn,
+    ) -> Result<ProviderBook, ResponseError> {
         tracing::debug!("sending volume query request for isbn: {}", isbn);
         Ok(self
             .client_from_type(request_type)
-            .book_by_isbn(isbn)
+            .provider_book_by_isbn(isbn)
             .await?)
     }
 
-    /// Returns a book by title and author
+    /// Returns a book by title and author, and the provider that returned it
     ///
-    /// Based on [`RequestType`](enum@RequestType) the correct provider will be used
+    /// Based on [`RequestType`](enum@RequestType) the correct provider will be queried first
     /// to fetch the book by title and author.
     /// If any there are communication problems, an HTTP status code that is not 200 is returned,
-    /// or the book is not found, an error is returned.
+    /// or the book is not found by any provider, an error is returned.
     pub async fn fetch_by_title_and_author(
         &self,
         request_type: &RequestType,
         title: &str,
         author: &str,
-    ) -> Result<Book, ResponseError> {
+    ) -> Result<ProviderBook, ResponseError> {
         tracing::debug!(
             "sending volume query request for title: {} and author: {}",
             title,
@@ -70,16 +308,16 @@ impl Client {
         );
         Ok(self
             .client_from_type(request_type)
-            .book(author, title)
+            .provider_book(author, title)
             .await?)
     }
 
     /// Returns a book
     ///
-    /// Based on [`RequestType`](enum@RequestType) the correct provider will be used
+    /// Based on [`RequestType`](enum@RequestType) the correct provider will be queried first
     /// to fetch the book by either ISBN, or title and author.
     /// ISBN has precedence over title and author.
-    /// If ISBN, title and author are all empty, and error is returned.
+    /// If ISBN, title and author are all empty, or the ISBN is not valid, an error is returned.
     /// If any there are communication problems, an HTTP status code that is not 200 is returned,
     /// or the book is not found, an error is returned.
     pub async fn fetch_book(
@@ -88,33 +326,207 @@ impl Client {
         isbn: &str,
         title: &str,
         author: &str,
-    ) -> Result<Book, ResponseError> {
-        if !isbn.is_empty() {
-            tracing::debug!("fetching book by isbn {} for {:?}", isbn, &request_type);
-            self.fetch_by_isbn(request_type, isbn).await
-        } else if !author.is_empty() && !title.is_empty() {
-            tracing::debug!(
-                "fetching book by title {} and author {} for {:?}",
-                title,
-                author,
-                &request_type
-            );
-            self.fetch_by_title_and_author(request_type, title, author)
-                .await
+    ) -> Result<ProviderBook, ResponseError> {
+        let lookup = Lookup::parse(isbn, title, author)?;
+        self.fetch(&self.providers, request_type, &lookup, true)
+            .await
+    }
+
+    /// Returns a book, as [`fetch_book`](Client::fetch_book) does, together with the rating
+    /// combined from all providers when [`aggregate_rating`](FetchOptions::aggregate_rating)
+    /// is true
+    ///
+    /// Only the requested provider is queried for the book, unless the options
+    /// [fall back](FetchOptions::fallback) to the other provider.
+    /// Google Books is queried with the given options, such as the language of returned volumes.
+    /// The other provider is queried for its rating at the same time as the book is fetched,
+    /// for at most [`RATING_TIMEOUT`](RATING_TIMEOUT), or once the book is fetched when falling
+    /// back, so that it is not queried again when it returned the book. Providers that fail,
+    /// do not have the book, or do not answer in time, are left out of the combined rating.
+    /// If the book is not returned within [`REQUEST_TIMEOUT`](REQUEST_TIMEOUT),
+    /// a [`Timeout`](ResponseError::Timeout) error is returned.
+    pub async fn fetch_book_with_rating(
+        &self,
+        request_type: &RequestType,
+        isbn: &str,
+        title: &str,
+        author: &str,
+        options: &FetchOptions,
+    ) -> Result<RatedBook, ResponseError> {
+        let lookup = Lookup::parse(isbn, title, author)?;
+        let providers = self.providers(&options.query_options);
+        let rated_book = self.fetch_rated(&providers, request_type, &lookup, options);
+        match tokio::time::timeout(REQUEST_TIMEOUT, rated_book).await {
+            Ok(rated_book) => rated_book,
+            Err(_) => {
+                tracing::debug!("timed out fetching book for {:?}", request_type);
+                Err(ResponseError::Timeout)
+            }
+        }
+    }
+
+    /// Returns the editions of the book with the given ISBN in each of the given languages,
+    /// as ISO 639-1 codes (e.g. `sq` or `en`)
+    ///
+    /// A language without editions is returned with no editions, so that callers can tell
+    /// which languages the book is available in.
+    /// If the ISBN is empty or not valid, an error is returned.
+    /// If any there are communication problems, an HTTP status code that is not 200 is returned,
+    /// or the book is not found, an error is returned.
+    pub async fn fetch_editions(
+        &self,
+        isbn: &str,
+        languages: &[String],
+    ) -> Result<BookEditions, ResponseError> {
+        if isbn.is_empty() {
+            return Err(ResponseError::MissingParameter(String::from(
+                "ISBN must be provided",
+            )));
+        }
+        let isbn = Isbn::parse(isbn)
+            .map_err(|err| ResponseError::InvalidParameter(format!("Invalid ISBN: {}", err)))?;
+        let editions_client =
+            self.editions_client
+                .as_ref()
+                .ok_or(ResponseError::BookClientError(ClientError::Unsupported(
+                    LookupKind::Editions,
+                )))?;
+
+        tracing::debug!("fetching editions of isbn {} in {:?}", isbn, languages);
+        let work_editions = editions_client.editions_by_isbn(&isbn).await?;
+        let languages = languages
+            .iter()
+            .map(|language| LanguageEditions {
+                language: language.clone(),
+                editions: work_editions
+                    .in_language(language)
+                    .into_iter()
+                    .cloned()
+                    .collect(),
+            })
+            .collect();
+        Ok(BookEditions {
+            work_key: work_editions.work_key,
+            languages,
+        })
+    }
+
+    async fn fetch_rated(
+        &self,
+        providers: &Providers,
+        request_type: &RequestType,
+        lookup: &Lookup<'_>,
+        options: &FetchOptions,
+    ) -> Result<RatedBook, ResponseError> {
+        if !options.aggregate_rating {
+            let answer = self
+                .fetch(providers, request_type, lookup, options.fallback)
+                .await?;
+            return Ok(RatedBook {
+                answer,
+                rating: None,
+            });
+        }
+
+        let (other_type, other_client) = match request_type {
+            RequestType::GoogleBooks => (RequestType::OpenLibrary, &providers.openlibrary),
+            RequestType::OpenLibrary => (RequestType::GoogleBooks, &providers.googlebooks),
+        };
+        let (answer, other_rating) = if options.fallback {
+            let answer = self.fetch(providers, request_type, lookup, true).await?;
+            // When the book was returned by the other provider, its rating is already included
+            let other_rating = if answer.provider == other_type.provider() {
+                None
+            } else {
+                Self::rating(other_type.provider(), other_client.as_ref(), lookup).await
+            };
+            (answer, other_rating)
         } else {
-            Err(ResponseError::MissingParameter(String::from(
-                "Either ISBN or title and author must be provided",
-            )))
+            let (answer, other_rating) = tokio::join!(
+                self.fetch(providers, request_type, lookup, false),
+                Self::rating(other_type.provider(), other_client.as_ref(), lookup),
+            );
+            (answer?, other_rating)
+        };
+
+        let mut ratings = Vec::new();
+        if let Some(rating) = answer.book.rating.clone() {
+            ratings.push(ProviderRating::new(&answer.provider, rating));
+        }
+        if let Some(rating) = other_rating {
+            ratings.push(ProviderRating::new(other_type.provider(), rating));
+        }
+        Ok(RatedBook {
+            answer,
+            rating: AggregatedRating::new(ratings),
+        })
+    }
+
+    /// Returns the book from the provider of the request type, or from the other provider
+    /// when `fallback` is true and the provider does not return it.
+    async fn fetch(
+        &self,
+        providers: &Providers,
+        request_type: &RequestType,
+        lookup: &Lookup<'_>,
+        fallback: bool,
+    ) -> Result<ProviderBook, ResponseError> {
+        match lookup {
+            Lookup::Isbn(isbn) => {
+                tracing::debug!("fetching book by isbn {} for {:?}", isbn, &request_type);
+            }
+            Lookup::TitleAndAuthor { title, author } => {
+                tracing::debug!(
+                    "fetching book by title {} and author {} for {:?}",
+                    title,
+                    author,
+                    &request_type
+                );
+            }
+        }
+        if fallback {
+            return Ok(lookup
+                .provider_book(providers.client_from_type(request_type))
+                .await?);
+        }
+        let book = lookup.book(providers.provider_client(request_type)).await?;
+        Ok(ProviderBook {
+            provider: String::from(request_type.provider()),
+            book,
+        })
+    }
+
+    /// Returns the rating of the book from the given provider, or [`None`](None) if the provider
+    /// fails, does not have the book, or does not answer within [`RATING_TIMEOUT`](RATING_TIMEOUT).
+    async fn rating(provider: &str, client: &BookApiClient, lookup: &Lookup<'_>) -> Option<Rating> {
+        match tokio::time::timeout(RATING_TIMEOUT, lookup.book(client)).await {
+            Ok(Ok(book)) => book.rating,
+            Ok(Err(err)) => {
+                tracing::debug!("could not fetch rating from {}: {:?}", provider, err);
+                None
+            }
+            Err(_) => {
+                tracing::debug!("timed out fetching rating from {}", provider);
+                None
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::book::Client;
+    use crate::book::EditionsClient;
+    use crate::book::{Client, FetchOptions};
     use crate::http::{RequestType, ResponseError};
     use async_trait::async_trait;
-    use zana::{Book, BookClient, ClientError};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use zana::googlebooks::QueryOptions;
+    use zana::isbn::Isbn;
+    use zana::openlibrary::{Edition, WorkEditions};
+    use zana::{Book, BookClient, ClientError, LookupKind, Rating};
 
     struct TestBookClient {
         isbn: String,
@@ -123,6 +535,7 @@ mod tests {
         pages: u32,
         description: String,
         provider_link: String,
+        rating: Option<Rating>,
     }
 
     impl TestBookClient {
@@ -145,9 +558,23 @@ mod tests {
                 author: String::from(author),
                 description: String::from(description),
                 provider_link: String::from(provider_link),
+                rating: None,
             }
         }
 
+        fn with_rating(self, rating: Rating) -> Self {
+            Self {
+                rating: Some(rating),
+                ..self
+            }
+        }
+
+        fn book(&self) -> Book {
+            let mut book = Book::new(self.pages, &self.description, &self.provider_link);
+            book.rating = self.rating.clone();
+            book
+        }
+
         fn new_with_isbn(isbn: &str, pages: u32, description: &str, provider_link: &str) -> Self {
             Self::new(isbn, "", "", pages, description, provider_link)
         }
@@ -165,13 +592,9 @@ mod tests {
 
     #[async_trait]
     impl BookClient for TestBookClient {
-        async fn book_by_isbn(&self, isbn: &str) -> Result<Book, ClientError> {
-            if self.isbn == isbn {
-                Ok(Book::new(
-                    self.pages,
-                    &self.description,
-                    &self.provider_link,
-                ))
+        async fn book_by_isbn(&self, isbn: &Isbn) -> Result<Book, ClientError> {
+            if self.isbn == isbn.as_str() {
+                Ok(self.book())
             } else {
                 Err(ClientError::NotFound)
             }
@@ -179,11 +602,7 @@ mod tests {
 
         async fn book(&self, author: &str, title: &str) -> Result<Book, ClientError> {
             if self.title == title && self.author == author {
-                Ok(Book::new(
-                    self.pages,
-                    &self.description,
-                    &self.provider_link,
-                ))
+                Ok(self.book())
             } else {
                 Err(ClientError::NotFound)
             }
@@ -209,6 +628,44 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn return_error_when_isbn_is_invalid() {
+        let gb_client = TestBookClient::default();
+        let op_client = TestBookClient::default();
+        let client = Client::new(Box::new(gb_client), Box::new(op_client));
+        match client
+            .fetch_book(&RequestType::OpenLibrary, "9781591026418", "", "")
+            .await
+        {
+            Ok(_) => panic!("error expected when isbn is invalid"),
+            Err(err) => match err {
+                ResponseError::InvalidParameter(message) => {
+                    assert_eq!("Invalid ISBN: invalid ISBN checksum", message)
+                }
+                _ => panic!("invalid error type returned"),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_book_by_isbn_with_separators() {
+        let pages = 100;
+        let description = "Book description";
+        let provider_link = "http://localhost/link/to/book";
+
+        let gb_client = TestBookClient::default();
+        let op_client =
+            TestBookClient::new_with_isbn("9781591026419", pages, description, provider_link);
+        let client = Client::new(Box::new(gb_client), Box::new(op_client));
+        let answer = client
+            .fetch_book(&RequestType::OpenLibrary, "ISBN 978-1-59102-641-9", "", "")
+            .await
+            .expect("could not retrieve book");
+
+        assert_eq!("openlibrary", answer.provider);
+        assert_eq!(Book::new(pages, description, provider_link), answer.book);
+    }
+
     #[tokio::test]
     async fn fetch_book_by_isbn() {
         let isbn = "9781591026419";
@@ -219,12 +676,13 @@ mod tests {
         let gb_client = TestBookClient::default();
         let op_client = TestBookClient::new_with_isbn(isbn, pages, description, provider_link);
         let client = Client::new(Box::new(gb_client), Box::new(op_client));
-        let returned_book = client
+        let answer = client
             .fetch_book(&RequestType::OpenLibrary, isbn, "", "")
             .await
             .expect("could not retrieve book");
 
-        assert_eq!(Book::new(pages, description, provider_link), returned_book);
+        assert_eq!("openlibrary", answer.provider);
+        assert_eq!(Book::new(pages, description, provider_link), answer.book);
     }
 
     #[tokio::test]
@@ -244,11 +702,570 @@ mod tests {
             provider_link,
         );
         let client = Client::new(Box::new(gb_client), Box::new(op_client));
-        let returned_book = client
+        let answer = client
             .fetch_book(&RequestType::OpenLibrary, "", title, author)
             .await
             .expect("could not retrieve book");
 
-        assert_eq!(Book::new(pages, description, provider_link), returned_book);
+        assert_eq!("openlibrary", answer.provider);
+        assert_eq!(Book::new(pages, description, provider_link), answer.book);
+    }
+
+    #[tokio::test]
+    async fn fetch_book_from_other_provider_when_not_found() {
+        let isbn = "9781591026419";
+        let pages = 100;
+        let description = "Book description";
+        let provider_link = "http://localhost/link/to/book";
+
+        let gb_client = TestBookClient::new_with_isbn(isbn, pages, description, provider_link);
+        let op_client = TestBookClient::default();
+        let client = Client::new(Box::new(gb_client), Box::new(op_client));
+        let answer = client
+            .fetch_book(&RequestType::OpenLibrary, isbn, "", "")
+            .await
+            .expect("could not retrieve book");
+
+        assert_eq!("googlebooks", answer.provider);
+        assert_eq!(Book::new(pages, description, provider_link), answer.book);
+    }
+
+    #[tokio::test]
+    async fn return_not_found_when_no_provider_has_the_book() {
+        let client = Client::new(
+            Box::new(TestBookClient::default()),
+            Box::new(TestBookClient::default()),
+        );
+        let returned_error = client
+            .fetch_book(&RequestType::GoogleBooks, "9781591026419", "", "")
+            .await
+            .expect_err("error expected when book is not found");
+
+        assert!(matches!(
+            returned_error,
+            ResponseError::BookClientError(ClientError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn return_not_found_from_requested_provider_without_fallback() {
+        let isbn = "9781591026419";
+        let gb_client = TestBookClient::new_with_isbn(isbn, 100, "", "");
+        let client = Client::new(Box::new(gb_client), Box::new(TestBookClient::default()));
+        let returned_error = client
+            .fetch_book_with_rating(
+                &RequestType::OpenLibrary,
+                isbn,
+                "",
+                "",
+                &FetchOptions::default(),
+            )
+            .await
+            .expect_err("error expected when requested provider does not have the book");
+
+        assert!(matches!(
+            returned_error,
+            ResponseError::BookClientError(ClientError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn fetch_book_with_rating_from_other_provider_with_fallback() {
+        let isbn = "9781591026419";
+        let gb_client =
+            TestBookClient::new_with_isbn(isbn, 100, "", "").with_rating(Rating::new(5.0, 10));
+        let client = Client::new(Box::new(gb_client), Box::new(TestBookClient::default()));
+        let rated_book = client
+            .fetch_book_with_rating(
+                &RequestType::OpenLibrary,
+                isbn,
+                "",
+                "",
+                &FetchOptions {
+                    aggregate_rating: true,
+                    fallback: true,
+                    ..FetchOptions::default()
+                },
+            )
+            .await
+            .expect("could not retrieve book");
+
+        assert_eq!("googlebooks", rated_book.answer.provider);
+        let rating = rated_book.rating.expect("rating expected");
+        assert_eq!(Rating::new(5.0, 10), rating.rating());
+        assert_eq!(1, rating.providers.len());
+    }
+
+    #[tokio::test]
+    async fn fetch_book_with_rating_combined_from_all_providers() {
+        let isbn = "9781591026419";
+        let gb_client =
+            TestBookClient::new_with_isbn(isbn, 100, "", "").with_rating(Rating::new(5.0, 10));
+        let op_client =
+            TestBookClient::new_with_isbn(isbn, 100, "", "").with_rating(Rating::new(3.0, 30));
+        let client = Client::new(Box::new(gb_client), Box::new(op_client));
+        let rated_book = client
+            .fetch_book_with_rating(
+                &RequestType::OpenLibrary,
+                isbn,
+                "",
+                "",
+                &FetchOptions {
+                    aggregate_rating: true,
+                    ..FetchOptions::default()
+                },
+            )
+            .await
+            .expect("could not retrieve book");
+
+        assert_eq!("openlibrary", rated_book.answer.provider);
+        let rating = rated_book.rating.expect("rating expected");
+        assert_eq!(Rating::new(3.5, 40), rating.rating());
+        assert_eq!(Some(&Rating::new(5.0, 10)), rating.provider("googlebooks"));
+        assert_eq!(Some(&Rating::new(3.0, 30)), rating.provider("openlibrary"));
+    }
+
+    #[tokio::test]
+    async fn fetch_book_with_rating_of_providers_that_have_the_book() {
+        let title = "Book title";
+        let author = "Author Rothua";
+        let gb_client = TestBookClient::new_with_title_and_author(title, author, 100, "", "")
+            .with_rating(Rating::new(4.0, 8));
+        let op_client = TestBookClient::default();
+        let client = Client::new(Box::new(gb_client), Box::new(op_client));
+        let rated_book = client
+            .fetch_book_with_rating(
+                &RequestType::GoogleBooks,
+                "",
+                title,
+                author,
+                &FetchOptions {
+                    aggregate_rating: true,
+                    ..FetchOptions::default()
+                },
+            )
+            .await
+            .expect("could not retrieve book");
+
+        let rating = rated_book.rating.expect("rating expected");
+        assert_eq!(Rating::new(4.0, 8), rating.rating());
+        assert_eq!(1, rating.providers.len());
+    }
+
+    #[tokio::test]
+    async fn fetch_book_without_rating_when_no_provider_has_one() {
+        let isbn = "9781591026419";
+        let gb_client = TestBookClient::new_with_isbn(isbn, 100, "", "");
+        let op_client = TestBookClient::new_with_isbn(isbn, 100, "", "");
+        let client = Client::new(Box::new(gb_client), Box::new(op_client));
+        let rated_book = client
+            .fetch_book_with_rating(
+                &RequestType::GoogleBooks,
+                isbn,
+                "",
+                "",
+                &FetchOptions {
+                    aggregate_rating: true,
+                    ..FetchOptions::default()
+                },
+            )
+            .await
+            .expect("could not retrieve book");
+
+        assert_eq!(None, rated_book.rating);
+    }
+
+    /// Client that returns a rated book for any lookup after the given delay, and counts
+    /// the lookups made.
+    struct SlowBookClient {
+        delay: Duration,
+        rating: Rating,
+        lookups: Arc<AtomicUsize>,
+    }
+
+    impl SlowBookClient {
+        fn new(delay: Duration, rating: Rating) -> (Self, Arc<AtomicUsize>) {
+            let lookups = Arc::new(AtomicUsize::new(0));
+            let client = Self {
+                delay,
+                rating,
+                lookups: Arc::clone(&lookups),
+            };
+            (client, lookups)
+        }
+    }
+
+    #[async_trait]
+    impl BookClient for SlowBookClient {
+        async fn book_by_isbn(&self, _isbn: &Isbn) -> Result<Book, ClientError> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            Ok(Book::new_with_rating(100, "", "", self.rating.clone()))
+        }
+
+        async fn book(&self, _author: &str, _title: &str) -> Result<Book, ClientError> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            Ok(Book::new_with_rating(100, "", "", self.rating.clone()))
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_book_without_querying_other_provider_when_rating_is_not_requested() {
+        let isbn = "9781591026419";
+        let gb_client =
+            TestBookClient::new_with_isbn(isbn, 100, "", "").with_rating(Rating::new(5.0, 10));
+        let (op_client, op_lookups) = SlowBookClient::new(Duration::ZERO, Rating::new(3.0, 30));
+        let client = Client::new(Box::new(gb_client), Box::new(op_client));
+        let rated_book = client
+            .fetch_book_with_rating(
+                &RequestType::GoogleBooks,
+                isbn,
+                "",
+                "",
+                &FetchOptions::default(),
+            )
+            .await
+            .expect("could not retrieve book");
+
+        assert_eq!(Some(Rating::new(5.0, 10)), rated_book.answer.book.rating);
+        assert_eq!(None, rated_book.rating);
+        assert_eq!(0, op_lookups.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fetch_rating_of_other_provider_concurrently() {
+        let delay = Duration::from_secs(2);
+        let (gb_client, _) = SlowBookClient::new(delay, Rating::new(5.0, 10));
+        let (op_client, _) = SlowBookClient::new(delay, Rating::new(3.0, 30));
+        let client = Client::new(Box::new(gb_client), Box::new(op_client));
+        let start = Instant::now();
+        let rated_book = client
+            .fetch_book_with_rating(
+                &RequestType::GoogleBooks,
+                "9781591026419",
+                "",
+                "",
+                &FetchOptions {
+                    aggregate_rating: true,
+                    ..FetchOptions::default()
+                },
+            )
+            .await
+            .expect("could not retrieve book");
+
+        assert!(start.elapsed() < delay * 2);
+        let rating = rated_book.rating.expect("rating expected");
+        assert_eq!(Rating::new(3.5, 40), rating.rating());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn leave_out_rating_of_other_provider_that_times_out() {
+        let isbn = "9781591026419";
+        let gb_client =
+            TestBookClient::new_with_isbn(isbn, 100, "", "").with_rating(Rating::new(5.0, 10));
+        let (op_client, _) = SlowBookClient::new(Duration::from_secs(60), Rating::new(3.0, 30));
+        let client = Client::new(Box::new(gb_client), Box::new(op_client));
+        let start = Instant::now();
+        let rated_book = client
+            .fetch_book_with_rating(
+                &RequestType::GoogleBooks,
+                isbn,
+                "",
+                "",
+                &FetchOptions {
+                    aggregate_rating: true,
+                    ..FetchOptions::default()
+                },
+            )
+            .await
+            .expect("could not retrieve book");
+
+        assert!(start.elapsed() < Duration::from_secs(60));
+        let rating = rated_book.rating.expect("rating expected");
+        assert_eq!(Rating::new(5.0, 10), rating.rating());
+        assert_eq!(None, rating.provider("openlibrary"));
+    }
+
+    #[tokio::test]
+    async fn skip_rating_of_other_provider_that_returned_the_book() {
+        let (gb_client, gb_lookups) = SlowBookClient::new(Duration::ZERO, Rating::new(5.0, 10));
+        let client = Client::new(Box::new(gb_client), Box::new(TestBookClient::default()));
+        let rated_book = client
+            .fetch_book_with_rating(
+                &RequestType::OpenLibrary,
+                "9781591026419",
+                "",
+                "",
+                &FetchOptions {
+                    aggregate_rating: true,
+                    fallback: true,
+                    ..FetchOptions::default()
+                },
+            )
+            .await
+            .expect("could not retrieve book");
+
+        assert_eq!("googlebooks", rated_book.answer.provider);
+        let rating = rated_book.rating.expect("rating expected");
+        assert_eq!(Rating::new(5.0, 10), rating.rating());
+        assert_eq!(1, rating.providers.len());
+        assert_eq!(1, gb_lookups.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn return_timeout_when_book_is_not_fetched_in_time() {
+        let (gb_client, _) = SlowBookClient::new(Duration::from_secs(60), Rating::new(5.0, 10));
+        let client = Client::new(Box::new(gb_client), Box::new(TestBookClient::default()));
+        let start = Instant::now();
+        let returned_error = client
+            .fetch_book_with_rating(
+                &RequestType::GoogleBooks,
+                "9781591026419",
+                "",
+                "",
+                &FetchOptions {
+                    aggregate_rating: true,
+                    fallback: true,
+                    ..FetchOptions::default()
+                },
+            )
+            .await
+            .expect_err("error expected when book is not fetched in time");
+
+        assert!(matches!(returned_error, ResponseError::Timeout));
+        assert!(start.elapsed() < Duration::from_secs(29));
+    }
+
+    fn albanian_options() -> QueryOptions {
+        QueryOptions {
+            lang_restrict: Some(String::from("sq")),
+            country: Some(String::from("AL")),
+            ..QueryOptions::default()
+        }
+    }
+
+    /// Returns a client whose Google Books clients created for options only have the book
+    /// with the given ISBN, and the number of clients created.
+    fn client_with_factory(isbn: &'static str) -> (Client, Arc<AtomicUsize>) {
+        let created = Arc::new(AtomicUsize::new(0));
+        let factory_created = Arc::clone(&created);
+        let client = Client::new(
+            Box::new(TestBookClient::default()),
+            Box::new(TestBookClient::default()),
+        )
+        .with_googlebooks_factory(Box::new(move |options| {
+            assert_eq!(&albanian_options(), options);
+            factory_created.fetch_add(1, Ordering::SeqCst);
+            Box::new(TestBookClient::new_with_isbn(
+                isbn,
+                100,
+                "Albanian edition",
+                "http://localhost/link/to/book",
+            ))
+        }));
+        (client, created)
+    }
+
+    #[tokio::test]
+    async fn fetch_book_with_query_options() {
+        let isbn = "9781591026419";
+        let (client, _) = client_with_factory(isbn);
+        let rated_book = client
+            .fetch_book_with_rating(
+                &RequestType::GoogleBooks,
+                isbn,
+                "",
+                "",
+                &FetchOptions {
+                    query_options: albanian_options(),
+                    ..FetchOptions::default()
+                },
+            )
+            .await
+            .expect("could not retrieve book");
+
+        assert_eq!("googlebooks", rated_book.answer.provider);
+        assert_eq!("Albanian edition", rated_book.answer.book.description);
+    }
+
+    #[tokio::test]
+    async fn reuse_clients_created_for_query_options() {
+        let isbn = "9781591026419";
+        let (client, created) = client_with_factory(isbn);
+        for _ in 0..2 {
+            client
+                .fetch_book_with_rating(
+                    &RequestType::GoogleBooks,
+                    isbn,
+                    "",
+                    "",
+                    &FetchOptions {
+                        query_options: albanian_options(),
+                        ..FetchOptions::default()
+                    },
+                )
+                .await
+                .expect("could not retrieve book");
+        }
+
+        assert_eq!(1, created.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn use_default_clients_without_query_options() {
+        let isbn = "9781591026419";
+        let (client, created) = client_with_factory(isbn);
+        let returned_error = client
+            .fetch_book_with_rating(
+                &RequestType::GoogleBooks,
+                isbn,
+                "",
+                "",
+                &FetchOptions::default(),
+            )
+            .await
+            .expect_err("error expected when book is not found");
+
+        assert!(matches!(
+            returned_error,
+            ResponseError::BookClientError(ClientError::NotFound)
+        ));
+        assert_eq!(0, created.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn ignore_query_options_without_factory() {
+        let isbn = "9781591026419";
+        let gb_client = TestBookClient::new_with_isbn(isbn, 100, "Default edition", "");
+        let client = Client::new(Box::new(gb_client), Box::new(TestBookClient::default()));
+        let rated_book = client
+            .fetch_book_with_rating(
+                &RequestType::GoogleBooks,
+                isbn,
+                "",
+                "",
+                &FetchOptions {
+                    query_options: albanian_options(),
+                    ..FetchOptions::default()
+                },
+            )
+            .await
+            .expect("could not retrieve book");
+
+        assert_eq!("Default edition", rated_book.answer.book.description);
+    }
+
+    struct TestEditionsClient {
+        editions: Vec<Edition>,
+    }
+
+    #[async_trait]
+    impl EditionsClient for TestEditionsClient {
+        async fn editions_by_isbn(&self, isbn: &Isbn) -> Result<WorkEditions, ClientError> {
+            if self
+                .editions
+                .iter()
+                .any(|edition| edition.isbns.contains(isbn))
+            {
+                Ok(WorkEditions {
+                    work_key: String::from("/works/OL8400950W"),
+                    editions: self.editions.clone(),
+                })
+            } else {
+                Err(ClientError::NotFound)
+            }
+        }
+    }
+
+    fn edition(title: &str, language: &str, isbn: &str) -> Edition {
+        Edition {
+            key: format!("/books/{}", isbn),
+            title: String::from(title),
+            languages: vec![String::from(language)],
+            publishers: Vec::new(),
+            publish_date: String::new(),
+            publish_year: None,
+            isbns: vec![Isbn::parse(isbn).expect("invalid isbn")],
+        }
+    }
+
+    fn client_with_editions() -> Client {
+        Client::new(
+            Box::new(TestBookClient::default()),
+            Box::new(TestBookClient::default()),
+        )
+        .with_editions_client(Box::new(TestEditionsClient {
+            editions: vec![
+                edition("The Blade Itself", "eng", "9780316387316"),
+                edition("Tehu", "alb", "9789928221841"),
+            ],
+        }))
+    }
+
+    #[tokio::test]
+    async fn fetch_editions_in_each_language() {
+        let client = client_with_editions();
+        let book_editions = client
+            .fetch_editions(
+                "9780316387316",
+                &[String::from("sq"), String::from("en"), String::from("de")],
+            )
+            .await
+            .expect("could not retrieve editions");
+
+        assert_eq!("/works/OL8400950W", book_editions.work_key);
+        let languages: Vec<(&str, usize)> = book_editions
+            .languages
+            .iter()
+            .map(|language| (language.language.as_str(), language.editions.len()))
+            .collect();
+        assert_eq!(vec![("sq", 1), ("en", 1), ("de", 0)], languages);
+        assert_eq!("Tehu", book_editions.languages[0].editions[0].title);
+    }
+
+    #[tokio::test]
+    async fn return_error_when_isbn_is_missing_for_editions() {
+        let client = client_with_editions();
+        let returned_error = client
+            .fetch_editions("", &[String::from("sq")])
+            .await
+            .expect_err("error expected when isbn is missing");
+
+        assert!(matches!(
+            returned_error,
+            ResponseError::MissingParameter(message) if message == "ISBN must be provided"
+        ));
+    }
+
+    #[tokio::test]
+    async fn return_invalid_parameter_when_isbn_is_invalid_for_editions() {
+        let client = client_with_editions();
+        let returned_error = client
+            .fetch_editions("978031638731", &[String::from("sq")])
+            .await
+            .expect_err("error expected when isbn is invalid");
+
+        assert!(matches!(
+            returned_error,
+            ResponseError::InvalidParameter(message) if message.starts_with("Invalid ISBN")
+        ));
+    }
+
+    #[tokio::test]
+    async fn return_unsupported_without_editions_client() {
+        let client = Client::new(
+            Box::new(TestBookClient::default()),
+            Box::new(TestBookClient::default()),
+        );
+        let returned_error = client
+            .fetch_editions("9780316387316", &[String::from("sq")])
+            .await
+            .expect_err("error expected without editions client");
+
+        assert!(matches!(
+            returned_error,
+            ResponseError::BookClientError(ClientError::Unsupported(LookupKind::Editions))
+        ));
     }
 }
@@ -7,12 +7,21 @@ and deal with HTTP error handling.
 use core::fmt;
 use std::fmt::{Debug, Formatter};
 use std::str::FromStr;
+use std::time::Duration;
 
 use lambda_http::http::StatusCode;
 use lambda_http::{Body, Error, RequestExt, Response};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use zana::{Book, ClientError};
+use zana::googlebooks::QueryOptions;
+use zana::openlibrary::{self, Edition};
+use zana::rating::AggregatedRating;
+use zana::{Book, ClientError, Rating};
+
+use crate::book::{BookEditions, RatedBook};
+
+/// Languages that editions are listed in, when the `lang` query parameter is missing.
+pub const DEFAULT_EDITION_LANGUAGES: [&str; 2] = ["sq", "en"];
 
 /// Enum that represents all supported book data providers.
 #[derive(Debug, PartialEq)]
@@ -21,6 +30,16 @@ pub enum RequestType {
     OpenLibrary,
 }
 
+impl RequestType {
+    /// Returns the name of the provider, as used in the `type` query parameter.
+    pub fn provider(&self) -> &'static str {
+        match self {
+            RequestType::GoogleBooks => "googlebooks",
+            RequestType::OpenLibrary => "openlibrary",
+        }
+    }
+}
+
 impl FromStr for RequestType {
     type Err = ();
 
@@ -38,13 +57,17 @@ impl FromStr for RequestType {
 /// The error contains different variants based on the source of the error.
 #[derive(Error, Debug)]
 pub enum ResponseError {
-    /// Occurs when a request parameter is missing or is invalid.
+    /// Occurs when a required request parameter is missing.
     MissingParameter(String),
+    /// Occurs when a request parameter is present, but its value is not valid.
+    InvalidParameter(String),
     /// Occurs when an error is returned from a request made from [`zana`](zana) clients.
     BookClientError(#[from] ClientError),
     /// Occurs for any error that comes from [`reqwest`](reqwest) crate. This will include errors
     /// when a timeout is reached, or no connection can be made to the specified endpoint.
     HttpClientError(#[from] reqwest::Error),
+    /// Occurs when the book is not returned before the deadline of the request.
+    Timeout,
     /// Generic error that occurs during request handling.
     ServiceError,
 }
@@ -52,17 +75,33 @@ pub enum ResponseError {
 impl ResponseError {
     fn status_and_details(&self) -> (u16, &str) {
         match self {
-            ResponseError::MissingParameter(details) => (400, details),
+            ResponseError::MissingParameter(details) | ResponseError::InvalidParameter(details) => {
+                (400, details)
+            }
             ResponseError::BookClientError(err) => {
                 let status_and_details = match err {
-                    ClientError::InternalClient(_) => {
+                    ClientError::InternalClient(_) | ClientError::Connect(_) => {
                         (503, "Could not retrieve data from external service")
                     }
-                    ClientError::RateLimitExceeded => {
+                    ClientError::Timeout(_) => (504, "External service did not respond in time"),
+                    ClientError::Deserialization(_) => {
+                        (502, "Invalid response from external service")
+                    }
+                    ClientError::Unauthorized(_, _) => {
+                        (502, "Could not authenticate with external service")
+                    }
+                    ClientError::InvalidInput(details) => (400, details.as_str()),
+                    ClientError::RateLimitExceeded(_) => {
                         (429, "Rate limit exceeded for external service")
                     }
                     ClientError::NotFound => (404, "Book not found"),
                     ClientError::Http(status_code, details) => (*status_code, details.as_str()),
+                    ClientError::CircuitOpen(_) => {
+                        (503, "External service is temporarily unavailable")
+                    }
+                    ClientError::Unsupported(_) => {
+                        (400, "Lookup is not supported by the external service")
+                    }
                 };
                 status_and_details
             }
@@ -76,21 +115,44 @@ impl ResponseError {
                 };
                 status_and_details
             }
+            ResponseError::Timeout => (504, "Book could not be retrieved in time"),
             _ => (500, "Something went wrong, please try again."),
         }
     }
 }
 
+impl ResponseError {
+    /// Returns how long the caller should wait before retrying, when the rate limit of
+    /// an external service is exceeded and the service provided it, or when requests to
+    /// the service are paused because it keeps failing.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ResponseError::BookClientError(err) => err.retry_after(),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for ResponseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let error_name = match self {
             ResponseError::MissingParameter(_) => "MissingParameter",
+            ResponseError::InvalidParameter(_) => "InvalidParameter",
             ResponseError::BookClientError(client_error) => match client_error {
-                ClientError::RateLimitExceeded => "RateLimitExceeded",
+                ClientError::RateLimitExceeded(_) => "RateLimitExceeded",
                 ClientError::NotFound => "NotFound",
-                ClientError::Http(_, _) | ClientError::InternalClient(_) => "HttpClientError",
+                ClientError::Timeout(_) => "Timeout",
+                ClientError::InvalidInput(_) => "InvalidInput",
+                ClientError::Unauthorized(_, _) => "ServiceError",
+                ClientError::CircuitOpen(_) => "ServiceUnavailable",
+                ClientError::Unsupported(_) => "Unsupported",
+                ClientError::Http(_, _)
+                | ClientError::InternalClient(_)
+                | ClientError::Connect(_)
+                | ClientError::Deserialization(_) => "HttpClientError",
             },
             ResponseError::HttpClientError(_) => "HttpClientError",
+            ResponseError::Timeout => "Timeout",
             ResponseError::ServiceError => "ServiceError",
         };
         write!(f, "{}", error_name)
@@ -143,48 +205,65 @@ impl FailureResponse {
 ///
 /// Ratings are by default not required, and set to `None`, since not all providers may support them,
 /// and not all books will have ratings attached when retrieved from providers.
+/// The rating of the provider is only returned as `rating`, and is left out of `data`.
+///
+/// The provider is the one that returned the book, which may differ from the requested provider
+/// when the book is not available there.
+///
+/// The aggregated rating combines the ratings of all providers that have the book, weighted by
+/// their number of ratings, and is meant to be shown as the headline rating of the book.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SuccessResponse {
-    pub data: BookData,
-    pub rating: Option<RatingData>,
+    pub data: Book,
+    pub rating: Option<Rating>,
+    pub provider: String,
+    pub aggregated_rating: Option<AggregatedRating>,
 }
 
 impl SuccessResponse {
-    fn new(data: BookData) -> Self {
-        SuccessResponse { data, rating: None }
+    fn new(rated_book: RatedBook) -> Self {
+        let mut book = rated_book.answer.book;
+        let rating = book.rating.take();
+        SuccessResponse {
+            data: book,
+            rating,
+            provider: rated_book.answer.provider,
+            aggregated_rating: rated_book.rating,
+        }
     }
 }
 
-/// Represents a book and some of its data.
+/// Response used to represent the editions of a book in each of the requested languages.
+///
+/// Every requested language is included, with `available` set to false when the book has
+/// no edition in it, so that callers can link the editions of a book in other languages.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct BookData {
-    pub page_count: u32,
-    pub description: String,
-    pub provider_link: String,
+pub struct EditionsResponse {
+    pub work_key: String,
+    pub languages: Vec<LanguageEditionsResponse>,
 }
 
-impl BookData {
-    pub fn new(page_count: u32, description: &str, provider_link: &str) -> Self {
-        Self {
-            page_count,
-            description: String::from(description),
-            provider_link: String::from(provider_link),
-        }
-    }
-}
-
-/// Represents a ratings about a specific book.
+/// Editions of a book in a single language.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct RatingData {
-    pub average_rating: f32,
-    pub ratings_count: u32,
+pub struct LanguageEditionsResponse {
+    pub language: String,
+    pub available: bool,
+    pub editions: Vec<Edition>,
 }
 
-impl RatingData {
-    pub fn new(average_rating: f32, ratings_count: u32) -> Self {
-        Self {
-            average_rating,
-            ratings_count,
+impl EditionsResponse {
+    fn new(book_editions: BookEditions) -> Self {
+        EditionsResponse {
+            work_key: book_editions.work_key,
+            languages: book_editions
+                .languages
+                .into_iter()
+                .map(|language| LanguageEditionsResponse {
+                    available: !language.editions.is_empty(),
+                    language: language.language,
+                    editions: language.editions,
+                })
+                .collect(),
         }
     }
 }
@@ -193,31 +272,40 @@ impl RatingData {
 ///
 /// Response is returned as JSON and content type is set to `application/json` by default.
 /// `503` status code is provided by errors if no other specific status code is available from the error.
+/// `Retry-After` header is set in seconds, when the error provides it.
 pub fn failure_response(error: ResponseError) -> Result<Response<Body>, Error> {
     let (status, details) = error.status_and_details();
     let response =
         serde_json::to_string(&FailureResponse::new_with_details(&error, details, status))?;
 
-    Ok(Response::builder()
+    let mut builder = Response::builder()
         .header("content-type", "application/json")
-        .status(StatusCode::from_u16(status).unwrap_or(StatusCode::SERVICE_UNAVAILABLE))
-        .body(Body::Text(response))?)
+        .status(StatusCode::from_u16(status).unwrap_or(StatusCode::SERVICE_UNAVAILABLE));
+    if let Some(retry_after) = error.retry_after() {
+        // Rounded up, so that callers do not retry before the rate limit resets
+        let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        builder = builder.header("retry-after", seconds.to_string());
+    }
+    Ok(builder.body(Body::Text(response))?)
 }
 
 /// Returns a new success response or an error if the response could not be constructed.
 ///
 /// Response is returned as JSON and content type is set to `application/json` by default.
-pub fn success_response(book: &Book) -> Result<Response<Body>, Error> {
-    let mut response = SuccessResponse::new(BookData::new(
-        book.page_count,
-        &book.description,
-        &book.provider_link,
-    ));
+pub fn success_response(rated_book: &RatedBook) -> Result<Response<Body>, Error> {
+    let response = serde_json::to_string(&SuccessResponse::new(rated_book.clone()))?;
 
-    if let Some(rating) = &book.rating {
-        response.rating = Some(RatingData::new(rating.average_rating, rating.ratings_count));
-    }
-    let response = serde_json::to_string(&response)?;
+    Ok(Response::builder()
+        .header("content-type", "application/json")
+        .status(200)
+        .body(Body::Text(response))?)
+}
+
+/// Returns a new editions response or an error if the response could not be constructed.
+///
+/// Response is returned as JSON and content type is set to `application/json` by default.
+pub fn editions_response(book_editions: &BookEditions) -> Result<Response<Body>, Error> {
+    let response = serde_json::to_string(&EditionsResponse::new(book_editions.clone()))?;
 
     Ok(Response::builder()
         .header("content-type", "application/json")
@@ -225,6 +313,38 @@ pub fn success_response(book: &Book) -> Result<Response<Body>, Error> {
         .body(Body::Text(response))?)
 }
 
+/// Returns true if the request is for the editions of a book, made to the `/editions` path.
+pub fn is_editions_request(request: &impl RequestExt) -> bool {
+    request
+        .raw_http_path()
+        .trim_end_matches('/')
+        .ends_with("/editions")
+}
+
+/// Returns the languages that editions are listed in, as ISO 639-1 codes, from the
+/// comma separated `lang` query parameter (e.g. `sq,en`).
+///
+/// When the parameter is missing, [`DEFAULT_EDITION_LANGUAGES`](DEFAULT_EDITION_LANGUAGES)
+/// are returned. If any of the languages is not known, an error is returned instead.
+pub fn edition_languages(request: &impl RequestExt) -> Result<Vec<String>, ResponseError> {
+    let languages: Vec<String> = match request.query_string_parameters().first("lang") {
+        Some(languages) => languages
+            .split(',')
+            .map(|language| language.trim().to_lowercase())
+            .filter(|language| !language.is_empty())
+            .collect(),
+        None => DEFAULT_EDITION_LANGUAGES.map(String::from).to_vec(),
+    };
+    if languages.is_empty()
+        || languages
+            .iter()
+            .any(|language| openlibrary::marc_language_code(language).is_none())
+    {
+        return Err(invalid_parameter("lang"));
+    }
+    Ok(languages)
+}
+
 /// Returns a query parameter from the request, or the default provided value if the parameter
 /// is missing.
 pub fn query_parameter(request: &impl RequestExt, name: &str, default: &str) -> String {
@@ -243,7 +363,7 @@ pub fn request_type(request: &impl RequestExt) -> Result<RequestType, ResponseEr
         Some(request_type) => match request_type.parse() {
             Ok(request_type) => request_type,
             Err(_) => {
-                return Err(ResponseError::MissingParameter(String::from(
+                return Err(ResponseError::InvalidParameter(String::from(
                     "Invalid type",
                 )));
             }
@@ -257,11 +377,86 @@ pub fn request_type(request: &impl RequestExt) -> Result<RequestType, ResponseEr
     Ok(request_type)
 }
 
+/// Returns true if the `aggregatedRating` query parameter is `true`, so that the rating
+/// combined from all providers is returned with the book.
+///
+/// When the parameter is missing, false is returned. If it is not `true` or `false`,
+/// an error is returned instead.
+pub fn aggregate_rating(request: &impl RequestExt) -> Result<bool, ResponseError> {
+    match request.query_string_parameters().first("aggregatedRating") {
+        Some(value) => value
+            .parse()
+            .map_err(|_| invalid_parameter("aggregatedRating")),
+        None => Ok(false),
+    }
+}
+
+/// Returns true if the `fallback` query parameter is `true`, so that the other provider is
+/// queried when the requested one does not return the book.
+///
+/// When the parameter is missing, false is returned. If it is not `true` or `false`,
+/// an error is returned instead.
+pub fn fallback(request: &impl RequestExt) -> Result<bool, ResponseError> {
+    match request.query_string_parameters().first("fallback") {
+        Some(value) => value.parse().map_err(|_| invalid_parameter("fallback")),
+        None => Ok(false),
+    }
+}
+
+/// Returns the [`QueryOptions`](struct@QueryOptions) for Google Books from the `lang`, `country`,
+/// `printType` and `projection` query parameters.
+///
+/// Only options given as query parameters are set, so requests without them are queried
+/// as they would be without options.
+/// If any of the query parameters is invalid, an error is returned instead.
+pub fn query_options(request: &impl RequestExt) -> Result<QueryOptions, ResponseError> {
+    let parameters = request.query_string_parameters();
+    let lang_restrict = match parameters.first("lang") {
+        Some(lang) if is_code(lang) => Some(lang.to_lowercase()),
+        Some(_) => return Err(invalid_parameter("lang")),
+        None => None,
+    };
+    let country = match parameters.first("country") {
+        Some(country) if is_code(country) => Some(country.to_uppercase()),
+        Some(_) => return Err(invalid_parameter("country")),
+        None => None,
+    };
+    let print_type = parameters
+        .first("printType")
+        .map(|print_type| print_type.parse())
+        .transpose()
+        .map_err(|_| invalid_parameter("printType"))?;
+    let projection = parameters
+        .first("projection")
+        .map(|projection| projection.parse())
+        .transpose()
+        .map_err(|_| invalid_parameter("projection"))?;
+
+    Ok(QueryOptions {
+        lang_restrict,
+        country,
+        print_type,
+        projection,
+    })
+}
+
+/// Returns true if the value is a two-letter language or country code.
+fn is_code(value: &str) -> bool {
+    value.len() == 2 && value.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+fn invalid_parameter(name: &str) -> ResponseError {
+    ResponseError::InvalidParameter(format!("Invalid {}", name))
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::book::RatedBook;
+    use crate::book::{BookEditions, LanguageEditions};
     use crate::http::{
-        failure_response, query_parameter, request_type, success_response, FailureResponse,
-        RequestType, ResponseError, SuccessResponse,
+        aggregate_rating, edition_languages, editions_response, failure_response, fallback,
+        is_editions_request, query_options, query_parameter, request_type, success_response,
+        EditionsResponse, FailureResponse, RequestType, ResponseError, SuccessResponse,
     };
     use lambda_http::aws_lambda_events::query_map::QueryMap;
     use lambda_http::ext::PayloadError;
@@ -272,23 +467,31 @@ mod tests {
     use serde::Deserialize;
     use std::collections::HashMap;
     use std::str::FromStr;
-    use zana::{Book, ClientError, Rating};
+    use std::time::Duration;
+    use zana::fallback::ProviderBook;
+    use zana::googlebooks::{PrintType, Projection, QueryOptions};
+    use zana::isbn::Isbn;
+    use zana::openlibrary::Edition;
+    use zana::rating::{AggregatedRating, ProviderRating};
+    use zana::{Book, ClientError, LookupKind, RateLimit, Rating};
 
     struct TestRequest {
         query_map: QueryMap,
+        path: String,
     }
 
     impl TestRequest {
         fn new(query_params: HashMap<String, String>) -> Self {
             Self {
                 query_map: query_params.into(),
+                path: String::from("/books"),
             }
         }
     }
 
     impl RequestExt for TestRequest {
         fn raw_http_path(&self) -> String {
-            unimplemented!()
+            self.path.clone()
         }
 
         fn with_raw_http_path(self, _path: &str) -> Self {
@@ -349,26 +552,45 @@ mod tests {
         match request_type(request) {
             Ok(_) => panic!("request type not expected when not provided as param"),
             Err(err) => match err {
-                ResponseError::MissingParameter(message) => assert_eq!(expected_message, message),
+                ResponseError::MissingParameter(message)
+                | ResponseError::InvalidParameter(message) => assert_eq!(expected_message, message),
                 _ => panic!("invalid error returned"),
             },
         }
     }
 
-    fn assert_book_success_response(book: &Book) {
-        let response = success_response(&book).expect("response expected to be present");
+    fn book_success_response(book: &Book, rating: Option<AggregatedRating>) -> SuccessResponse {
+        let rated_book = RatedBook {
+            answer: ProviderBook {
+                provider: String::from("openlibrary"),
+                book: book.clone(),
+            },
+            rating,
+        };
+        let response = success_response(&rated_book).expect("response expected to be present");
         let body = String::from_utf8(response.body().to_vec()).expect("utf8 string expected");
-        let response_book: SuccessResponse =
-            serde_json::from_str(&body).expect("response expected to be parsed");
 
         assert_eq!(StatusCode::OK, response.status());
+        serde_json::from_str(&body).expect("response expected to be parsed")
+    }
+
+    fn assert_book_success_response(book: &Book) {
+        let response_book = book_success_response(book, None);
+
+        assert_eq!("openlibrary", response_book.provider);
+        assert_eq!(None, response_book.aggregated_rating);
         assert_book_equality(book, response_book);
     }
 
     fn assert_book_equality(book: &Book, response_book: SuccessResponse) {
-        assert_eq!(book.page_count, response_book.data.page_count);
-        assert_eq!(book.provider_link, response_book.data.provider_link);
-        assert_eq!(book.description, response_book.data.description);
+        assert_eq!(None, response_book.data.rating);
+        assert_eq!(
+            Book {
+                rating: None,
+                ..book.clone()
+            },
+            response_book.data
+        );
 
         if let Some(book_rating) = &book.rating {
             let response_book_rating = response_book.rating.expect("rating expected");
@@ -407,6 +629,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn status_code_400_on_invalid_parameter() {
+        let error = ResponseError::InvalidParameter(String::from("Invalid ISBN: invalid length"));
+        assert_eq!(
+            (400, "Invalid ISBN: invalid length"),
+            error.status_and_details()
+        );
+        assert_eq!("InvalidParameter", error.to_string());
+    }
+
+    #[test]
+    fn status_code_400_on_unsupported_lookup() {
+        let error =
+            ResponseError::BookClientError(ClientError::Unsupported(LookupKind::AuthorAndTitle));
+        assert_eq!(400, error.status_and_details().0);
+        assert_eq!("Unsupported", error.to_string());
+    }
+
     #[test]
     fn status_code_404_on_missing_book() {
         assert_eq!(
@@ -421,12 +661,55 @@ mod tests {
     fn status_code_429_on_exceeded_limit() {
         assert_eq!(
             429,
-            ResponseError::BookClientError(ClientError::RateLimitExceeded)
+            ResponseError::BookClientError(ClientError::RateLimitExceeded(RateLimit::default()))
                 .status_and_details()
                 .0
         );
     }
 
+    #[test]
+    fn status_code_400_on_invalid_input() {
+        assert_eq!(
+            (400, "author or title must be provided"),
+            ResponseError::BookClientError(ClientError::InvalidInput(String::from(
+                "author or title must be provided"
+            )))
+            .status_and_details()
+        );
+    }
+
+    #[test]
+    fn status_code_502_on_unauthorized_external_request() {
+        assert_eq!(
+            502,
+            ResponseError::BookClientError(ClientError::Unauthorized(
+                400,
+                String::from("API key not valid")
+            ))
+            .status_and_details()
+            .0
+        );
+    }
+
+    #[test]
+    fn status_code_502_on_invalid_external_response() {
+        assert_eq!(
+            502,
+            ResponseError::BookClientError(ClientError::Deserialization(String::from(
+                "expected value"
+            )))
+            .status_and_details()
+            .0
+        );
+    }
+
+    #[test]
+    fn status_code_504_on_request_timeout() {
+        let error = ResponseError::Timeout;
+        assert_eq!(504, error.status_and_details().0);
+        assert_eq!("Timeout", error.to_string());
+    }
+
     #[test]
     fn status_code_from_book_client_http_error() {
         let status_code: u16 = 500;
@@ -453,6 +736,54 @@ mod tests {
         assert_eq!(expected_response, body);
     }
 
+    #[test]
+    fn response_with_retry_after_on_exceeded_limit() {
+        let response_error =
+            ResponseError::BookClientError(ClientError::RateLimitExceeded(RateLimit {
+                retry_after: Some(Duration::from_millis(30_500)),
+                reason: None,
+            }));
+
+        let response = failure_response(response_error).expect("response expected to be present");
+
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+        assert_eq!(
+            Some("31"),
+            response
+                .headers()
+                .get("retry-after")
+                .and_then(|value| value.to_str().ok())
+        );
+    }
+
+    #[test]
+    fn response_without_retry_after_on_exceeded_limit() {
+        let response_error =
+            ResponseError::BookClientError(ClientError::RateLimitExceeded(RateLimit::default()));
+
+        let response = failure_response(response_error).expect("response expected to be present");
+
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+        assert!(response.headers().get("retry-after").is_none());
+    }
+
+    #[test]
+    fn response_with_retry_after_when_circuit_is_open() {
+        let response_error =
+            ResponseError::BookClientError(ClientError::CircuitOpen(Duration::from_secs(12)));
+
+        let response = failure_response(response_error).expect("response expected to be present");
+
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
+        assert_eq!(
+            Some("12"),
+            response
+                .headers()
+                .get("retry-after")
+                .and_then(|value| value.to_str().ok())
+        );
+    }
+
     #[test]
     fn response_from_book() {
         let rating = Rating::new(4.5, 123);
@@ -465,6 +796,43 @@ mod tests {
         assert_book_success_response(&book);
     }
 
+    #[test]
+    fn response_with_rating_only_outside_of_book_data() {
+        let book = Book::new_with_rating(
+            531,
+            "Book description here",
+            "http://localhost/link/to/book",
+            Rating::new(4.5, 123),
+        );
+        let rated_book = RatedBook {
+            answer: ProviderBook {
+                provider: String::from("openlibrary"),
+                book,
+            },
+            rating: None,
+        };
+        let response = success_response(&rated_book).expect("response expected to be present");
+        let body: serde_json::Value =
+            serde_json::from_slice(response.body()).expect("response expected to be parsed");
+
+        assert_eq!(None, body["data"].get("rating"));
+        assert_eq!(123, body["rating"]["ratings_count"]);
+    }
+
+    #[test]
+    fn response_from_book_with_bibliographic_data() {
+        let mut book = Book::new(
+            531,
+            "Book description here",
+            "http://localhost/link/to/book",
+        );
+        book.title = String::from("Book title");
+        book.authors = vec![String::from("Author Rothua")];
+        book.publisher = String::from("Publisher");
+        book.language = String::from("sq");
+        assert_book_success_response(&book);
+    }
+
     #[test]
     fn response_from_book_without_ratings() {
         let book = Book::new(
@@ -475,6 +843,24 @@ mod tests {
         assert_book_success_response(&book);
     }
 
+    #[test]
+    fn response_from_book_with_aggregated_rating() {
+        let book = Book::new_with_rating(
+            531,
+            "Book description here",
+            "http://localhost/link/to/book",
+            Rating::new(3.0, 30),
+        );
+        let rating = AggregatedRating::new(vec![
+            ProviderRating::new("googlebooks", Rating::new(5.0, 10)),
+            ProviderRating::new("openlibrary", Rating::new(3.0, 30)),
+        ]);
+        let response_book = book_success_response(&book, rating.clone());
+
+        assert_eq!(rating, response_book.aggregated_rating);
+        assert_book_equality(&book, response_book);
+    }
+
     #[test]
     fn query_parameter_when_it_exists() {
         let param = "param-name";
@@ -511,9 +897,218 @@ mod tests {
         assert_request_type_err(&request, "Invalid type");
     }
 
+    #[test]
+    fn request_type_invalid_is_not_reported_as_missing() {
+        let request = TestRequest::new(HashMap::from([(
+            String::from("type"),
+            String::from("invalid"),
+        )]));
+        assert!(matches!(
+            request_type(&request),
+            Err(ResponseError::InvalidParameter(_))
+        ));
+    }
+
     #[test]
     fn request_type_missing() {
         let request = TestRequest::new(HashMap::new());
         assert_request_type_err(&request, "Type is required");
     }
+
+    fn query_params(params: &[(&str, &str)]) -> TestRequest {
+        TestRequest::new(
+            params
+                .iter()
+                .map(|(name, value)| (String::from(*name), String::from(*value)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn aggregate_rating_from_query_parameter() {
+        for (params, expected) in [
+            (vec![("aggregatedRating", "true")], true),
+            (vec![("aggregatedRating", "false")], false),
+            (vec![], false),
+        ] {
+            let request = query_params(&params);
+            assert_eq!(
+                expected,
+                aggregate_rating(&request).expect("could not retrieve parameter")
+            );
+        }
+    }
+
+    #[test]
+    fn aggregate_rating_invalid() {
+        let request = query_params(&[("aggregatedRating", "yes")]);
+        match aggregate_rating(&request) {
+            Err(ResponseError::InvalidParameter(message)) => {
+                assert_eq!("Invalid aggregatedRating", message)
+            }
+            other => panic!("invalid aggregatedRating expected, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn fallback_from_query_parameter() {
+        for (params, expected) in [
+            (vec![("fallback", "true")], true),
+            (vec![("fallback", "false")], false),
+            (vec![], false),
+        ] {
+            let request = query_params(&params);
+            assert_eq!(
+                expected,
+                fallback(&request).expect("could not retrieve parameter")
+            );
+        }
+    }
+
+    #[test]
+    fn fallback_invalid() {
+        let request = query_params(&[("fallback", "yes")]);
+        match fallback(&request) {
+            Err(ResponseError::InvalidParameter(message)) => {
+                assert_eq!("Invalid fallback", message)
+            }
+            other => panic!("invalid fallback expected, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn query_options_from_query_parameters() {
+        let request = query_params(&[
+            ("lang", "SQ"),
+            ("country", "al"),
+            ("printType", "books"),
+            ("projection", "lite"),
+        ]);
+        let options = query_options(&request).expect("could not retrieve options");
+
+        assert_eq!(
+            QueryOptions {
+                lang_restrict: Some(String::from("sq")),
+                country: Some(String::from("AL")),
+                print_type: Some(PrintType::Books),
+                projection: Some(Projection::Lite),
+            },
+            options
+        );
+    }
+
+    #[test]
+    fn query_options_without_query_parameters() {
+        let request = query_params(&[("type", "googlebooks"), ("isbn", "9781591026419")]);
+
+        assert_eq!(
+            QueryOptions::default(),
+            query_options(&request).expect("could not retrieve options")
+        );
+    }
+
+    #[test]
+    fn query_options_invalid() {
+        for (param, value) in [
+            ("lang", "albanian"),
+            ("country", "ALB"),
+            ("printType", "ebooks"),
+            ("projection", "compact"),
+        ] {
+            let request = query_params(&[(param, value)]);
+            match query_options(&request) {
+                Err(ResponseError::InvalidParameter(message)) => {
+                    assert_eq!(format!("Invalid {}", param), message)
+                }
+                other => panic!("invalid {} expected, got {:?}", param, other),
+            }
+        }
+    }
+
+    #[test]
+    fn editions_request_from_path() {
+        for (path, expected) in [
+            ("/books/editions", true),
+            ("/books/editions/", true),
+            ("/books", false),
+            ("/books/editions-list", false),
+        ] {
+            let request = TestRequest {
+                path: String::from(path),
+                ..query_params(&[])
+            };
+            assert_eq!(expected, is_editions_request(&request), "path {}", path);
+        }
+    }
+
+    #[test]
+    fn edition_languages_from_query_parameter() {
+        let request = query_params(&[("lang", "SQ, en")]);
+        assert_eq!(
+            vec![String::from("sq"), String::from("en")],
+            edition_languages(&request).expect("could not retrieve languages")
+        );
+    }
+
+    #[test]
+    fn default_edition_languages() {
+        let request = query_params(&[]);
+        assert_eq!(
+            vec![String::from("sq"), String::from("en")],
+            edition_languages(&request).expect("could not retrieve languages")
+        );
+    }
+
+    #[test]
+    fn edition_languages_invalid() {
+        for languages in ["", "sq,xx", "albanian"] {
+            let request = query_params(&[("lang", languages)]);
+            assert!(
+                matches!(
+                    edition_languages(&request),
+                    Err(ResponseError::InvalidParameter(message)) if message == "Invalid lang"
+                ),
+                "invalid lang expected for {:?}",
+                languages
+            );
+        }
+    }
+
+    #[test]
+    fn response_from_editions() {
+        let edition = Edition {
+            key: String::from("/books/OL32101577M"),
+            title: String::from("Tehu"),
+            languages: vec![String::from("alb")],
+            publishers: vec![String::from("Dudaj")],
+            publish_date: String::from("2019"),
+            publish_year: Some(2019),
+            isbns: vec![Isbn::parse("9789928221841").expect("invalid isbn")],
+        };
+        let book_editions = BookEditions {
+            work_key: String::from("/works/OL8400950W"),
+            languages: vec![
+                LanguageEditions {
+                    language: String::from("sq"),
+                    editions: vec![edition.clone()],
+                },
+                LanguageEditions {
+                    language: String::from("en"),
+                    editions: Vec::new(),
+                },
+            ],
+        };
+        let response = editions_response(&book_editions).expect("response expected to be present");
+        let body = String::from_utf8(response.body().to_vec()).expect("utf8 string expected");
+        let editions: EditionsResponse =
+            serde_json::from_str(&body).expect("response expected to be parsed");
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!("/works/OL8400950W", editions.work_key);
+        assert_eq!("sq", editions.languages[0].language);
+        assert!(editions.languages[0].available);
+        assert_eq!(vec![edition], editions.languages[0].editions);
+        assert_eq!("en", editions.languages[1].language);
+        assert!(!editions.languages[1].available);
+    }
 }
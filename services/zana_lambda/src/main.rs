@@ -1,16 +1,74 @@
 extern crate core;
 
 use std::env;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
 
 use lambda_http::{run, service_fn, Body, Error, Request, Response};
-use zana::{googlebooks, openlibrary};
+use tokio::sync::OnceCell;
+use zana::cache::{CachePolicy, CachingClient, MemoryStore};
+use zana::circuit::{CircuitBreaker, CircuitPolicy};
+use zana::googlebooks::QueryOptions;
+use zana::retry::{RetryPolicy, RetryingClient};
+use zana::{googlebooks, openlibrary, BookClient};
 
-use zana_lambda::book::Client;
+use zana_lambda::book::{BookApiClient, Client, FetchOptions};
 use zana_lambda::http;
-use zana_lambda::http::{failure_response, success_response, RequestType, ResponseError};
+use zana_lambda::http::{
+    editions_response, failure_response, success_response, RequestType, ResponseError,
+};
 use zana_lambda::params::{AWSParamStore, ParamStore};
 
-async fn function_handler(event: Request) -> Result<Response<Body>, Error> {
+/// Timeouts of a single request to a provider, kept well below the API Gateway timeout
+/// of 29 seconds, since a lookup can make several requests.
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(5);
+const PROVIDER_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Retries are limited so that a lookup fits in the API Gateway timeout of 29 seconds.
+fn retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        max_retry_after: Duration::from_secs(2),
+        max_elapsed_time: Some(Duration::from_secs(10)),
+        ..RetryPolicy::default()
+    }
+}
+
+/// Clients are created once per container, so that warm invocations share the cached books.
+static CLIENT: OnceCell<Client> = OnceCell::const_new();
+
+/// Requests to a provider that keeps failing are rejected right away, instead of waiting
+/// for its timeouts.
+fn circuit_policy() -> CircuitPolicy {
+    CircuitPolicy {
+        failure_threshold: 3,
+        cool_down: Duration::from_secs(30),
+        ..CircuitPolicy::default()
+    }
+}
+
+/// Results are cached for a short time, since data of the providers can change.
+fn cache_policy() -> CachePolicy {
+    CachePolicy {
+        capacity: NonZeroUsize::new(500).expect("capacity is not zero"),
+        ttl: Duration::from_secs(15 * 60),
+        not_found_ttl: Some(Duration::from_secs(60)),
+    }
+}
+
+/// Returns the client wrapped with caching, circuit breaking and retries.
+fn wrap_client<C: BookClient + Send + Sync + 'static>(client: C) -> Box<BookApiClient> {
+    Box::new(CachingClient::new(
+        CircuitBreaker::new(
+            RetryingClient::new(client, retry_policy()),
+            circuit_policy(),
+        ),
+        cache_policy(),
+    ))
+}
+
+async fn create_client() -> Result<Client, Error> {
     // Required env variables
     let zana_env = env::var("ZANA_ENV").expect("environment variable 'ZANA_ENV' not set");
 
@@ -24,14 +82,6 @@ async fn function_handler(event: Request) -> Result<Response<Body>, Error> {
         parameter_store_port
     );
 
-    let request_type: RequestType = match http::request_type(&event) {
-        Ok(request_type) => request_type,
-        Err(err) => return failure_response(err),
-    };
-    let isbn = http::query_parameter(&event, "isbn", "");
-    let author = http::query_parameter(&event, "author", "");
-    let title = http::query_parameter(&event, "title", "");
-
     let param_store = AWSParamStore::new(&parameter_store_url, &aws_token);
 
     let googlebooks_url = param_store
@@ -56,30 +106,125 @@ async fn function_handler(event: Request) -> Result<Response<Body>, Error> {
         )
         .await?;
 
-    let googlebooks_client = match googlebooks::Client::new(&googlebooks_key, &googlebooks_url) {
-        Ok(client) => Box::new(client),
-        Err(err) => return failure_response(ResponseError::BookClientError(err)),
+    let googlebooks_client = googlebooks::Client::builder(&googlebooks_key, &googlebooks_url)
+        .timeout(PROVIDER_TIMEOUT)
+        .connect_timeout(PROVIDER_CONNECT_TIMEOUT)
+        .user_agent_suffix("zana-lambda")
+        .build()
+        .map_err(ResponseError::BookClientError)?;
+    let openlibrary_client = openlibrary::Client::builder(&openlibrary_url)
+        .timeout(PROVIDER_TIMEOUT)
+        .connect_timeout(PROVIDER_CONNECT_TIMEOUT)
+        .user_agent_suffix("zana-lambda")
+        .build()
+        .map_err(ResponseError::BookClientError)?;
+
+    // Clients of all query options share the circuit breaker and the cache, with results
+    // cached per options, since they vary by language and country
+    let googlebooks_store = Arc::new(MemoryStore::new(cache_policy().capacity));
+    let googlebooks_circuit = Arc::new(CircuitBreaker::new(
+        RetryingClient::new(googlebooks_client, retry_policy()),
+        circuit_policy(),
+    ));
+    let googlebooks_default = CachingClient::with_store(
+        Arc::clone(&googlebooks_circuit),
+        "googlebooks",
+        Arc::clone(&googlebooks_store),
+        cache_policy(),
+    );
+    let googlebooks_factory = move |options: &QueryOptions| -> Box<BookApiClient> {
+        let googlebooks_client = googlebooks_circuit.inner().inner().with_options(options);
+        Box::new(CachingClient::with_store(
+            googlebooks_circuit.shared(RetryingClient::new(googlebooks_client, retry_policy())),
+            &format!("googlebooks?{}", options),
+            Arc::clone(&googlebooks_store),
+            cache_policy(),
+        ))
+    };
+
+    // Shared with the editions lookup, which is made with the client directly
+    let openlibrary_client = Arc::new(openlibrary_client);
+
+    Ok(Client::new(
+        Box::new(googlebooks_default),
+        wrap_client(Arc::clone(&openlibrary_client)),
+    )
+    .with_googlebooks_factory(Box::new(googlebooks_factory))
+    .with_editions_client(Box::new(openlibrary_client)))
+}
+
+async fn editions_handler(event: Request) -> Result<Response<Body>, Error> {
+    let isbn = http::query_parameter(&event, "isbn", "");
+    let languages = match http::edition_languages(&event) {
+        Ok(languages) => languages,
+        Err(err) => return failure_response(err),
     };
 
-    let openlibrary_client = match openlibrary::Client::new(&openlibrary_url) {
-        Ok(client) => Box::new(client),
-        Err(err) => return failure_response(ResponseError::BookClientError(err)),
+    let client = CLIENT.get_or_try_init(create_client).await?;
+    tracing::info!(
+        "sending request to fetch editions (isbn: {}, languages: {:?})",
+        &isbn,
+        &languages,
+    );
+
+    match client.fetch_editions(&isbn, &languages).await {
+        Ok(book_editions) => editions_response(&book_editions),
+        Err(err) => {
+            tracing::error!(
+                "could not fetch editions (isbn: {}, languages: {:?}), {:?}",
+                &isbn,
+                &languages,
+                err,
+            );
+            failure_response(err)
+        }
+    }
+}
+
+async fn function_handler(event: Request) -> Result<Response<Body>, Error> {
+    if http::is_editions_request(&event) {
+        return editions_handler(event).await;
+    }
+    let request_type: RequestType = match http::request_type(&event) {
+        Ok(request_type) => request_type,
+        Err(err) => return failure_response(err),
+    };
+    let isbn = http::query_parameter(&event, "isbn", "");
+    let author = http::query_parameter(&event, "author", "");
+    let title = http::query_parameter(&event, "title", "");
+    let query_options = match http::query_options(&event) {
+        Ok(query_options) => query_options,
+        Err(err) => return failure_response(err),
+    };
+    let aggregate_rating = match http::aggregate_rating(&event) {
+        Ok(aggregate_rating) => aggregate_rating,
+        Err(err) => return failure_response(err),
+    };
+    let fallback = match http::fallback(&event) {
+        Ok(fallback) => fallback,
+        Err(err) => return failure_response(err),
+    };
+    let options = FetchOptions {
+        query_options,
+        aggregate_rating,
+        fallback,
     };
 
-    let client = Client::new(googlebooks_client, openlibrary_client);
+    let client = CLIENT.get_or_try_init(create_client).await?;
     tracing::info!(
-        "sending request to fetch book data for {:?} (isbn: {}, author: {}, title: {})",
+        "sending request to fetch book data for {:?} (isbn: {}, author: {}, title: {}, options: {:?})",
         &request_type,
         &isbn,
         &author,
         &title,
+        &options,
     );
 
     let book = client
-        .fetch_book(&request_type, &isbn, &title, &author)
+        .fetch_book_with_rating(&request_type, &isbn, &title, &author, &options)
         .await;
     match book {
-        Ok(book) => success_response(&book),
+        Ok(rated_book) => success_response(&rated_book),
         Err(err) => {
             tracing::error!(
                 "could not fetch book for {:?} (isbn: {}, author: {}, title: {}), {:?}",
@@ -0,0 +1,70 @@
+const fs = require('fs');
+const { XMLParser } = require('fast-xml-parser');
+
+// Range data published by the International ISBN Agency
+const RANGE_MESSAGE_URL = 'https://www.isbn-international.org/export_rangemessage.xml';
+const RANGES_FILE = '../../services/zana/src/isbn/ranges.txt';
+
+const HEADER = `# ISBN ranges of the International ISBN Agency (https://www.isbn-international.org/range_file_generation)
+#
+# Generated by tools/isbn_ranges from RangeMessage.xml, do not edit manually.
+#
+# Each \`prefix\` or \`group\` line is followed by its rules. A rule contains a range of the 7 digits
+# that follow the prefix or group, and the length of the next element (registration group or registrant)
+# for ISBNs within that range. A length of 0 means the range is not in use.`;
+
+function toArray(value) {
+    if (value === undefined) {
+        return [];
+    }
+    return Array.isArray(value) ? value : [value];
+}
+
+function rulesToLines(rules) {
+    return toArray(rules && rules.Rule)
+        .map(rule => `${rule.Range}\t${rule.Length}`);
+}
+
+function groupsToLines(kind, groups) {
+    return toArray(groups).flatMap(group => [
+        `${kind}\t${group.Prefix}\t${group.Agency}`,
+        ...rulesToLines(group.Rules),
+    ]);
+}
+
+function generateRanges(rangeMessageXml) {
+    const parser = new XMLParser({ parseTagValue: false });
+    const message = parser.parse(rangeMessageXml).ISBNRangeMessage;
+
+    const lines = [
+        HEADER,
+        `# Source: ${message.MessageSource}, serial number ${message.MessageSerialNumber}, ${message.MessageDate}`,
+        ...groupsToLines('prefix', message['EAN.UCCPrefixes']['EAN.UCC']),
+        ...groupsToLines('group', message.RegistrationGroups.Group),
+    ];
+    return lines.join('\n') + '\n';
+}
+
+async function readRangeMessage(filename) {
+    if (filename) {
+        return fs.readFileSync(filename, 'utf8');
+    }
+    const response = await fetch(RANGE_MESSAGE_URL);
+    if (!response.ok) {
+        throw new Error(`could not download range message, status ${response.status}`);
+    }
+    return response.text();
+}
+
+// An already downloaded RangeMessage.xml can be passed as the first argument
+const [,, filename] = process.argv;
+
+readRangeMessage(filename)
+    .then(rangeMessageXml => {
+        fs.writeFileSync(RANGES_FILE, generateRanges(rangeMessageXml), 'utf8');
+        console.log(`ISBN ranges written to ${RANGES_FILE}`);
+    })
+    .catch(err => {
+        console.error(err);
+        process.exit(1);
+    });